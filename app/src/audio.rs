@@ -46,6 +46,7 @@ impl AudioTable {
             Audio::SoundEffect(SoundEffect::DoorOpen) => audio_player.load_sound(DOOR_OPEN),
             Audio::SoundEffect(SoundEffect::Heal) => audio_player.load_sound(HEAL),
             Audio::SoundEffect(SoundEffect::Die) => audio_player.load_sound(DIE),
+            Audio::SoundEffect(SoundEffect::WindowShatter) => audio_player.load_sound(PUNCH),
         ]
         });
         Self { map }
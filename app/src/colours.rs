@@ -9,8 +9,10 @@ pub const SPACE_FOREGROUND: Rgba32 = Rgba32::new_grey(0xAA);
 pub const SPACE_FOREGROUND_DIM: Rgba32 = Rgba32::new_grey(0x66);
 pub const WINDOWS: Rgba32 = Rgba32::new_rgb(0xBE, 0xED, 0xFF);
 pub const STRIPE: Rgba32 = Rgba32::new_rgb(0xFF, 0xBE, 0x4C);
+pub const CRACK: Rgba32 = Rgba32::new_rgb(0x1A, 0x10, 0x00);
 pub const DOOR: Rgba32 = Rgba32::new_rgb(0x88, 0x88, 0x88);
 pub const DOOR_BORDER: Rgba32 = Rgba32::new_grey(0x33);
+pub const DOOR_LOCK: Rgba32 = Rgba32::new_rgb(0xFF, 0x20, 0x20);
 pub const STAIRS_BACKGROUND: Rgba32 = Rgba32::new_grey(0x33);
 pub const STAIRS_0: Rgba32 = Rgba32::new_grey(0xAA);
 pub const STAIRS_1: Rgba32 = Rgba32::new_grey(0x88);
@@ -20,7 +22,16 @@ pub const ZOMBIE: Rgba32 = Rgba32::new_rgb(0x3F, 0x3E, 0x0B);
 pub const TANK: Rgba32 = Rgba32::new_rgb(0x51, 0x0C, 0x03);
 pub const SKELETON: Rgba32 = Rgba32::new_rgb(0x03, 0x51, 0x45);
 pub const BOOMER: Rgba32 = Rgba32::new_rgb(0x17, 0x80, 0x14);
+pub const DRONE: Rgba32 = Rgba32::new_rgb(0x1C, 0x2C, 0x80);
+pub const GUARDIAN: Rgba32 = Rgba32::new_rgb(0x2A, 0x2A, 0x2A);
+pub const SURVIVOR: Rgba32 = Rgba32::new_rgb(0x1F, 0x5F, 0xBF);
+pub const GUARDIAN_WEAK_POINT: Rgba32 = Rgba32::new_rgb(0x00, 0xFF, 0x40);
 pub const BLOOD: Rgba32 = Rgba32::new_rgb(0xFF, 0x00, 0x22);
+pub const BURNING: Rgba32 = Rgba32::new_rgb(0xFF, 0x88, 0x00);
+pub const STUNNED: Rgba32 = Rgba32::new_rgb(0xFF, 0xFF, 0x44);
+pub const SLOWED: Rgba32 = Rgba32::new_rgb(0x44, 0xAA, 0xFF);
+pub const SHOT_PREVIEW: Rgba32 = Rgba32::new(255, 255, 0, 32);
+pub const SHOT_PREVIEW_BREACH: Rgba32 = Rgba32::new(255, 0, 0, 128);
 pub const FUEL_BAY_BACKGROUND: Rgba32 = Rgba32::new_grey(0x44);
 pub const FUEL_BAY_FOREGROUND: Rgba32 = Rgba32::new_rgb(0, 0, 255);
 pub const BULLET: Rgba32 = Rgba32::new_grey(0);
@@ -31,6 +42,9 @@ pub const GUN_METAL: Rgba32 = Rgba32::new_grey(0x11);
 pub const WOOD: Rgba32 = Rgba32::new_rgb(0xab, 0x40, 0x0a);
 pub const PLASMA: Rgba32 = Rgba32::new_rgb(0x00, 0xFF, 0xFF);
 pub const CHAINSAW: Rgba32 = Rgba32::new_rgb(0x7a, 0x6a, 0x00);
+pub const CROWBAR: Rgba32 = Rgba32::new_rgb(0xC0, 0x10, 0x10);
+pub const SHOCK_BATON: Rgba32 = Rgba32::new_rgb(0x40, 0xA0, 0xFF);
+pub const FIRE_AXE: Rgba32 = Rgba32::new_rgb(0xE0, 0x20, 0x00);
 pub const LASER: Rgba32 = Rgba32::new_rgb(0, 255, 0);
 pub const GAUS: Rgba32 = Rgba32::new_rgb(127, 0, 255);
 pub const OXYGEN: Rgba32 = Rgba32::new_rgb(127, 127, 255);
@@ -39,3 +53,21 @@ pub const MEDKIT: Rgba32 = Rgba32::new_grey(200);
 pub const MEDKIT_TOP: Rgba32 = Rgba32::new_grey(150);
 pub const MAP_FOREGROUND: Rgba32 = Rgba32::new_rgb(0, 63, 0);
 pub const MAP_BACKGROUND: Rgba32 = Rgba32::new_rgb(0, 255, 0);
+pub const MAGNETIC_BOOTS: Rgba32 = Rgba32::new_rgb(0xFF, 0x44, 0x00);
+pub const KEYCARD: Rgba32 = Rgba32::new_rgb(0xFF, 0xD0, 0x20);
+pub const FLASHLIGHT: Rgba32 = Rgba32::new_rgb(0xFF, 0xFA, 0xDC);
+pub const WALL_LIGHT: Rgba32 = Rgba32::new_rgb(0xFF, 0xFF, 0xC0);
+pub const WALL_LIGHT_BROKEN: Rgba32 = Rgba32::new_grey(0x33);
+pub const REPAIR_KIT: Rgba32 = Rgba32::new_rgb(0xE0, 0xB0, 0x00);
+pub const AMMO_SHELLS: Rgba32 = Rgba32::new_rgb(0xD0, 0x30, 0x10);
+pub const AMMO_BULLETS: Rgba32 = Rgba32::new_rgb(0xC0, 0x90, 0x30);
+pub const AMMO_SLUGS: Rgba32 = Rgba32::new_rgb(0x00, 0xC0, 0xC0);
+pub const AMMO_CELLS: Rgba32 = Rgba32::new_rgb(0x60, 0xFF, 0x60);
+pub const VENDOR_BACKGROUND: Rgba32 = Rgba32::new_rgb(0x00, 0x1A, 0x1A);
+pub const TERMINAL_FOREGROUND: Rgba32 = Rgba32::new_rgb(0xFF, 0xA0, 0x00);
+pub const TERMINAL_BACKGROUND: Rgba32 = Rgba32::new_rgb(0x1A, 0x10, 0x00);
+pub const WEAPON_MOD: Rgba32 = Rgba32::new_rgb(0xFF, 0xD7, 0x00);
+pub const WORKBENCH_TOP: Rgba32 = Rgba32::new_rgb(0x8A, 0x5A, 0x2B);
+pub const WORKBENCH_LEGS: Rgba32 = Rgba32::new_grey(0x55);
+pub const GRENADE: Rgba32 = Rgba32::new_rgb(0x4B, 0x5A, 0x1E);
+pub const GRENADE_FUSE: Rgba32 = Rgba32::new_rgb(0xFF, 0x22, 0x00);
@@ -1,19 +1,24 @@
 use gridbugs::{
     chargrid::input::{GamepadButton, Input, KeyboardInput},
-    direction::CardinalDirection,
+    direction::Direction,
 };
 use maplit::btreemap;
 use orbital_decay_game::player::RangedWeaponSlot;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{btree_map, BTreeMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AppInput {
-    Direction(CardinalDirection),
+    Direction(Direction),
     Slot(RangedWeaponSlot),
     Wait,
     Examine,
     Get,
+    Throw,
+    Inventory,
+    Reload,
+    Shove,
+    Flashlight,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,41 +30,84 @@ pub struct Controls {
 impl Default for Controls {
     fn default() -> Self {
         let keys = btreemap![
-            KeyboardInput::Left => AppInput::Direction(CardinalDirection::West),
-            KeyboardInput::Right => AppInput::Direction(CardinalDirection::East),
-            KeyboardInput::Up => AppInput::Direction(CardinalDirection::North),
-            KeyboardInput::Down => AppInput::Direction(CardinalDirection::South),
-            KeyboardInput::Char('a') => AppInput::Direction(CardinalDirection::West),
-            KeyboardInput::Char('d') => AppInput::Direction(CardinalDirection::East),
-            KeyboardInput::Char('w') => AppInput::Direction(CardinalDirection::North),
-            KeyboardInput::Char('s') => AppInput::Direction(CardinalDirection::South),
-            KeyboardInput::Char('h') => AppInput::Direction(CardinalDirection::West),
-            KeyboardInput::Char('l') => AppInput::Direction(CardinalDirection::East),
-            KeyboardInput::Char('k') => AppInput::Direction(CardinalDirection::North),
-            KeyboardInput::Char('j') => AppInput::Direction(CardinalDirection::South),
+            KeyboardInput::Left => AppInput::Direction(Direction::West),
+            KeyboardInput::Right => AppInput::Direction(Direction::East),
+            KeyboardInput::Up => AppInput::Direction(Direction::North),
+            KeyboardInput::Down => AppInput::Direction(Direction::South),
+            KeyboardInput::Char('a') => AppInput::Direction(Direction::West),
+            KeyboardInput::Char('d') => AppInput::Direction(Direction::East),
+            KeyboardInput::Char('w') => AppInput::Direction(Direction::North),
+            KeyboardInput::Char('s') => AppInput::Direction(Direction::South),
+            KeyboardInput::Char('h') => AppInput::Direction(Direction::West),
+            KeyboardInput::Char('l') => AppInput::Direction(Direction::East),
+            KeyboardInput::Char('k') => AppInput::Direction(Direction::North),
+            KeyboardInput::Char('j') => AppInput::Direction(Direction::South),
+            KeyboardInput::Char('y') => AppInput::Direction(Direction::NorthWest),
+            KeyboardInput::Char('u') => AppInput::Direction(Direction::NorthEast),
+            KeyboardInput::Char('b') => AppInput::Direction(Direction::SouthWest),
+            KeyboardInput::Char('n') => AppInput::Direction(Direction::SouthEast),
+            // the diagonal keys on a numpad with num lock off
+            KeyboardInput::Home => AppInput::Direction(Direction::NorthWest),
+            KeyboardInput::PageUp => AppInput::Direction(Direction::NorthEast),
+            KeyboardInput::End => AppInput::Direction(Direction::SouthWest),
+            KeyboardInput::PageDown => AppInput::Direction(Direction::SouthEast),
+            // the numpad with num lock on, apart from its bottom row as 1-3 choose weapon slots
+            KeyboardInput::Char('4') => AppInput::Direction(Direction::West),
+            KeyboardInput::Char('6') => AppInput::Direction(Direction::East),
+            KeyboardInput::Char('8') => AppInput::Direction(Direction::North),
+            KeyboardInput::Char('7') => AppInput::Direction(Direction::NorthWest),
+            KeyboardInput::Char('9') => AppInput::Direction(Direction::NorthEast),
+            KeyboardInput::Char('5') => AppInput::Wait,
             KeyboardInput::Char('x') => AppInput::Examine,
             KeyboardInput::Char('1') => AppInput::Slot(RangedWeaponSlot::Slot1),
             KeyboardInput::Char('2') => AppInput::Slot(RangedWeaponSlot::Slot2),
             KeyboardInput::Char('3') => AppInput::Slot(RangedWeaponSlot::Slot3),
             KeyboardInput::Char('g') => AppInput::Get,
+            KeyboardInput::Char('t') => AppInput::Throw,
+            KeyboardInput::Char('i') => AppInput::Inventory,
+            KeyboardInput::Char('r') => AppInput::Reload,
+            KeyboardInput::Char('f') => AppInput::Shove,
+            KeyboardInput::Char('e') => AppInput::Flashlight,
             KeyboardInput::Char(' ') => AppInput::Wait,
         ];
         let gamepad = btreemap![
-            GamepadButton::DPadLeft => AppInput::Direction(CardinalDirection::West),
-            GamepadButton::DPadRight => AppInput::Direction(CardinalDirection::East),
-            GamepadButton::DPadUp => AppInput::Direction(CardinalDirection::North),
-            GamepadButton::DPadDown => AppInput::Direction(CardinalDirection::South),
+            GamepadButton::DPadLeft => AppInput::Direction(Direction::West),
+            GamepadButton::DPadRight => AppInput::Direction(Direction::East),
+            GamepadButton::DPadUp => AppInput::Direction(Direction::North),
+            GamepadButton::DPadDown => AppInput::Direction(Direction::South),
             GamepadButton::Select => AppInput::Wait,
             GamepadButton::North => AppInput::Get,
             GamepadButton::West => AppInput::Slot(RangedWeaponSlot::Slot1),
             GamepadButton::South => AppInput::Slot(RangedWeaponSlot::Slot2),
             GamepadButton::East => AppInput::Slot(RangedWeaponSlot::Slot3),
             GamepadButton::RightBumper => AppInput::Examine,
+            GamepadButton::LeftBumper => AppInput::Throw,
         ];
         Self { keys, gamepad }
     }
 }
 impl Controls {
+    /// Binds any keys and buttons from the default controls that aren't bound to anything, so
+    /// controls saved by an older version pick up bindings for new actions. Returns whether any
+    /// bindings were added.
+    pub fn add_missing_defaults(&mut self) -> bool {
+        let Self { keys, gamepad } = Self::default();
+        let mut changed = false;
+        for (key, app_input) in keys {
+            if let btree_map::Entry::Vacant(entry) = self.keys.entry(key) {
+                entry.insert(app_input);
+                changed = true;
+            }
+        }
+        for (button, app_input) in gamepad {
+            if let btree_map::Entry::Vacant(entry) = self.gamepad.entry(button) {
+                entry.insert(app_input);
+                changed = true;
+            }
+        }
+        changed
+    }
+
     pub fn get(&self, input: Input) -> Option<AppInput> {
         match input {
             Input::Keyboard(keyboard_input) => self.keys.get(&keyboard_input).cloned(),
@@ -68,7 +116,7 @@ impl Controls {
         }
     }
 
-    pub fn get_direction(&self, input: Input) -> Option<CardinalDirection> {
+    pub fn get_direction(&self, input: Input) -> Option<Direction> {
         self.get(input).and_then(|app_input| match app_input {
             AppInput::Direction(direction) => Some(direction),
             _ => None,
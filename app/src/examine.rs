@@ -1,5 +1,6 @@
+use crate::ui;
 use gridbugs::chargrid::{prelude::*, text::StyledString};
-use orbital_decay_game::{CellVisibility, Game, Tile};
+use orbital_decay_game::{AmmoType, CellVisibility, Game, Mass, Tile};
 
 #[derive(Clone, Copy, Debug)]
 enum MessageVerb {
@@ -10,6 +11,8 @@ enum MessageVerb {
 pub fn examine(game: &Game, coord: Coord) -> Option<StyledString> {
     let vis_count = game.visibility_grid().count();
     let mut entity_under_cursor = None;
+    let mut vacuum_note = None;
+    let mut status_note = None;
     if let Some(visibility_cell_under_cursor) = game.visibility_grid().get_cell(coord) {
         let verb = match visibility_cell_under_cursor.visibility(vis_count) {
             CellVisibility::CurrentlyVisibleWithLightColour(Some(_)) => Some(MessageVerb::See),
@@ -26,6 +29,14 @@ pub fn examine(game: &Game, coord: Coord) -> Option<StyledString> {
             }
             if let Some(character) = visibility_cell_under_cursor.tile_layers().character {
                 entity_under_cursor = Some((character.tile, verb));
+                if let MessageVerb::See = verb {
+                    if game.is_character_adrift(coord) {
+                        vacuum_note = Some("It's drifting away into space.");
+                    } else {
+                        vacuum_note = vacuum_str(game, coord, character.tile);
+                    }
+                    status_note = status_string(game, coord, character.tile);
+                }
             }
             if let Some(item) = visibility_cell_under_cursor.tile_layers().item {
                 entity_under_cursor = Some((item.tile, verb));
@@ -39,13 +50,57 @@ pub fn examine(game: &Game, coord: Coord) -> Option<StyledString> {
                     MessageVerb::Remember => "remember seeing",
                     MessageVerb::See => "see",
                 };
-                StyledString::plain_text(format!("You {} {} here.", verb_str, name))
+                let mut string = format!("You {} {} here.", verb_str, name);
+                if let Some(note) = vacuum_note {
+                    string.push(' ');
+                    string.push_str(note);
+                }
+                if let Some(note) = status_note.as_ref() {
+                    string.push(' ');
+                    string.push_str(note);
+                }
+                StyledString::plain_text(string)
             }
             TileLabel::Literal(literal) => StyledString::plain_text(literal.to_string()),
         })
     })
 }
 
+/// Describes how a character will react to a hull breach, so the player can predict who will be
+/// sucked out
+fn vacuum_str(game: &Game, coord: Coord, tile: Tile) -> Option<&'static str> {
+    if game.is_character_anchored(coord) {
+        return Some(if tile == Tile::Player {
+            "You can hold onto the door if the hull is breached."
+        } else {
+            "It's close enough to a door to hold on if the hull is breached."
+        });
+    }
+    match game.character_mass(coord)? {
+        Mass::Light => Some("It's light enough to be sucked out quickly."),
+        Mass::Heavy => Some("It's heavy enough to resist all but the strongest decompression."),
+        Mass::Normal => None,
+    }
+}
+
+/// Lists the status effects currently affecting a character
+fn status_string(game: &Game, coord: Coord, tile: Tile) -> Option<String> {
+    let names = game
+        .character_status_effects(coord)?
+        .iter()
+        .map(|active| ui::status_effect_name(active.effect).to_lowercase())
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return None;
+    }
+    let subject = if tile == Tile::Player {
+        "You are"
+    } else {
+        "It is"
+    };
+    Some(format!("{} {}.", subject, names.join(", ")))
+}
+
 enum TileLabel {
     Literal(&'static str),
     Name(&'static str),
@@ -55,28 +110,86 @@ fn tile_str(tile: Tile) -> Option<TileLabel> {
     let label = match tile {
         Tile::Player => TileLabel::Name("yourself"),
         Tile::DoorClosed(_) | Tile::DoorOpen(_) => TileLabel::Name("a door"),
+        Tile::DoorLocked(_) => TileLabel::Literal(
+            "A locked vault door - open it with a keycard, or keep walking into it to hack the lock.",
+        ),
         Tile::Wall | Tile::WallText0 | Tile::WallText1 | Tile::WallText2 | Tile::WallText3 => {
             TileLabel::Name("a wall")
         }
         Tile::Floor | Tile::FuelText0 | Tile::FuelText1 => TileLabel::Name("the floor"),
         Tile::FuelHatch => TileLabel::Name("the fuel bay"),
         Tile::Window(_) => TileLabel::Name("a window"),
+        Tile::WallCracked
+        | Tile::WallText0Cracked
+        | Tile::WallText1Cracked
+        | Tile::WallText2Cracked
+        | Tile::WallText3Cracked => TileLabel::Name("a cracked wall"),
+        Tile::WallLight => TileLabel::Literal(
+            "A wall light - it goes dark if the station loses power, or if you shoot it out.",
+        ),
+        Tile::WallLightBroken => TileLabel::Name("a broken wall light"),
+        Tile::WindowCracked(_) => TileLabel::Name("a cracked window"),
         Tile::Stairs => TileLabel::Name("a staircase leading further down"),
         Tile::Zombie => TileLabel::Name("a zombie"),
         Tile::Skeleton => TileLabel::Name("a skeleton"),
         Tile::SkeletonRespawn => TileLabel::Name("a twitching pile of bones"),
         Tile::Boomer => TileLabel::Name("a boomer"),
         Tile::Tank => TileLabel::Name("a tank"),
+        Tile::Drone => TileLabel::Name("a security drone"),
+        Tile::Guardian(_) => TileLabel::Name("the reactor guardian"),
+        Tile::Survivor => TileLabel::Literal(
+            "A stranded crew member - keep them close when you take the stairs and they'll pay you for the rescue.",
+        ),
         Tile::Bullet => return None,
         Tile::Credit1 => TileLabel::Name("a $1 credit chip"),
         Tile::Credit2 => TileLabel::Name("a $2 credit chip"),
         Tile::Upgrade => TileLabel::Name("an upgrade store"),
+        Tile::Vendor => TileLabel::Literal(
+            "A vendor terminal - spend credit here on weapons, supplies and ammo.",
+        ),
+        Tile::Workbench => TileLabel::Literal(
+            "A workbench - spend credit here to fit mods to the weapons you're carrying.",
+        ),
+        Tile::Terminal => TileLabel::Literal(
+            "A security terminal - runs one command before it shuts down for good.",
+        ),
+        Tile::TerminalOffline => TileLabel::Name("a dead security terminal"),
         Tile::Map => TileLabel::Name("a map terminal"),
         Tile::MapLocked => TileLabel::Name("a locked map terminal"),
         Tile::Medkit => TileLabel::Name("a medkit"),
+        Tile::OxygenCanister => TileLabel::Literal(
+            "An oxygen canister - refills your oxygen wherever you are.",
+        ),
+        Tile::RepairKit => TileLabel::Literal(
+            "A repair kit - fixes damaged walls, doors and windows next to you.",
+        ),
+        Tile::Ammo(AmmoType::Shells) => TileLabel::Name("a box of shotgun shells"),
+        Tile::Ammo(AmmoType::Bullets) => TileLabel::Name("a box of rifle bullets"),
+        Tile::Ammo(AmmoType::Slugs) => TileLabel::Name("a box of railgun slugs"),
+        Tile::Ammo(AmmoType::Cells) => TileLabel::Name("a pack of energy cells"),
+        Tile::Grenade => TileLabel::Literal(
+            "A grenade - its blast is strong enough to blow a hole in the hull.",
+        ),
+        Tile::LiveGrenade => TileLabel::Name("a live grenade"),
+        Tile::MagneticBoots => TileLabel::Literal(
+            "Magnetic boots - lets you walk on the outside of the hull. Bring plenty of oxygen!",
+        ),
+        Tile::Keycard => TileLabel::Literal("A keycard - opens one locked vault door."),
+        Tile::Flashlight => TileLabel::Literal(
+            "A flashlight - lights the way ahead when the power is out. Its battery recharges on each floor.",
+        ),
         Tile::Chainsaw => {
             TileLabel::Literal("A chainsaw - melee weapon with high DMG and limited uses.")
         }
+        Tile::Crowbar => {
+            TileLabel::Literal("A crowbar - never wears out, and wedges open the doors you open.")
+        }
+        Tile::ShockBaton => {
+            TileLabel::Literal("A shock baton - stuns whatever it hits until the charge runs out.")
+        }
+        Tile::FireAxe => TileLabel::Literal(
+            "A fire axe - walk into a wall or window to hack through it. Mind the vacuum!",
+        ),
         Tile::Shotgun => TileLabel::Literal("A shotgun - high DMG, low PEN."),
         Tile::Railgun => TileLabel::Literal("A railgun - it can shoot through almost anything!"),
         Tile::Rifle => TileLabel::Literal("A rifle - general all-rounder. Boring."),
@@ -70,6 +70,19 @@ pub fn render_game_with_visibility(game: &Game, ctx: Ctx, fb: &mut FrameBuffer)
             | CellVisibility::CurrentlyVisibleWithLightColour(None) => (),
         }
     }
+    for entity in game.revealed_enemies() {
+        if !game
+            .visibility_grid()
+            .is_coord_currently_visible(entity.coord)
+        {
+            tile_3x3::render_3x3(
+                &entity,
+                game,
+                ctx_tint!(ctx, Remembered).add_depth(layer_depth(entity.layer)),
+                fb,
+            );
+        }
+    }
     for entity in game.to_render_entities_realtime() {
         match game.visibility_grid().cell_visibility(entity.coord) {
             CellVisibility::CurrentlyVisibleWithLightColour(Some(light_colour)) => {
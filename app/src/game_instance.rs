@@ -97,6 +97,9 @@ impl GameInstance {
         let player = self.game.inner_ref().player();
         let player_info = self.game.inner_ref().player_info();
         ui::render_hud(player, player_info, ctx.add_xy(64, 4), fb);
+        if let Some(boss_hit_points) = self.game.inner_ref().visible_boss_hit_points() {
+            ui::render_boss_hud(boss_hit_points, ctx.add_xy(64, 1), fb);
+        }
     }
 }
 
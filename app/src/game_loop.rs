@@ -9,16 +9,23 @@ use crate::{
 };
 use gridbugs::{
     chargrid::{
-        border::BorderStyle, control_flow::*, input::*, menu, menu::Menu, pad_by::Padding,
-        prelude::*, text::StyledString,
+        border::BorderStyle,
+        control_flow::*,
+        input::*,
+        menu,
+        menu::Menu,
+        pad_by::Padding,
+        prelude::*,
+        text::{StyledString, Text},
     },
     direction::Direction,
     storage::{format, Storage},
 };
 use orbital_decay_game::{
-    player,
+    perk, player,
     witness::{self, GameOver, GameOverType, Witness},
-    ActionError, Config as GameConfig, ExternalEvent, Game, Music, MAP_SIZE,
+    ActionError, Config as GameConfig, Consumable, ExternalEvent, Game, MovementRuleset, Music,
+    TerminalCommand, Ware, MAP_SIZE,
 };
 use rand::{Rng, SeedableRng};
 use rand_isaac::Isaac64Rng;
@@ -39,6 +46,12 @@ struct Config {
     sfx_volume: f32,
     won: bool,
     first_run: bool,
+    /// Applies to the next new game
+    #[serde(default)]
+    movement_ruleset: MovementRuleset,
+    /// Applies to the next new game
+    #[serde(default)]
+    free_aim: bool,
 }
 
 impl Default for Config {
@@ -48,6 +61,8 @@ impl Default for Config {
             sfx_volume: 0.5,
             won: false,
             first_run: true,
+            movement_ruleset: MovementRuleset::default(),
+            free_aim: false,
         }
     }
 }
@@ -290,15 +305,73 @@ fn action_error_message(action_error: ActionError) -> StyledString {
     let string = match action_error {
         ActionError::WalkIntoSolidCell => "You can't walk there!".to_string(),
         ActionError::CannotAffordUpgrade => "You can't afford that!".to_string(),
+        ActionError::PerkUnavailable => "You can't take that perk!".to_string(),
+        ActionError::CannotAffordWeaponMod => "You can't afford that!".to_string(),
+        ActionError::CannotAffordWare => "You can't afford that!".to_string(),
+        ActionError::SoldOut => "That's sold out!".to_string(),
+        ActionError::NoSpaceToDispense => {
+            "There's no space at your feet for the vendor to put that!".to_string()
+        }
+        ActionError::AmmoReserveFull(ammo_type) => {
+            format!(
+                "You can't carry any more {}!",
+                ui::ammo_type_name(ammo_type)
+            )
+        }
+        ActionError::NoRoomForAmmo(ammo_type) => {
+            format!(
+                "You don't have room for a full pack of {}!",
+                ui::ammo_type_name(ammo_type)
+            )
+        }
+        ActionError::CannotFitWeaponMod => "That mod can't be fitted to this weapon!".to_string(),
+        ActionError::NothingToShove => "There is nobody there to shove!".to_string(),
+        ActionError::TooHeavyToShove => "They're too heavy to shove!".to_string(),
+        ActionError::NoSpaceToShove => "There's no room to shove them there!".to_string(),
+        ActionError::TerminalOffline => "This terminal has shut down.".to_string(),
+        ActionError::NothingToUnlock => "There are no locked doors on this floor!".to_string(),
+        ActionError::NoWindowsToVent => "That room has no windows to vent!".to_string(),
+        ActionError::CannotAimThere => "You can't aim in that direction!".to_string(),
+        ActionError::CannotMoveThatWay => "You can't move in that direction!".to_string(),
+        ActionError::NoFlashlight => "You don't have a flashlight!".to_string(),
+        ActionError::FlashlightBatteryFlat => "Your flashlight's battery is flat!".to_string(),
         ActionError::NoItemToGet => "There is no item here!".to_string(),
         ActionError::NoWeaponInSlot(slot) => format!("No weapon in slot {}!", slot.number()),
         ActionError::WeaponOutOfAmmo(name) => {
             format!("{} is out of ammo!", ui::weapon_name_text(name).string)
         }
+        ActionError::WalkOffHull => "Your magnetic boots won't let go of the hull!".to_string(),
+        ActionError::NoGrenades => "You don't have any grenades!".to_string(),
+        ActionError::InventoryEmpty => "You aren't carrying anything!".to_string(),
+        ActionError::InventoryFull => "Your inventory is full!".to_string(),
+        ActionError::NoItemInInventorySlot => "There's nothing there!".to_string(),
+        ActionError::NoSpaceToDrop => "There's already something on the floor here!".to_string(),
+        ActionError::NothingToRepair => "There's nothing here to repair!".to_string(),
+        ActionError::AlreadyAtFullHealth => "You're already at full health!".to_string(),
+        ActionError::AlreadyAtFullOxygen => "Your oxygen is already full!".to_string(),
+        ActionError::NothingToReload => "You have nothing to reload!".to_string(),
+        ActionError::MagazineFull(name) => {
+            format!("{} is already loaded!", ui::weapon_name_text(name).string)
+        }
+        ActionError::NoReserveAmmo(name) => {
+            format!(
+                "You have no spare ammo for the {}!",
+                ui::weapon_name_text(name).string
+            )
+        }
     };
     StyledString { string, style }
 }
 
+/// What the player is aiming while choosing a target
+#[derive(Clone, Copy)]
+enum Aim {
+    Weapon(player::RangedWeaponSlot),
+    Grenade,
+    /// Choosing a room to vent from a terminal
+    Vent,
+}
+
 struct ScreenShake {
     direction: Direction,
     remaining: Duration,
@@ -316,6 +389,7 @@ pub struct GameLoopData {
     context_message: Option<StyledString>,
     examine_message: Option<StyledString>,
     cursor: Option<Coord>,
+    targeting: Option<Aim>,
     duration: Duration,
     screen_shake: Option<ScreenShake>,
     effect_rng: XorShiftRng,
@@ -323,12 +397,15 @@ pub struct GameLoopData {
 
 impl GameLoopData {
     pub fn new(
-        game_config: GameConfig,
+        mut game_config: GameConfig,
         mut storage: AppStorage,
         initial_rng_seed: InitialRngSeed,
         audio_player: AppAudioPlayer,
         force_new_game: bool,
     ) -> (Self, GameLoopState) {
+        let config = storage.load_config().unwrap_or_default();
+        game_config.movement_ruleset = config.movement_ruleset;
+        game_config.free_aim = config.free_aim;
         let mut rng_seed_source = RngSeedSource::new(initial_rng_seed);
         let (instance, state) = match storage.load_game() {
             Some(instance) => {
@@ -350,7 +427,10 @@ impl GameLoopData {
                 }
             }
         };
-        let controls = if let Some(controls) = storage.load_controls() {
+        let controls = if let Some(mut controls) = storage.load_controls() {
+            if controls.add_missing_defaults() {
+                storage.save_controls(&controls);
+            }
             controls
         } else {
             let controls = Controls::default();
@@ -359,7 +439,6 @@ impl GameLoopData {
         };
         let menu_background = MenuBackground::new(&mut Isaac64Rng::from_entropy());
         let mut audio_state = AudioState::new(audio_player);
-        let config = storage.load_config().unwrap_or_default();
         if let Some(instance) = instance.as_ref() {
             if let Some(music) = instance.current_music {
                 audio_state.loop_music(game_music_to_audio(music), config.music_volume);
@@ -380,6 +459,7 @@ impl GameLoopData {
                 context_message: None,
                 examine_message: None,
                 cursor: None,
+                targeting: None,
                 duration: Duration::from_millis(0),
                 screen_shake: None,
                 effect_rng: XorShiftRng::from_entropy(),
@@ -403,6 +483,8 @@ impl GameLoopData {
     }
 
     fn new_game(&mut self) -> witness::Running {
+        self.game_config.movement_ruleset = self.config.movement_ruleset;
+        self.game_config.free_aim = self.config.free_aim;
         let (instance, running) = new_game(&mut self.rng_seed_source, &self.game_config);
         self.instance = Some(instance);
         running
@@ -428,6 +510,34 @@ impl GameLoopData {
         };
         let instance = self.instance.as_ref().unwrap();
         instance.render(ctx, fb);
+        if let (Some(aim), Some(cursor)) = (self.targeting, self.cursor) {
+            let game = instance.game.inner_ref();
+            let preview = match aim {
+                Aim::Weapon(slot) => game.shot_preview(slot, cursor),
+                Aim::Grenade => game.throw_preview(cursor),
+                Aim::Vent => game.vent_preview(cursor),
+            };
+            let cells = preview
+                .path
+                .iter()
+                .map(|&coord| (coord, colours::SHOT_PREVIEW))
+                .chain(
+                    preview
+                        .breach
+                        .iter()
+                        .map(|&coord| (coord, colours::SHOT_PREVIEW_BREACH)),
+                );
+            for (coord, colour) in cells {
+                for offset in Size::new_u16(3, 3).coord_iter_row_major() {
+                    fb.set_cell_relative_to_ctx(
+                        ctx,
+                        (coord * 3) + offset,
+                        9,
+                        RenderCell::BLANK.with_background(colour),
+                    );
+                }
+            }
+        }
         if let Some(cursor) = self.cursor {
             if cursor.is_valid(MAP_SIZE + Size::new_u16(1, 1)) {
                 let screen_cursor = cursor * 3;
@@ -481,11 +591,29 @@ impl GameLoopData {
                 if let Some(app_input) = self.controls.get(input) {
                     let (witness, action_result) = match app_input {
                         AppInput::Direction(direction) => {
-                            running.walk(&mut instance.game, direction, &self.game_config)
+                            if instance
+                                .game
+                                .inner_ref()
+                                .movement_ruleset()
+                                .allows(direction)
+                            {
+                                running.walk(&mut instance.game, direction, &self.game_config)
+                            } else {
+                                (running.into_witness(), Ok(()))
+                            }
                         }
                         AppInput::Wait => running.wait(&mut instance.game, &self.game_config),
-                        AppInput::Get => running.get(&instance.game),
-                        AppInput::Slot(slot) => running.fire_weapon(&instance.game, slot),
+                        AppInput::Get => running.get(&mut instance.game, &self.game_config),
+                        AppInput::Slot(slot) => {
+                            running.fire_weapon(&mut instance.game, slot, &self.game_config)
+                        }
+                        AppInput::Throw => running.throw_grenade(&instance.game),
+                        AppInput::Inventory => running.inventory(&instance.game),
+                        AppInput::Reload => running.reload(&instance.game),
+                        AppInput::Shove => (running.shove(), Ok(())),
+                        AppInput::Flashlight => {
+                            running.toggle_flashlight(&mut instance.game, &self.game_config)
+                        }
                         AppInput::Examine => {
                             return GameLoopState::Examine(running);
                         }
@@ -570,6 +698,14 @@ impl GameLoopData {
         self.game().inner_ref()
     }
 
+    /// The direction chosen by an input, if the current run's movement ruleset allows it
+    fn allowed_direction(&self, input: Input) -> Option<Direction> {
+        let movement_ruleset = self.game_inner().movement_ruleset();
+        self.controls
+            .get_direction(input)
+            .filter(|&direction| movement_ruleset.allows(direction))
+    }
+
     fn player_has_third_weapon_slot(&self) -> bool {
         self.game_inner().player_has_third_weapon_slot()
     }
@@ -616,10 +752,12 @@ impl Component for GameInstanceComponent {
     }
 }
 
-struct GameExamineWithMouseComponent;
+struct GameTargetingComponent {
+    aim: Aim,
+}
 
-impl Component for GameExamineWithMouseComponent {
-    type Output = ();
+impl Component for GameTargetingComponent {
+    type Output = Option<Coord>;
     type State = GameLoopData;
 
     fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
@@ -627,7 +765,49 @@ impl Component for GameExamineWithMouseComponent {
     }
 
     fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        if let Some(input) = event.input() {
+            if let Some(direction) = state.controls.get_direction(input) {
+                let cursor = state
+                    .cursor
+                    .unwrap_or_else(|| state.game_inner().player_coord());
+                state.cursor = Some(cursor + direction.coord());
+            }
+            let fire = match input {
+                Input::Keyboard(keys::RETURN) => true,
+                Input::Mouse(MouseInput::MousePress {
+                    button: MouseButton::Left,
+                    coord,
+                }) => {
+                    state.cursor = Some(coord / 3);
+                    true
+                }
+                _ => match (self.aim, state.controls.get(input)) {
+                    (Aim::Weapon(slot), Some(AppInput::Slot(input_slot))) => slot == input_slot,
+                    (Aim::Grenade, Some(AppInput::Throw)) => true,
+                    _ => false,
+                },
+            };
+            if fire {
+                if let Some(target) = state.cursor {
+                    let game = state.game_inner();
+                    let valid = match self.aim {
+                        // any room the player has seen can be vented, including their own
+                        Aim::Vent => !game.visibility_grid().is_coord_never_visible(target),
+                        Aim::Weapon(_) => game.can_aim_at(target),
+                        Aim::Grenade => {
+                            target != game.player_coord()
+                                && game.visibility_grid().is_coord_currently_visible(target)
+                        }
+                    };
+                    if valid {
+                        return Some(target);
+                    }
+                }
+            }
+        }
         state.examine_mouse(event);
+        state.update_examine_text();
+        None
     }
 
     fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
@@ -766,59 +946,61 @@ fn menu_style<T: 'static>(menu: AppCF<T>) -> AppCF<T> {
         )
 }
 
-fn upgrade_identifier(upgrade: player::Upgrade) -> String {
-    let name = match upgrade.typ {
-        player::UpgradeType::Toughness => "Toughness",
-        player::UpgradeType::Accuracy => "Accuracy",
-        player::UpgradeType::Endurance => "Endurance",
-    };
-    let level = match upgrade.level {
-        player::UpgradeLevel::Level1 => "1",
-        player::UpgradeLevel::Level2 => "2",
-    };
-    let price = upgrade.level.cost();
-    format!("{} {} (${})", name, level, price)
-}
-
-fn upgrade_description(upgrade: &player::Upgrade) -> &'static str {
-    use player::{UpgradeLevel::*, UpgradeType::*};
-    match upgrade {
-        player::Upgrade {
-            typ: Toughness,
-            level: Level1,
-        } => "Toughness 1: Strong Back\nGain a third ranged weapon slot.",
-        player::Upgrade {
-            typ: Toughness,
-            level: Level2,
-        } => "Toughness 2: Hardy\nDouble your maximum health.",
-        player::Upgrade {
-            typ: Accuracy,
-            level: Level1,
-        } => "Accuracy 1: Careful\nReduce hull pen chance by half.",
-        player::Upgrade {
-            typ: Accuracy,
-            level: Level2,
-        } => "Accuracy 2: Kill Shot\nDeal double damage to enemies.",
-        player::Upgrade {
-            typ: Endurance,
-            level: Level1,
-        } => "Endurance 1: Sure-Footed\nVacuum pulls you one space each turn instead of two.",
-        player::Upgrade {
-            typ: Endurance,
-            level: Level2,
-        } => "Endurance 2: Big Lungs\nDouble your maximum oxygen.",
+fn perk_marker(status: perk::PerkStatus) -> char {
+    match status {
+        perk::PerkStatus::Taken => '✓',
+        perk::PerkStatus::Available => '+',
+        perk::PerkStatus::Locked => '·',
+        perk::PerkStatus::Excluded => 'x',
     }
 }
 
+fn perk_description(perks: &perk::Perks, perk: perk::Perk) -> String {
+    let spec = perk.spec();
+    let branch = perk::PERK_TREE
+        .iter()
+        .find(|branch| branch.root == perk || branch.children.contains(&perk))
+        .map(|branch| branch.name)
+        .unwrap_or("");
+    let note = match perks.status(perk) {
+        perk::PerkStatus::Taken => "You have this perk.".to_string(),
+        perk::PerkStatus::Locked => match spec.requires {
+            Some(required) => format!("Requires {}.", required.spec().name),
+            None => String::new(),
+        },
+        perk::PerkStatus::Excluded => {
+            let closed_by = perks
+                .iter()
+                .find(|taken| taken.spec().excludes.contains(&perk))
+                .map(|taken| taken.spec().name)
+                .unwrap_or("");
+            format!("Ruled out by {}.", closed_by)
+        }
+        perk::PerkStatus::Available => {
+            let names = spec
+                .excludes
+                .iter()
+                .map(|excluded| excluded.spec().name)
+                .collect::<Vec<_>>();
+            if names.is_empty() {
+                String::new()
+            } else {
+                format!("Rules out {}.", names.join(", "))
+            }
+        }
+    };
+    format!("{}: {}\n{}\n{}", branch, spec.name, spec.description, note)
+}
+
 struct UpgradeMenuDecorated {
-    menu: Menu<player::Upgrade>,
+    menu: Menu<perk::Perk>,
 }
 impl UpgradeMenuDecorated {
-    const MENU_Y_OFFSET: i32 = 4;
+    const MENU_Y_OFFSET: i32 = 6;
     const TEXT_STYLE: Style = Style::new()
         .with_bold(false)
         .with_foreground(Rgba32::new_grey(255));
-    const SIZE: Size = Size::new_u16(33, 13);
+    const SIZE: Size = Size::new_u16(40, 21);
 
     fn text(ctx: Ctx, fb: &mut FrameBuffer, string: String) {
         StyledString {
@@ -829,24 +1011,33 @@ impl UpgradeMenuDecorated {
     }
 }
 impl Component for UpgradeMenuDecorated {
-    type Output = Option<player::Upgrade>;
+    type Output = Option<perk::Perk>;
     type State = GameLoopData;
 
     fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
         let instance = state.instance.as_ref().unwrap();
-        let balance = instance.game.player().credit;
-        Self::text(ctx, fb, "Buy an Upgrade (escape cancels)".to_string());
-        Self::text(ctx.add_y(2), fb, format!("Your balance: ${}", balance));
+        let player = instance.game.player();
+        Self::text(ctx, fb, "Buy a Perk (escape cancels)".to_string());
+        Self::text(
+            ctx.add_y(2),
+            fb,
+            format!("Your balance: ${}", player.credit),
+        );
+        Self::text(
+            ctx.add_y(4),
+            fb,
+            "✓ taken + available · locked x ruled out".to_string(),
+        );
         self.menu.render(&(), ctx.add_y(Self::MENU_Y_OFFSET), fb);
-        let description = upgrade_description(self.menu.selected());
+        let description = perk_description(&player.perks, *self.menu.selected());
         StyledString {
-            string: description.to_string(),
+            string: description,
             style: Self::TEXT_STYLE,
         }
         .wrap_word()
         .cf()
         .bound_width(Self::SIZE.width())
-        .render(&(), ctx.add_y(9), fb);
+        .render(&(), ctx.add_y(Self::MENU_Y_OFFSET + 10), fb);
     }
 
     fn update(&mut self, _state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
@@ -859,22 +1050,250 @@ impl Component for UpgradeMenuDecorated {
     }
 }
 
-fn upgrade_menu() -> AppCF<player::Upgrade> {
+/// Lists every perk, with each branch's children drawn beneath its root
+fn upgrade_menu() -> AppCF<perk::Perk> {
     on_state_then(|state: &mut State| {
-        let instance = state.instance.as_ref().unwrap();
-        let upgrades = instance.game.inner_ref().available_upgrades();
+        let perks = &state.game_inner().player().perks;
         use menu::builder::*;
         let mut builder = menu_builder().vi_keys();
-        for upgrade in upgrades {
-            let name = upgrade_identifier(upgrade);
-            let identifier = MENU_FADE_SPEC.identifier(move |b| write!(b, "{}", name).unwrap());
-            builder = builder.add_item(item(upgrade, identifier));
+        for branch in perk::PERK_TREE {
+            let mut lines = vec![(branch.root, String::new())];
+            for (i, &child) in branch.children.iter().enumerate() {
+                let prefix = if i + 1 == branch.children.len() {
+                    " └─"
+                } else {
+                    " ├─"
+                };
+                lines.push((child, prefix.to_string()));
+            }
+            for (perk, prefix) in lines {
+                let spec = perk.spec();
+                let marker = perk_marker(perks.status(perk));
+                let (name, cost) = (spec.name, spec.cost);
+                let identifier = MENU_FADE_SPEC.identifier(move |b| {
+                    write!(b, "{}{} {} (${})", prefix, marker, name, cost).unwrap()
+                });
+                builder = builder.add_item(item(perk, identifier));
+            }
         }
         let menu = builder.build();
         UpgradeMenuDecorated { menu }
     })
 }
 
+fn ware_description(ware: Ware) -> String {
+    match ware {
+        Ware::RangedWeapon(_) => "A ranged weapon. It will be left at your feet.",
+        Ware::Consumable(Consumable::Medkit) => "Restores you to full health when used.",
+        Ware::Consumable(Consumable::OxygenCanister) => "Refills your oxygen when used.",
+        Ware::Consumable(Consumable::Grenade) => "Blows a hole in anything next to it.",
+        Ware::Consumable(Consumable::RepairKit) => "Repairs damaged walls, doors and windows.",
+        Ware::Ammo(_) => "Spare ammo, added straight to your reserve.",
+    }
+    .to_string()
+}
+
+struct VendorMenuDecorated {
+    menu: Menu<usize>,
+    descriptions: Vec<String>,
+}
+impl VendorMenuDecorated {
+    const MENU_Y_OFFSET: i32 = 4;
+    const SIZE: Size = Size::new_u16(33, 15);
+}
+impl Component for VendorMenuDecorated {
+    type Output = Option<usize>;
+    type State = GameLoopData;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let instance = state.instance.as_ref().unwrap();
+        let balance = instance.game.player().credit;
+        UpgradeMenuDecorated::text(ctx, fb, "Buy Supplies (escape cancels)".to_string());
+        UpgradeMenuDecorated::text(ctx.add_y(2), fb, format!("Your balance: ${}", balance));
+        self.menu.render(&(), ctx.add_y(Self::MENU_Y_OFFSET), fb);
+        StyledString {
+            string: self.descriptions[*self.menu.selected()].clone(),
+            style: UpgradeMenuDecorated::TEXT_STYLE,
+        }
+        .wrap_word()
+        .cf()
+        .bound_width(Self::SIZE.width())
+        .render(&(), ctx.add_y(Self::MENU_Y_OFFSET + 7), fb);
+    }
+
+    fn update(&mut self, _state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
+        self.menu
+            .update(&mut (), ctx.add_y(Self::MENU_Y_OFFSET), event)
+    }
+
+    fn size(&self, _state: &Self::State, _ctx: Ctx) -> Size {
+        Self::SIZE
+    }
+}
+
+fn vendor_menu(stock: Vec<Ware>) -> AppCF<usize> {
+    use menu::builder::*;
+    let mut builder = menu_builder().vi_keys();
+    for (index, &ware) in stock.iter().enumerate() {
+        let name = ui::ware_text(ware).string;
+        let price = ware.price();
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "{} (${})", name, price).unwrap());
+        builder = builder.add_item(item(index, identifier));
+    }
+    let menu = builder.build();
+    let descriptions = stock.into_iter().map(ware_description).collect();
+    cf(VendorMenuDecorated { menu, descriptions })
+}
+
+fn weapon_mod_description(weapon_mod: player::WeaponMod) -> String {
+    let name = ui::weapon_mod_name(weapon_mod);
+    let effect = match weapon_mod {
+        player::WeaponMod::ArmourPiercing => "Increase PEN by half.".to_string(),
+        player::WeaponMod::Dampener => {
+            "Halve HULL PEN, making accidental breaches less likely.".to_string()
+        }
+        player::WeaponMod::ExtendedMagazine => "Increase the magazine size by half.".to_string(),
+        player::WeaponMod::Ability(ability) => {
+            format!(
+                "Add an ability: {}.",
+                ui::weapon_ability_text(ability).string
+            )
+        }
+    };
+    format!("{}\n{}", name, effect)
+}
+
+struct WeaponModMenuDecorated {
+    menu: Menu<player::WeaponMod>,
+    weapon_name: player::WeaponName,
+}
+impl WeaponModMenuDecorated {
+    const MENU_Y_OFFSET: i32 = 4;
+    const SIZE: Size = Size::new_u16(33, 13);
+}
+impl Component for WeaponModMenuDecorated {
+    type Output = Option<player::WeaponMod>;
+    type State = GameLoopData;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let instance = state.instance.as_ref().unwrap();
+        let balance = instance.game.player().credit;
+        Text::from(vec![
+            StyledString::plain_text("Modify your ".to_string()),
+            ui::weapon_name_text(self.weapon_name),
+            StyledString::plain_text(" (escape cancels)".to_string()),
+        ])
+        .render(&(), ctx, fb);
+        UpgradeMenuDecorated::text(ctx.add_y(2), fb, format!("Your balance: ${}", balance));
+        self.menu.render(&(), ctx.add_y(Self::MENU_Y_OFFSET), fb);
+        StyledString {
+            string: weapon_mod_description(*self.menu.selected()),
+            style: UpgradeMenuDecorated::TEXT_STYLE,
+        }
+        .wrap_word()
+        .cf()
+        .bound_width(Self::SIZE.width())
+        .render(&(), ctx.add_y(9), fb);
+    }
+
+    fn update(&mut self, _state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
+        self.menu
+            .update(&mut (), ctx.add_y(Self::MENU_Y_OFFSET), event)
+    }
+
+    fn size(&self, _state: &Self::State, _ctx: Ctx) -> Size {
+        Self::SIZE
+    }
+}
+
+fn weapon_mod_menu(
+    weapon_name: player::WeaponName,
+    weapon_mods: Vec<player::WeaponMod>,
+) -> AppCF<player::WeaponMod> {
+    use menu::builder::*;
+    let mut builder = menu_builder().vi_keys();
+    for weapon_mod in weapon_mods {
+        let name = ui::weapon_mod_name(weapon_mod);
+        let cost = weapon_mod.cost();
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "{} (${})", name, cost).unwrap());
+        builder = builder.add_item(item(weapon_mod, identifier));
+    }
+    let menu = builder.build();
+    cf(WeaponModMenuDecorated { menu, weapon_name })
+}
+
+fn held_weapon_menu() -> AppCF<player::HeldWeapon> {
+    on_state_then(|state: &mut State| {
+        let game = state.game_inner();
+        use menu::builder::*;
+        let mut builder = menu_builder().vi_keys();
+        for (index, held_weapon) in game.moddable_weapons().into_iter().enumerate() {
+            let ch = (b'a' + index as u8) as char;
+            let slot = match held_weapon {
+                player::HeldWeapon::Melee => "Melee".to_string(),
+                player::HeldWeapon::Ranged(slot) => format!("Ranged {}", slot.number()),
+            };
+            let weapon_name = game.player().held_weapon(held_weapon).unwrap().name;
+            let name = ui::weapon_name_text(weapon_name).string;
+            let identifier = MENU_FADE_SPEC
+                .identifier(move |b| write!(b, "({}) {}: {}", ch, slot, name).unwrap());
+            builder = builder.add_item(item(held_weapon, identifier).add_hotkey_char(ch));
+        }
+        builder.build_cf()
+    })
+}
+
+fn inventory_menu() -> AppCF<usize> {
+    on_state_then(|state: &mut State| {
+        let inventory = &state.game_inner().player().inventory;
+        use menu::builder::*;
+        let mut builder = menu_builder().vi_keys();
+        for (index, consumable) in inventory.iter().enumerate() {
+            let ch = (b'a' + index as u8) as char;
+            let name = ui::consumable_name(consumable);
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+            builder = builder.add_item(item(index, identifier).add_hotkey_char(ch));
+        }
+        builder.build_cf()
+    })
+}
+
+#[derive(Clone, Copy)]
+enum InventoryAction {
+    Use,
+    Drop,
+}
+
+fn inventory_action_menu(consumable: Consumable) -> AppCF<InventoryAction> {
+    use menu::builder::*;
+    let verb = if consumable == Consumable::Grenade {
+        "Throw"
+    } else {
+        "Use"
+    };
+    menu_builder()
+        .vi_keys()
+        .add_item(
+            item(
+                InventoryAction::Use,
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "(u) {}", verb).unwrap()),
+            )
+            .add_hotkey_char('u'),
+        )
+        .add_item(
+            item(
+                InventoryAction::Drop,
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "(d) Drop").unwrap()),
+            )
+            .add_hotkey_char('d'),
+        )
+        .build_cf()
+        .with_title_vertical(cf(ui::consumable_text(consumable)).ignore_state(), 1)
+}
+
 fn yes_no_menu() -> AppCF<bool> {
     use menu::builder::*;
     menu_builder()
@@ -936,7 +1355,20 @@ fn upgrade_component(upgrade_witness: witness::Upgrade) -> AppCF<Witness> {
                 Err(Close) => val_once(upgrade_witness.cancel()),
                 Ok(upgrade) => {
                     let instance = state.instance.as_mut().unwrap();
-                    if upgrade.level.cost() > instance.game.player().credit {
+                    let player = instance.game.player();
+                    let unavailable = match player.perks.status(upgrade) {
+                        perk::PerkStatus::Taken => Some("You already have that perk!"),
+                        perk::PerkStatus::Locked => {
+                            Some("You need to take the perk above it first!")
+                        }
+                        perk::PerkStatus::Excluded => {
+                            Some("A perk you've taken rules that one out!")
+                        }
+                        perk::PerkStatus::Available => None,
+                    };
+                    if let Some(message) = unavailable {
+                        popup(message.to_string()).map_val(|| upgrade_witness.cancel())
+                    } else if upgrade.spec().cost > player.credit {
                         popup("You can't afford that!".to_string())
                             .map_val(|| upgrade_witness.cancel())
                     } else {
@@ -952,18 +1384,308 @@ fn upgrade_component(upgrade_witness: witness::Upgrade) -> AppCF<Witness> {
         })
 }
 
+fn vendor_component(vendor_witness: witness::Vendor) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        let stock = vendor_witness.stock(state.game()).to_vec();
+        if stock.is_empty() {
+            return popup("This vendor is sold out!".to_string())
+                .map_val(|| vendor_witness.cancel());
+        }
+        menu_style(vendor_menu(stock.clone()))
+            .menu_harness()
+            .and_then(move |result| {
+                on_state_then(move |state: &mut State| match result {
+                    Err(Close) => val_once(vendor_witness.cancel()),
+                    Ok(index) => {
+                        if stock[index].price() > state.game_inner().player().credit {
+                            return popup("You can't afford that!".to_string())
+                                .map_val(|| vendor_witness.cancel());
+                        }
+                        let (game, config) = state.game_mut_config();
+                        let (witness, result) = vendor_witness.commit(game, index, config);
+                        state.context_message = result.err().map(action_error_message);
+                        val_once(witness)
+                    }
+                })
+            })
+    })
+}
+
+#[derive(Clone, Copy)]
+enum TerminalMenuEntry {
+    UnlockDoors,
+    VentRoom,
+    LightsOff,
+    RevealEnemies,
+}
+
+fn terminal_menu() -> AppCF<TerminalMenuEntry> {
+    use menu::builder::*;
+    let mut builder = menu_builder().vi_keys();
+    for (entry, ch, name) in [
+        (TerminalMenuEntry::UnlockDoors, 'u', "Unlock Doors"),
+        (TerminalMenuEntry::VentRoom, 'v', "Vent Room"),
+        (TerminalMenuEntry::LightsOff, 'l', "Lights Off"),
+        (TerminalMenuEntry::RevealEnemies, 'r', "Reveal Enemies"),
+    ] {
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+        builder = builder.add_item(item(entry, identifier).add_hotkey_char(ch));
+    }
+    builder.build_cf().with_title_vertical(
+        cf(StyledString {
+            string: "Security Terminal - one command only (escape cancels)".to_string(),
+            style: Style::plain_text(),
+        }
+        .wrap_word())
+        .ignore_state()
+        .bound_width(30),
+        1,
+    )
+}
+
+fn vent_room_target(witness: witness::Terminal) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        state.context_message = Some(StyledString {
+            string: "Choose a room to vent and press enter (escape/start to cancel)".to_string(),
+            style: Style::plain_text()
+                .with_bold(true)
+                .with_foreground(Rgba32::hex_rgb(0xFF0000)),
+        });
+        state.cursor = Some(state.game_inner().player_coord());
+        state.targeting = Some(Aim::Vent);
+        cf(GameTargetingComponent { aim: Aim::Vent })
+            .catch_escape_or_start()
+            .and_then(|target_or_err| {
+                on_state(move |state: &mut State| {
+                    state.context_message = None;
+                    state.cursor = None;
+                    state.targeting = None;
+                    match target_or_err {
+                        Err(_escape_or_start) => witness.cancel(),
+                        Ok(target) => {
+                            let (game, config) = state.game_mut_config();
+                            let (witness, result) =
+                                witness.commit(game, TerminalCommand::VentRoom(target), config);
+                            state.context_message = result.err().map(action_error_message);
+                            witness
+                        }
+                    }
+                })
+            })
+    })
+}
+
+fn terminal_component(terminal_witness: witness::Terminal) -> AppCF<Witness> {
+    menu_style(terminal_menu())
+        .menu_harness()
+        .and_then(move |result| {
+            on_state_then(move |state: &mut State| {
+                let command = match result {
+                    Err(Close) => return val_once(terminal_witness.cancel()),
+                    Ok(TerminalMenuEntry::VentRoom) => return vent_room_target(terminal_witness),
+                    Ok(TerminalMenuEntry::UnlockDoors) => TerminalCommand::UnlockDoors,
+                    Ok(TerminalMenuEntry::LightsOff) => TerminalCommand::LightsOff,
+                    Ok(TerminalMenuEntry::RevealEnemies) => TerminalCommand::RevealEnemies,
+                };
+                let (game, config) = state.game_mut_config();
+                let (witness, result) = terminal_witness.commit(game, command, config);
+                state.context_message = result.err().map(action_error_message);
+                val_once(witness)
+            })
+        })
+}
+
+fn workbench_component(workbench_witness: witness::Workbench) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        if state.game_inner().moddable_weapons().is_empty() {
+            return popup("You have no weapons to modify!".to_string())
+                .map_val(|| workbench_witness.cancel());
+        }
+        menu_style(
+            held_weapon_menu().with_title_vertical(
+                cf(StyledString {
+                    string: "Choose a weapon to modify (escape cancels)".to_string(),
+                    style: Style::plain_text(),
+                })
+                .ignore_state(),
+                1,
+            ),
+        )
+        .menu_harness()
+        .and_then(|result| {
+            on_state_then(move |state: &mut State| {
+                let held_weapon = match result {
+                    Err(Close) => return val_once(workbench_witness.cancel()),
+                    Ok(held_weapon) => held_weapon,
+                };
+                let game = state.game_inner();
+                let weapon_name = game.player().held_weapon(held_weapon).unwrap().name;
+                let weapon_mods =
+                    game.available_weapon_mods(held_weapon, workbench_witness.ability());
+                if weapon_mods.is_empty() {
+                    return popup(
+                        "This workbench can't improve that weapon any further!".to_string(),
+                    )
+                    .map_val(|| workbench_witness.cancel());
+                }
+                menu_style(weapon_mod_menu(weapon_name, weapon_mods))
+                    .menu_harness()
+                    .and_then(move |result| {
+                        on_state_then(move |state: &mut State| match result {
+                            Err(Close) => val_once(workbench_witness.cancel()),
+                            Ok(weapon_mod) => {
+                                if weapon_mod.cost() > state.game_inner().player().credit {
+                                    return popup("You can't afford that!".to_string())
+                                        .map_val(|| workbench_witness.cancel());
+                                }
+                                let (game, config) = state.game_mut_config();
+                                let (witness, result) =
+                                    workbench_witness.commit(game, held_weapon, weapon_mod, config);
+                                state.context_message = result.err().map(action_error_message);
+                                val_once(witness)
+                            }
+                        })
+                    })
+            })
+        })
+    })
+}
+
 fn try_upgrade_component(upgrade_witness: witness::Upgrade) -> AppCF<Witness> {
     on_state_then(move |state: &mut State| {
         let instance = state.instance.as_ref().unwrap();
         let upgrades = instance.game.inner_ref().available_upgrades();
         if upgrades.is_empty() {
-            popup("No remaining upgrades!".to_string()).map_val(|| upgrade_witness.cancel())
+            popup("No remaining perks!".to_string()).map_val(|| upgrade_witness.cancel())
         } else {
             upgrade_component(upgrade_witness)
         }
     })
 }
 
+fn inventory_component(inventory_witness: witness::Inventory) -> AppCF<Witness> {
+    menu_style(
+        inventory_menu().with_title_vertical(
+            cf(StyledString {
+                string: "Inventory (escape cancels)".to_string(),
+                style: Style::plain_text(),
+            })
+            .ignore_state(),
+            1,
+        ),
+    )
+    .menu_harness()
+    .and_then(|result| {
+        on_state_then(move |state: &mut State| {
+            let index = match result {
+                Err(Close) => return val_once(inventory_witness.cancel()),
+                Ok(index) => index,
+            };
+            let consumable = state.game_inner().player().inventory.get(index).unwrap();
+            menu_style(inventory_action_menu(consumable))
+                .menu_harness()
+                .and_then(move |result| {
+                    on_state(move |state: &mut State| {
+                        let (game, config) = state.game_mut_config();
+                        let (witness, action_result) = match result {
+                            Err(Close) => return inventory_witness.cancel(),
+                            Ok(InventoryAction::Use) => {
+                                inventory_witness.use_item(game, index, config)
+                            }
+                            Ok(InventoryAction::Drop) => {
+                                inventory_witness.drop_item(game, index, config)
+                            }
+                        };
+                        state.context_message = action_result.err().map(action_error_message);
+                        witness
+                    })
+                })
+        })
+    })
+}
+
+fn reload_component(witness: witness::Reload) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        let slots = state.game_inner().reloadable_slots();
+        if let [slot] = slots[..] {
+            let (game, config) = state.game_mut_config();
+            let (witness, result) = witness.commit(game, slot, config);
+            state.context_message = result.err().map(action_error_message);
+            return val_once(witness);
+        }
+        state.context_message = Some(StyledString {
+            string: "Choose a weapon to reload: (press 1-3 or escape/start to cancel)".to_string(),
+            style: Style::plain_text()
+                .with_bold(true)
+                .with_foreground(Rgba32::hex_rgb(0xFF0000)),
+        });
+        on_input_state(move |input, state: &mut State| {
+            state
+                .controls
+                .get_slot(input)
+                .filter(|slot| slots.contains(slot))
+        })
+        .catch_escape_or_start()
+        .overlay(
+            render_state(|state: &State, ctx, fb| state.render(CURSOR_COLOUR, ctx, fb)),
+            10,
+        )
+        .and_then(|slot_or_err| {
+            on_state(move |state: &mut State| {
+                state.context_message = None;
+                match slot_or_err {
+                    Err(_escape_or_start) => witness.cancel(),
+                    Ok(slot) => {
+                        let (game, config) = state.game_mut_config();
+                        let (witness, result) = witness.commit(game, slot, config);
+                        state.context_message = result.err().map(action_error_message);
+                        witness
+                    }
+                }
+            })
+        })
+    })
+}
+
+fn shove_component(witness: witness::Shove) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        state.context_message = Some(StyledString {
+            string: "Choose a direction to shove: (press a direction or escape/start to cancel)"
+                .to_string(),
+            style: Style::plain_text()
+                .with_bold(true)
+                .with_foreground(Rgba32::hex_rgb(0xFF0000)),
+        });
+        on_input_state(move |input, state: &mut State| {
+            let movement_ruleset = state.game_inner().movement_ruleset();
+            state
+                .controls
+                .get_direction(input)
+                .filter(|&direction| movement_ruleset.allows(direction))
+        })
+        .catch_escape_or_start()
+        .overlay(
+            render_state(|state: &State, ctx, fb| state.render(CURSOR_COLOUR, ctx, fb)),
+            10,
+        )
+        .and_then(|direction_or_err| {
+            on_state(move |state: &mut State| {
+                state.context_message = None;
+                match direction_or_err {
+                    Err(_escape_or_start) => witness.cancel(),
+                    Ok(direction) => {
+                        let (game, config) = state.game_mut_config();
+                        let (witness, result) = witness.commit(game, direction, config);
+                        state.context_message = result.err().map(action_error_message);
+                        witness
+                    }
+                }
+            })
+        })
+    })
+}
+
 fn try_get_ranged_weapon(witness: witness::GetRangedWeapon) -> AppCF<Witness> {
     on_state_then(move |state: &mut State| {
         let num_weapon_slots = if state.player_has_third_weapon_slot() {
@@ -1037,6 +1759,16 @@ fn try_get_melee_weapon(witness: witness::GetMeleeWeapon) -> AppCF<Witness> {
 }
 
 fn fire_weapon(witness: witness::FireWeapon) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        if state.game_inner().free_aim() {
+            aim_weapon(witness)
+        } else {
+            fire_weapon_in_direction(witness)
+        }
+    })
+}
+
+fn fire_weapon_in_direction(witness: witness::FireWeapon) -> AppCF<Witness> {
     on_state_then(move |state: &mut State| {
         state.context_message = Some(StyledString {
             string: format!(
@@ -1047,17 +1779,101 @@ fn fire_weapon(witness: witness::FireWeapon) -> AppCF<Witness> {
                 .with_bold(true)
                 .with_foreground(Rgba32::hex_rgb(0xFF0000)),
         });
-        on_input_state(move |input, state: &mut State| state.controls.get_direction(input))
+        on_input_state(move |input, state: &mut State| state.allowed_direction(input))
             .catch_escape_or_start()
-            .overlay(GameExamineWithMouseComponent, 10)
+            .overlay(
+                render_state(|state: &State, ctx, fb| state.render(CURSOR_COLOUR, ctx, fb)),
+                10,
+            )
             .and_then(|direction_or_err| {
                 on_state(move |state: &mut State| {
                     state.context_message = None;
                     match direction_or_err {
                         Err(_escape_or_start) => witness.cancel(),
                         Ok(direction) => {
+                            let target = state.game_inner().player_coord() + direction.coord();
+                            let (game, config) = state.game_mut_config();
+                            let (witness, result) = witness.commit(game, target, config);
+                            state.context_message = result.err().map(action_error_message);
+                            witness
+                        }
+                    }
+                })
+            })
+    })
+}
+
+fn aim_weapon(witness: witness::FireWeapon) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        let slot = witness.slot();
+        state.context_message = Some(StyledString {
+            string: format!(
+                "Aim weapon {} and press {} or enter to fire (escape/start to cancel)",
+                slot.number(),
+                slot.number(),
+            ),
+            style: Style::plain_text()
+                .with_bold(true)
+                .with_foreground(Rgba32::hex_rgb(0xFF0000)),
+        });
+        let game = state.game_inner();
+        state.cursor = Some(
+            game.nearest_visible_enemy_coord()
+                .unwrap_or_else(|| game.player_coord()),
+        );
+        state.targeting = Some(Aim::Weapon(slot));
+        cf(GameTargetingComponent {
+            aim: Aim::Weapon(slot),
+        })
+        .catch_escape_or_start()
+        .and_then(|target_or_err| {
+            on_state(move |state: &mut State| {
+                state.context_message = None;
+                state.cursor = None;
+                state.targeting = None;
+                match target_or_err {
+                    Err(_escape_or_start) => witness.cancel(),
+                    Ok(target) => {
+                        let (game, config) = state.game_mut_config();
+                        let (witness, result) = witness.commit(game, target, config);
+                        state.context_message = result.err().map(action_error_message);
+                        witness
+                    }
+                }
+            })
+        })
+    })
+}
+
+fn throw_grenade(witness: witness::ThrowGrenade) -> AppCF<Witness> {
+    on_state_then(move |state: &mut State| {
+        state.context_message = Some(StyledString {
+            string: "Aim grenade and press t or enter to throw (escape/start to cancel)"
+                .to_string(),
+            style: Style::plain_text()
+                .with_bold(true)
+                .with_foreground(Rgba32::hex_rgb(0xFF0000)),
+        });
+        let game = state.game_inner();
+        state.cursor = Some(
+            game.nearest_visible_enemy_coord()
+                .unwrap_or_else(|| game.player_coord()),
+        );
+        state.targeting = Some(Aim::Grenade);
+        cf(GameTargetingComponent { aim: Aim::Grenade })
+            .catch_escape_or_start()
+            .and_then(|target_or_err| {
+                on_state(move |state: &mut State| {
+                    state.context_message = None;
+                    state.cursor = None;
+                    state.targeting = None;
+                    match target_or_err {
+                        Err(_escape_or_start) => witness.cancel(),
+                        Ok(target) => {
                             let (game, config) = state.game_mut_config();
-                            witness.commit(game, direction, config)
+                            let (witness, result) = witness.commit(game, target, config);
+                            state.context_message = result.err().map(action_error_message);
+                            witness
                         }
                     }
                 })
@@ -1147,6 +1963,7 @@ fn main_menu_loop() -> AppCF<MainMenuOutput> {
 #[derive(Clone)]
 enum PauseMenuEntry {
     Resume,
+    Inventory,
     SaveQuit,
     Save,
     NewGame,
@@ -1168,6 +1985,8 @@ fn pause_menu() -> AppCF<PauseMenuEntry> {
             builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
         };
         add_item(Resume, "Resume", 'r');
+        // gamepads have no button left over for the inventory
+        add_item(Inventory, "Inventory", 'i');
         add_item(SaveQuit, "Save and Quit", 'q');
         add_item(Save, "Save", 's');
         add_item(NewGame, "New Game", 'n');
@@ -1192,6 +2011,15 @@ fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
             move |running, entry_or_escape| match entry_or_escape {
                 Ok(entry) => match entry {
                     Resume => break_(PauseOutput::ContinueGame { running }),
+                    Inventory => on_state(|state: &mut State| {
+                        let (witness, action_result) =
+                            running.inventory(&state.instance.as_ref().unwrap().game);
+                        if let Err(action_error) = action_result {
+                            state.context_message = Some(action_error_message(action_error));
+                        }
+                        PauseOutput::OpenInventory { witness }
+                    })
+                    .break_(),
                     SaveQuit => on_state(|state: &mut State| {
                         state.save_instance(running);
                         PauseOutput::Quit
@@ -1222,6 +2050,7 @@ fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
 
 enum PauseOutput {
     ContinueGame { running: witness::Running },
+    OpenInventory { witness: Witness },
     MainMenu,
     Quit,
 }
@@ -1245,6 +2074,8 @@ fn pause(running: witness::Running) -> AppCF<PauseOutput> {
 enum OptionsMenuEntry {
     MusicVolume,
     SfxVolume,
+    Movement,
+    Aiming,
     Back,
 }
 struct OptionsMenuComponent {
@@ -1270,13 +2101,47 @@ impl Component for OptionsMenuComponent {
             style,
         }
         .render(&(), ctx.add_offset(Coord { x: x_offset, y: 1 }), fb);
+        let movement = match state.config.movement_ruleset {
+            MovementRuleset::Cardinal => "4-way",
+            MovementRuleset::EightDirection => "8-way",
+        };
+        StyledString {
+            string: format!("< {} >", movement),
+            style,
+        }
+        .render(&(), ctx.add_offset(Coord { x: x_offset, y: 2 }), fb);
+        let aiming = if state.config.free_aim {
+            "free"
+        } else {
+            "locked"
+        };
+        StyledString {
+            string: format!("< {} >", aiming),
+            style,
+        }
+        .render(&(), ctx.add_offset(Coord { x: x_offset, y: 3 }), fb);
     }
 
     fn update(&mut self, state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
-        let mut update_volume = |volume_delta: f32| {
+        let mut update_option = |volume_delta: f32| {
             let volume = match self.menu.selected() {
                 OptionsMenuEntry::MusicVolume => &mut state.config.music_volume,
                 OptionsMenuEntry::SfxVolume => &mut state.config.sfx_volume,
+                OptionsMenuEntry::Movement => {
+                    // only takes effect when a new game is started
+                    state.config.movement_ruleset = match state.config.movement_ruleset {
+                        MovementRuleset::Cardinal => MovementRuleset::EightDirection,
+                        MovementRuleset::EightDirection => MovementRuleset::Cardinal,
+                    };
+                    state.save_config();
+                    return;
+                }
+                OptionsMenuEntry::Aiming => {
+                    // only takes effect when a new game is started
+                    state.config.free_aim = !state.config.free_aim;
+                    state.save_config();
+                    return;
+                }
                 OptionsMenuEntry::Back => return,
             };
             *volume = (*volume + volume_delta).clamp(0., 1.);
@@ -1287,8 +2152,8 @@ impl Component for OptionsMenuComponent {
         };
         if let Some(input_policy) = event.input_policy() {
             match input_policy {
-                InputPolicy::Left => update_volume(-0.05),
-                InputPolicy::Right => update_volume(0.05),
+                InputPolicy::Left => update_option(-0.05),
+                InputPolicy::Right => update_option(0.05),
                 InputPolicy::Select => {
                     // prevent hitting enter on a menu option from closing the menu
                     if OptionsMenuEntry::Back != *self.menu.selected() {
@@ -1315,6 +2180,8 @@ fn options_menu() -> AppCF<()> {
     };
     add_item(&mut builder, MusicVolume, "Music Volume:");
     add_item(&mut builder, SfxVolume, "SFX Volume:");
+    add_item(&mut builder, Movement, "Movement:");
+    add_item(&mut builder, Aiming, "Aiming:");
     builder.add_space_mut();
     add_item(&mut builder, Back, "Back");
     let menu = builder.build();
@@ -1415,6 +2282,13 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
                     Witness::Upgrade(upgrade) => {
                         try_upgrade_component(upgrade).map(Playing).continue_()
                     }
+                    Witness::Vendor(vendor) => vendor_component(vendor).map(Playing).continue_(),
+                    Witness::Terminal(terminal) => {
+                        terminal_component(terminal).map(Playing).continue_()
+                    }
+                    Witness::Workbench(workbench) => {
+                        workbench_component(workbench).map(Playing).continue_()
+                    }
                     Witness::GetRangedWeapon(get_ranged_weapon) => {
                         try_get_ranged_weapon(get_ranged_weapon)
                             .map(Playing)
@@ -1428,6 +2302,20 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
                     Witness::FireWeapon(fire_weapon_witness) => {
                         fire_weapon(fire_weapon_witness).map(Playing).continue_()
                     }
+                    Witness::Reload(reload_witness) => {
+                        reload_component(reload_witness).map(Playing).continue_()
+                    }
+                    Witness::Shove(shove_witness) => {
+                        shove_component(shove_witness).map(Playing).continue_()
+                    }
+                    Witness::Inventory(inventory_witness) => inventory_component(inventory_witness)
+                        .map(Playing)
+                        .continue_(),
+                    Witness::ThrowGrenade(throw_grenade_witness) => {
+                        throw_grenade(throw_grenade_witness)
+                            .map(Playing)
+                            .continue_()
+                    }
                     Witness::GameOver(game_over_witness) => game_over(game_over_witness)
                         .map_val(|| MainMenu)
                         .continue_(),
@@ -1443,6 +2331,9 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
                     PauseOutput::ContinueGame { running } => {
                         LoopControl::Continue(Playing(running.into_witness()))
                     }
+                    PauseOutput::OpenInventory { witness } => {
+                        LoopControl::Continue(Playing(witness))
+                    }
                     PauseOutput::MainMenu => LoopControl::Continue(MainMenu),
                     PauseOutput::Quit => LoopControl::Break(()),
                 }),
@@ -42,6 +42,8 @@ pub fn app(
         omniscient: if omniscient { Config::OMNISCIENT } else { None },
         demo: false,
         debug: false,
+        movement_ruleset: Default::default(),
+        free_aim: false,
     };
     let (game_loop_data, initial_state) =
         game_loop::GameLoopData::new(config, storage, initial_rng_seed, audio_player, new_game);
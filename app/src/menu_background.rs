@@ -16,6 +16,8 @@ impl MenuBackground {
             omniscient: Some(Omniscient),
             demo: true,
             debug: false,
+            movement_ruleset: Default::default(),
+            free_aim: false,
         };
         let game = Game::new(&config, rng);
         let stars = Stars::new(rng);
@@ -68,31 +68,114 @@ pub fn help(width: u32) -> AppCF<()> {
         t("Each weapon has a DMG(♥) and PEN(♦) stat, and each enemy has heatlh(♥) and armour(♦). "),
         t("If an enemy is hit with a weapon that has a higher PEN than their armour, their health is "),
         t("reduced by the weapon's DMG. If a projectile's PEN exceeds an enemy's armour, it continues "),
-        t("on its path with its PEN reduced by the enemy's armour. If it hits the hull, it has a "),
-        t("chance to breach the hull (its HULL PEN stat).\n\n"),
+        t("on its path with its PEN reduced by the enemy's armour. If it hits the hull, it damages "),
+        t("the hull in proportion to its HULL PEN stat. Damaged walls and windows crack before they "),
+        t("give way, and cracked sections next to a breach give way along with it. "),
+        t("Windows are especially fragile, and damage the hull around them when they shatter. "),
+        t("Characters turn to face the direction they move in. Tanks are heavily armoured at the "),
+        t("front but have thinner armour behind, so it pays to get around them or knock them back. "),
+        t("Any hit has a small chance to be a critical hit which does double damage, and this "),
+        t("chance is raised by upgrading accuracy. "),
+        t("Few weapons can get through the armour of the guardian on the final floor, but its "),
+        t("reactor is exposed on its back (shown in green), where any hit does double damage.\n\n"),
+        b("Melee Weapons\n"),
+        t("Walking into an enemy attacks it with your melee weapon. A crowbar never wears out and "),
+        t("wedges open any door you open with it so it can't close again. A shock baton stuns what "),
+        t("it hits. A fire axe hacks at any wall or window you walk into and can breach the hull. "),
+        t("Chainsaws, batons and axes have limited uses, after which you're back to bare hands. "),
+        t("Any enemy short of a tank can be shoved one cell away, which takes a turn and is a good "),
+        t("way to push them out into the vacuum.\n\n"),
+        b("Ammo\n"),
+        t("Ranged weapons are no longer refilled when you descend. Shells, bullets, slugs and "),
+        t("energy cells are found around the station and are walked over to pick them up. Spare "),
+        t("ammo is shown after your weapon's magazine, and reloading takes a turn. Firing a weapon "),
+        t("with an empty magazine reloads it instead if you have spare ammo for it.\n\n"),
+        b("Vendors\n"),
+        t("Each floor has a vendor terminal with its own stock of weapons, supplies and ammo. "),
+        t("Walk into it to spend credit. Supplies go straight into your inventory and ammo into "),
+        t("your reserve, while a bought weapon is left at your feet to be picked up.\n\n"),
+        b("Workbenches\n"),
+        t("Walk into a workbench to spend credit modifying a weapon you're carrying. Mods can raise "),
+        t("PEN, halve HULL PEN, extend the magazine, or add the ability offered by that workbench. "),
+        t("Each kind of mod can be fitted to a weapon once, and modified stats are highlighted in "),
+        t("yellow. Mods are lost if the weapon is replaced.\n\n"),
+        b("Survivors\n"),
+        t("Stranded crew members follow you once they've seen you. They can't fight, and enemies "),
+        t("will attack them if they can't see you. Walk into a crew member to swap places. Each "),
+        t("one within two cells of you when you take the stairs is rescued for $3 of credit.\n\n"),
+        b("Vaults\n"),
+        t("Some floors have a vault room full of loot behind a locked door, marked in red. A "),
+        t("keycard opens it, and one is either lying somewhere on that floor or carried by an "),
+        t("enemy who drops it when killed. Without a keycard, keep walking into the door to hack "),
+        t("the lock, which takes 6 turns and makes some noise. A crowbar forces it open at once.\n\n"),
+        b("Security Terminals\n"),
+        t("Every floor but the last has a security terminal which runs a single command before "),
+        t("shutting down. It can unlock every locked door on the floor, vent a room you've seen by "),
+        t("blowing out its windows and hurting the hostiles inside, switch off the lights so "),
+        t("enemies can only spot you up close, or reveal where every enemy on the floor is.\n\n"),
+        b("Lighting\n"),
+        t("Wall lights illuminate the station while it has power. A light can be shot out, and "),
+        t("some floors suffer a blackout where only your own light and muzzle flashes can be seen "),
+        t("by. In the dark, enemies can only spot you up close. A flashlight can be found early on "),
+        t("which lights a cone in the direction you're facing, but enemies can see it from afar. "),
+        t("Its battery drains while it's on and recharges on each new floor.\n\n"),
         b("Hull Breaches\n"),
         t("If the hull is breached the air is sucked out of connected areas of the station. "),
         t("For several turns, characters and items in connected areas are pulled towards the breach. "),
         t("This is indicated by a red light. "),
+        t("Light things are pulled further, heavy things barely move, "),
+        t("and anyone standing next to a door can hold on to it. "),
         t("After the air is drained, the light will turn blue indicating vacuum. "),
         t("Your oxygen will start decreasing, and if it runs out then your health will start decreasing "),
-        t("until you get back into a pressurised area.\n\n"),
+        t("until you get back into a pressurised area. "),
+        t("Wearing magnetic boots lets you walk along the outside of the hull, "),
+        t("but there's no air out there. "),
+        t("Medkits, oxygen canisters, grenades and repair kits can be picked up and carried in your "),
+        t("inventory, which holds up to 6 items. Repair kits restore damaged walls, doors and "),
+        t("windows next to you. "),
+        t("Grenades can be thrown up to 6 cells and explode 3 turns after landing. "),
+        t("Their blast destroys any wall, door or window right next to it, so they can be used "),
+        t("to breach the hull deliberately.\n\n"),
+        b("Status Effects\n"),
+        t("Some weapons and enemies inflict status effects which wear off after a few turns. "),
+        t("Burning characters lose health every turn until the fire burns out or they reach a vacuum. "),
+        t("Bleeding characters lose health every second turn. "),
+        t("Stunned characters lose their next turn, and slowed characters only move every second turn.\n\n"),
+        b("Movement\n"),
+        t("By default you and the station's inhabitants can only move and shoot in the four "),
+        t("cardinal directions. Diagonal movement and shooting can be enabled in the options "),
+        t("menu, and applies from the next new game. Nobody can squeeze diagonally between two "),
+        t("walls. Free aiming can also be enabled in the options menu, letting ranged weapons be "),
+        t("aimed at any cell you can see. While aiming, the cursor starts on the nearest visible "),
+        t("enemy, the path of the shot is highlighted, and any wall, door or window the shot "),
+        t("would break through is shown in red.\n\n"),
         b("Default Keyboard Controls\n"),
-        t("Movement/Aim: Arrows/WASD/HJKL\n"),
+        t("Movement/Aim: Arrows/WASD/HJKL/Numpad 4, 6 and 8\n"),
+        t("Diagonal Movement/Aim: YUBN/Home/PgUp/End/PgDn/Numpad 7 and 9\n"),
+        t("With num lock on, numpad 1-3 fire weapons, so turn it off to move down\n"),
         t("Cancel Aim: Escape\n"),
-        t("Wait: Space\n"),
+        t("Wait: Space/Numpad 5\n"),
         t("Examine: X\n"),
-        t("Get Weapon: G\n"),
-        t("Fire Ranged Weapon: 1-3\n\n"),
+        t("Get Item: G\n"),
+        t("Inventory: I\n"),
+        t("Reload: R\n"),
+        t("Shove: F\n"),
+        t("Toggle Flashlight: E\n"),
+        t("Fire/Aim Ranged Weapon: 1-3\n"),
+        t("Aim Grenade: T\n"),
+        t("Fire/Throw At Target: Enter/1-3/T/Left Click\n\n"),
         b("Default Gamepad Controls\n"),
         t("Movement/Aim: D-Pad\n"),
         t("Cancel Aim: Select\n"),
         t("Wait: Select\n"),
         t("Examine: Right Bumper\n"),
-        t("Get Weapon: Y/Triangle\n"),
-        t("Fire Ranged Weapon Slot 1: X/Square\n"),
-        t("Fire Ranged Weapon Slot 2: A/Cross\n"),
-        t("Fire Ranged Weapon Slot 2: B/Circle\n"),
+        t("Get Item: Y/Triangle\n"),
+        t("Aim Grenade: Left Bumper\n"),
+        t("Inventory: Start, then Inventory\n"),
+        t("Aim/Fire Ranged Weapon Slot 1: X/Square\n"),
+        t("Aim/Fire Ranged Weapon Slot 2: A/Cross\n"),
+        t("Aim/Fire Ranged Weapon Slot 2: B/Circle\n"),
+        t("Reload: Fire an empty weapon\n"),
         f("\n\n\n\n\nPress any key..."),
     ])
 }
@@ -1,9 +1,11 @@
-use crate::colours;
+use crate::{colours, ui};
 use gridbugs::{
     chargrid::core::prelude::*,
     coord_2d::{Axis, Coord, Size},
 };
-use orbital_decay_game::{EntityTile, Game, Tile, ToRenderEntity, VisibilityCell};
+use orbital_decay_game::{
+    AmmoType, Direction, EntityTile, Game, Tile, ToRenderEntity, VisibilityCell,
+};
 
 struct StrStyle(Style);
 impl StrStyle {
@@ -40,7 +42,7 @@ pub fn render_3x3_from_visibility(
 ) {
     let ctx = ctx.add_offset(coord * 3);
     let mut render_tile = |entity, tile, ctx| match tile {
-        Tile::Wall => {
+        Tile::Wall | Tile::WallCracked => {
             let below = coord + Coord::new(0, 1);
             if let Some(render_cell) = game.visibility_grid().get_cell(below) {
                 if render_cell.tile_layers().feature.is_some() {
@@ -51,11 +53,32 @@ pub fn render_3x3_from_visibility(
             } else {
                 wall_front(ctx, fb);
             }
+            if tile == Tile::WallCracked {
+                cracks(ctx, fb);
+            }
         }
+        Tile::WallLight => wall_light(game.lights_on(), ctx, fb),
+        Tile::WallLightBroken => wall_light(false, ctx, fb),
         Tile::WallText0 => wall_front_0(ctx, fb),
         Tile::WallText1 => wall_front_1(ctx, fb),
         Tile::WallText2 => wall_front_2(ctx, fb),
         Tile::WallText3 => wall_front_3(ctx, fb),
+        Tile::WallText0Cracked => {
+            wall_front_0(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText1Cracked => {
+            wall_front_1(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText2Cracked => {
+            wall_front_2(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText3Cracked => {
+            wall_front_3(ctx, fb);
+            cracks(ctx, fb);
+        }
         Tile::Floor => floor(ctx, fb),
         Tile::FuelText0 => fuel_text_0(ctx, fb),
         Tile::FuelText1 => fuel_text_1(ctx, fb),
@@ -66,10 +89,21 @@ pub fn render_3x3_from_visibility(
             window_y(game.contains_floor(below), ctx, fb);
         }
         Tile::Window(Axis::X) => window_x(ctx, fb),
+        Tile::WindowCracked(Axis::Y) => {
+            let below = coord + Coord::new(0, 1);
+            window_y(game.contains_floor(below), ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WindowCracked(Axis::X) => {
+            window_x(ctx, fb);
+            cracks(ctx, fb);
+        }
         Tile::DoorOpen(Axis::X) => door_open_x(ctx, fb),
         Tile::DoorOpen(Axis::Y) => door_open_y(ctx, fb),
         Tile::DoorClosed(Axis::X) => door_closed_x(ctx, fb),
         Tile::DoorClosed(Axis::Y) => door_closed_y(ctx, fb),
+        Tile::DoorLocked(Axis::X) => door_locked_x(ctx, fb),
+        Tile::DoorLocked(Axis::Y) => door_locked_y(ctx, fb),
         Tile::Stairs => stairs(ctx, fb),
         Tile::Bullet => bullet(ctx, fb),
         Tile::Zombie => {
@@ -97,12 +131,34 @@ pub fn render_3x3_from_visibility(
                 tank(&entity, ctx, fb);
             }
         }
+        Tile::Drone => {
+            if let Some(entity) = game.to_render_entity(entity) {
+                drone(&entity, ctx, fb);
+            }
+        }
+        Tile::Guardian(facing) => {
+            if let Some(entity) = game.to_render_entity(entity) {
+                guardian(&entity, facing, ctx, fb);
+            }
+        }
+        Tile::Survivor => {
+            if let Some(entity) = game.to_render_entity(entity) {
+                survivor(&entity, ctx, fb);
+            }
+        }
         Tile::Credit1 => credit1(ctx, fb),
         Tile::Credit2 => credit2(ctx, fb),
         Tile::Upgrade => upgrade(ctx, fb),
+        Tile::Workbench => workbench(ctx, fb),
+        Tile::Vendor => vendor(ctx, fb),
+        Tile::Terminal => terminal(ctx, fb),
+        Tile::TerminalOffline => terminal_offline(ctx, fb),
         Tile::Map => map(ctx, fb),
         Tile::MapLocked => map_locked(ctx, fb),
         Tile::Chainsaw => chainsaw(ctx, fb),
+        Tile::Crowbar => crowbar(ctx, fb),
+        Tile::ShockBaton => shock_baton(ctx, fb),
+        Tile::FireAxe => fire_axe(ctx, fb),
         Tile::Shotgun => shotgun(ctx, fb),
         Tile::Railgun => railgun(ctx, fb),
         Tile::Rifle => rifle(ctx, fb),
@@ -110,6 +166,14 @@ pub fn render_3x3_from_visibility(
         Tile::Oxidiser => oxidiser(ctx, fb),
         Tile::LifeStealer => life_stealer(ctx, fb),
         Tile::Medkit => medkit(ctx, fb),
+        Tile::OxygenCanister => oxygen_canister(ctx, fb),
+        Tile::RepairKit => repair_kit(ctx, fb),
+        Tile::Ammo(ammo_type) => ammo(ammo_type, ctx, fb),
+        Tile::MagneticBoots => magnetic_boots(ctx, fb),
+        Tile::Keycard => keycard(ctx, fb),
+        Tile::Flashlight => flashlight(ctx, fb),
+        Tile::Grenade => grenade(ctx, fb),
+        Tile::LiveGrenade => live_grenade(ctx, fb),
     };
     let tile_layers = visibility_cell.tile_layers();
     if let Some(EntityTile { entity, tile }) = tile_layers.floor {
@@ -135,7 +199,7 @@ pub fn render_3x3_from_visibility_remembered(
 ) {
     let ctx = ctx.add_offset(coord * 3);
     let mut render_tile = |tile, ctx| match tile {
-        Tile::Wall => {
+        Tile::Wall | Tile::WallCracked => {
             let below = coord + Coord::new(0, 1);
             if let Some(render_cell) = game.visibility_grid().get_cell(below) {
                 if render_cell.tile_layers().feature.is_some() {
@@ -146,11 +210,32 @@ pub fn render_3x3_from_visibility_remembered(
             } else {
                 wall_front(ctx, fb);
             }
+            if tile == Tile::WallCracked {
+                cracks(ctx, fb);
+            }
         }
+        Tile::WallLight => wall_light(game.lights_on(), ctx, fb),
+        Tile::WallLightBroken => wall_light(false, ctx, fb),
         Tile::WallText0 => wall_front_0(ctx, fb),
         Tile::WallText1 => wall_front_1(ctx, fb),
         Tile::WallText2 => wall_front_2(ctx, fb),
         Tile::WallText3 => wall_front_3(ctx, fb),
+        Tile::WallText0Cracked => {
+            wall_front_0(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText1Cracked => {
+            wall_front_1(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText2Cracked => {
+            wall_front_2(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText3Cracked => {
+            wall_front_3(ctx, fb);
+            cracks(ctx, fb);
+        }
         Tile::Floor => floor(ctx, fb),
         Tile::FuelText0 => fuel_text_0(ctx, fb),
         Tile::FuelText1 => fuel_text_1(ctx, fb),
@@ -161,23 +246,44 @@ pub fn render_3x3_from_visibility_remembered(
             window_y(game.contains_floor(below), ctx, fb);
         }
         Tile::Window(Axis::X) => window_x(ctx, fb),
+        Tile::WindowCracked(Axis::Y) => {
+            let below = coord + Coord::new(0, 1);
+            window_y(game.contains_floor(below), ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WindowCracked(Axis::X) => {
+            window_x(ctx, fb);
+            cracks(ctx, fb);
+        }
         Tile::DoorOpen(Axis::X) => door_open_x(ctx, fb),
         Tile::DoorOpen(Axis::Y) => door_open_y(ctx, fb),
         Tile::DoorClosed(Axis::X) => door_closed_x(ctx, fb),
         Tile::DoorClosed(Axis::Y) => door_closed_y(ctx, fb),
+        Tile::DoorLocked(Axis::X) => door_locked_x(ctx, fb),
+        Tile::DoorLocked(Axis::Y) => door_locked_y(ctx, fb),
         Tile::Stairs => stairs(ctx, fb),
         Tile::Bullet => bullet(ctx, fb),
         Tile::Zombie => (),
         Tile::Skeleton => (),
         Tile::SkeletonRespawn => (),
         Tile::Tank => (),
+        Tile::Drone => (),
+        Tile::Guardian(_) => (),
+        Tile::Survivor => (),
         Tile::Boomer => (),
         Tile::Credit1 => credit1(ctx, fb),
         Tile::Credit2 => credit2(ctx, fb),
         Tile::Upgrade => upgrade(ctx, fb),
+        Tile::Workbench => workbench(ctx, fb),
+        Tile::Vendor => vendor(ctx, fb),
+        Tile::Terminal => terminal(ctx, fb),
+        Tile::TerminalOffline => terminal_offline(ctx, fb),
         Tile::Map => map(ctx, fb),
         Tile::MapLocked => map_locked(ctx, fb),
         Tile::Chainsaw => chainsaw(ctx, fb),
+        Tile::Crowbar => crowbar(ctx, fb),
+        Tile::ShockBaton => shock_baton(ctx, fb),
+        Tile::FireAxe => fire_axe(ctx, fb),
         Tile::Shotgun => shotgun(ctx, fb),
         Tile::Railgun => railgun(ctx, fb),
         Tile::Rifle => rifle(ctx, fb),
@@ -185,6 +291,14 @@ pub fn render_3x3_from_visibility_remembered(
         Tile::Oxidiser => oxidiser(ctx, fb),
         Tile::LifeStealer => life_stealer(ctx, fb),
         Tile::Medkit => medkit(ctx, fb),
+        Tile::OxygenCanister => oxygen_canister(ctx, fb),
+        Tile::RepairKit => repair_kit(ctx, fb),
+        Tile::Ammo(ammo_type) => ammo(ammo_type, ctx, fb),
+        Tile::MagneticBoots => magnetic_boots(ctx, fb),
+        Tile::Keycard => keycard(ctx, fb),
+        Tile::Flashlight => flashlight(ctx, fb),
+        Tile::Grenade => grenade(ctx, fb),
+        Tile::LiveGrenade => (),
     };
     let tile_layers = visibility_cell.tile_layers();
     if let Some(EntityTile { entity: _, tile }) = tile_layers.floor {
@@ -212,18 +326,39 @@ pub fn render_3x3_tile(coord: Coord, tile: Tile, ctx: Ctx, fb: &mut FrameBuffer)
 pub fn render_3x3(entity: &ToRenderEntity, game: &Game, ctx: Ctx, fb: &mut FrameBuffer) {
     let ctx = ctx.add_offset(entity.coord * 3);
     match entity.tile {
-        Tile::Wall => {
+        Tile::Wall | Tile::WallCracked => {
             let below = entity.coord + Coord::new(0, 1);
             if game.contains_wall_like(below) {
                 wall_top(ctx, fb);
             } else {
                 wall_front(ctx, fb);
             }
+            if entity.tile == Tile::WallCracked {
+                cracks(ctx, fb);
+            }
         }
+        Tile::WallLight => wall_light(game.lights_on(), ctx, fb),
+        Tile::WallLightBroken => wall_light(false, ctx, fb),
         Tile::WallText0 => wall_front_0(ctx, fb),
         Tile::WallText1 => wall_front_1(ctx, fb),
         Tile::WallText2 => wall_front_2(ctx, fb),
         Tile::WallText3 => wall_front_3(ctx, fb),
+        Tile::WallText0Cracked => {
+            wall_front_0(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText1Cracked => {
+            wall_front_1(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText2Cracked => {
+            wall_front_2(ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WallText3Cracked => {
+            wall_front_3(ctx, fb);
+            cracks(ctx, fb);
+        }
         Tile::Floor => floor(ctx, fb),
         Tile::FuelText0 => fuel_text_0(ctx, fb),
         Tile::FuelText1 => fuel_text_1(ctx, fb),
@@ -234,10 +369,21 @@ pub fn render_3x3(entity: &ToRenderEntity, game: &Game, ctx: Ctx, fb: &mut Frame
             window_y(game.contains_floor(below), ctx, fb);
         }
         Tile::Window(Axis::X) => window_x(ctx, fb),
+        Tile::WindowCracked(Axis::Y) => {
+            let below = entity.coord + Coord::new(0, 1);
+            window_y(game.contains_floor(below), ctx, fb);
+            cracks(ctx, fb);
+        }
+        Tile::WindowCracked(Axis::X) => {
+            window_x(ctx, fb);
+            cracks(ctx, fb);
+        }
         Tile::DoorOpen(Axis::X) => door_open_x(ctx, fb),
         Tile::DoorOpen(Axis::Y) => door_open_y(ctx, fb),
         Tile::DoorClosed(Axis::X) => door_closed_x(ctx, fb),
         Tile::DoorClosed(Axis::Y) => door_closed_y(ctx, fb),
+        Tile::DoorLocked(Axis::X) => door_locked_x(ctx, fb),
+        Tile::DoorLocked(Axis::Y) => door_locked_y(ctx, fb),
         Tile::Stairs => stairs(ctx, fb),
         Tile::Bullet => bullet(ctx, fb),
         Tile::Zombie => zombie(entity, ctx, fb),
@@ -245,12 +391,22 @@ pub fn render_3x3(entity: &ToRenderEntity, game: &Game, ctx: Ctx, fb: &mut Frame
         Tile::SkeletonRespawn => skeleton_respawn(entity, ctx, fb),
         Tile::Boomer => boomer(entity, ctx, fb),
         Tile::Tank => tank(entity, ctx, fb),
+        Tile::Drone => drone(entity, ctx, fb),
+        Tile::Guardian(facing) => guardian(entity, facing, ctx, fb),
+        Tile::Survivor => survivor(entity, ctx, fb),
         Tile::Credit1 => credit1(ctx, fb),
         Tile::Credit2 => credit2(ctx, fb),
         Tile::Upgrade => upgrade(ctx, fb),
+        Tile::Workbench => workbench(ctx, fb),
+        Tile::Vendor => vendor(ctx, fb),
+        Tile::Terminal => terminal(ctx, fb),
+        Tile::TerminalOffline => terminal_offline(ctx, fb),
         Tile::Map => map(ctx, fb),
         Tile::MapLocked => map_locked(ctx, fb),
         Tile::Chainsaw => chainsaw(ctx, fb),
+        Tile::Crowbar => crowbar(ctx, fb),
+        Tile::ShockBaton => shock_baton(ctx, fb),
+        Tile::FireAxe => fire_axe(ctx, fb),
         Tile::Shotgun => shotgun(ctx, fb),
         Tile::Railgun => railgun(ctx, fb),
         Tile::Rifle => rifle(ctx, fb),
@@ -258,6 +414,14 @@ pub fn render_3x3(entity: &ToRenderEntity, game: &Game, ctx: Ctx, fb: &mut Frame
         Tile::Oxidiser => oxidiser(ctx, fb),
         Tile::LifeStealer => life_stealer(ctx, fb),
         Tile::Medkit => medkit(ctx, fb),
+        Tile::OxygenCanister => oxygen_canister(ctx, fb),
+        Tile::RepairKit => repair_kit(ctx, fb),
+        Tile::Ammo(ammo_type) => ammo(ammo_type, ctx, fb),
+        Tile::MagneticBoots => magnetic_boots(ctx, fb),
+        Tile::Keycard => keycard(ctx, fb),
+        Tile::Flashlight => flashlight(ctx, fb),
+        Tile::Grenade => grenade(ctx, fb),
+        Tile::LiveGrenade => live_grenade(ctx, fb),
     }
 }
 
@@ -340,6 +504,24 @@ pub fn wall_top(ctx: Ctx, fb: &mut FrameBuffer) {
     }
 }
 
+pub fn wall_light(lit: bool, ctx: Ctx, fb: &mut FrameBuffer) {
+    wall_front(ctx, fb);
+    let colour = if lit {
+        colours::WALL_LIGHT
+    } else {
+        colours::WALL_LIGHT_BROKEN
+    };
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('▄')
+            .with_foreground(colour)
+            .with_background(colours::WALL_FRONT),
+    );
+}
+
 pub fn wall_front(ctx: Ctx, fb: &mut FrameBuffer) {
     for offset in Size::new_u16(3, 1).coord_iter_row_major() {
         fb.set_cell_relative_to_ctx(
@@ -382,6 +564,19 @@ pub fn wall_front(ctx: Ctx, fb: &mut FrameBuffer) {
 }
 
 const WALL_TEXT_DEPTH: i8 = 5;
+const CRACK_DEPTH: i8 = 6;
+
+pub fn cracks(ctx: Ctx, fb: &mut FrameBuffer) {
+    let crack = |ch| {
+        RenderCell::default()
+            .with_character(ch)
+            .with_foreground(colours::CRACK)
+            .with_bold(true)
+    };
+    fb.set_cell_relative_to_ctx(ctx, Coord { x: 2, y: 0 }, CRACK_DEPTH, crack('/'));
+    fb.set_cell_relative_to_ctx(ctx, Coord { x: 1, y: 1 }, CRACK_DEPTH, crack('/'));
+    fb.set_cell_relative_to_ctx(ctx, Coord { x: 0, y: 2 }, CRACK_DEPTH, crack('/'));
+}
 
 pub fn wall_front_0(ctx: Ctx, fb: &mut FrameBuffer) {
     wall_front(ctx, fb);
@@ -1016,6 +1211,25 @@ pub fn zombie(entity: &ToRenderEntity, ctx: Ctx, fb: &mut FrameBuffer) {
     );
 }
 
+pub fn survivor(entity: &ToRenderEntity, ctx: Ctx, fb: &mut FrameBuffer) {
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::SURVIVOR)
+            .with_bold(true),
+    )
+    .render("Crw", ctx, fb);
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::SURVIVOR)
+            .with_bold(false),
+    )
+    .render(
+        format!("♥{:02}", entity.hit_points.unwrap().current).as_str(),
+        ctx.add_offset(Coord { x: 0, y: 2 }),
+        fb,
+    );
+}
+
 pub fn skeleton(entity: &ToRenderEntity, ctx: Ctx, fb: &mut FrameBuffer) {
     StrStyle::new(
         Style::new()
@@ -1114,6 +1328,70 @@ pub fn tank(entity: &ToRenderEntity, ctx: Ctx, fb: &mut FrameBuffer) {
     );
 }
 
+pub fn drone(entity: &ToRenderEntity, ctx: Ctx, fb: &mut FrameBuffer) {
+    StrStyle::new(Style::new().with_foreground(colours::DRONE).with_bold(true))
+        .render("Drn", ctx, fb);
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::DRONE)
+            .with_bold(false),
+    )
+    .render(
+        format!("♦{:02}", entity.armour.unwrap().value).as_str(),
+        ctx.add_offset(Coord { x: 0, y: 1 }),
+        fb,
+    );
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::DRONE)
+            .with_bold(false),
+    )
+    .render(
+        format!("♥{:02}", entity.hit_points.unwrap().current).as_str(),
+        ctx.add_offset(Coord { x: 0, y: 2 }),
+        fb,
+    );
+}
+
+pub fn guardian(entity: &ToRenderEntity, facing: Direction, ctx: Ctx, fb: &mut FrameBuffer) {
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::GUARDIAN)
+            .with_bold(true),
+    )
+    .render("Grd", ctx, fb);
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::GUARDIAN)
+            .with_bold(false),
+    )
+    .render(
+        format!("♦{:02}", entity.armour.unwrap().value).as_str(),
+        ctx.add_offset(Coord { x: 0, y: 1 }),
+        fb,
+    );
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::GUARDIAN)
+            .with_bold(false),
+    )
+    .render(
+        format!("♥{:02}", entity.hit_points.unwrap().current).as_str(),
+        ctx.add_offset(Coord { x: 0, y: 2 }),
+        fb,
+    );
+    // highlight the side (or corner, if facing diagonally) of the tile where the exposed reactor
+    // is
+    let weak_point = RenderCell::default().with_background(colours::GUARDIAN_WEAK_POINT);
+    let facing = facing.coord();
+    for coord in Size::new_u16(3, 3).coord_iter_row_major() {
+        let from_centre = coord - Coord { x: 1, y: 1 };
+        if from_centre.x * facing.x + from_centre.y * facing.y < 0 {
+            fb.set_cell_relative_to_ctx(ctx, coord, 1, weak_point);
+        }
+    }
+}
+
 pub fn bullet(ctx: Ctx, fb: &mut FrameBuffer) {
     fb.set_cell_relative_to_ctx(
         ctx,
@@ -1159,6 +1437,80 @@ pub fn upgrade(ctx: Ctx, fb: &mut FrameBuffer) {
     str_style.render("E++", ctx.add_offset(Coord { x: 0, y: 2 }), fb);
 }
 
+pub fn vendor(ctx: Ctx, fb: &mut FrameBuffer) {
+    let str_style = StrStyle::new(
+        Style::new()
+            .with_foreground(colours::CREDIT_FOREGROUND.saturating_scalar_mul_div(2, 1))
+            .with_background(colours::VENDOR_BACKGROUND)
+            .with_bold(true),
+    );
+    str_style.render("$$$", ctx, fb);
+    str_style.render("BUY", ctx.add_offset(Coord { x: 0, y: 1 }), fb);
+    str_style.render("$$$", ctx.add_offset(Coord { x: 0, y: 2 }), fb);
+}
+
+pub fn terminal(ctx: Ctx, fb: &mut FrameBuffer) {
+    let str_style = StrStyle::new(
+        Style::new()
+            .with_foreground(colours::TERMINAL_FOREGROUND)
+            .with_background(colours::TERMINAL_BACKGROUND)
+            .with_bold(true),
+    );
+    str_style.render("[>_", ctx, fb);
+    str_style.render("SEC", ctx.add_offset(Coord { x: 0, y: 1 }), fb);
+    str_style.render("___", ctx.add_offset(Coord { x: 0, y: 2 }), fb);
+}
+
+pub fn terminal_offline(ctx: Ctx, fb: &mut FrameBuffer) {
+    let str_style = StrStyle::new(
+        Style::new()
+            .with_foreground(colours::TERMINAL_BACKGROUND)
+            .with_background(colours::GUN_METAL),
+    );
+    str_style.render("[  ", ctx, fb);
+    str_style.render("SEC", ctx.add_offset(Coord { x: 0, y: 1 }), fb);
+    str_style.render("___", ctx.add_offset(Coord { x: 0, y: 2 }), fb);
+}
+
+pub fn workbench(ctx: Ctx, fb: &mut FrameBuffer) {
+    StrStyle::new(Style::new().with_foreground(colours::WORKBENCH_TOP)).render("▄▄▄", ctx, fb);
+    StrStyle::new(
+        Style::new()
+            .with_foreground(colours::WEAPON_MOD)
+            .with_background(colours::WORKBENCH_TOP)
+            .with_bold(true),
+    )
+    .render("MOD", ctx.add_offset(Coord { x: 0, y: 1 }), fb);
+    StrStyle::new(Style::new().with_foreground(colours::WORKBENCH_LEGS)).render(
+        "▌ ▐",
+        ctx.add_offset(Coord { x: 0, y: 2 }),
+        fb,
+    );
+}
+
+fn door_lock(ctx: Ctx, fb: &mut FrameBuffer) {
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 1 },
+        1,
+        RenderCell::default()
+            .with_character('■')
+            .with_foreground(colours::DOOR_LOCK)
+            .with_background(colours::DOOR)
+            .with_bold(true),
+    );
+}
+
+pub fn door_locked_y(ctx: Ctx, fb: &mut FrameBuffer) {
+    door_closed_y(ctx, fb);
+    door_lock(ctx, fb);
+}
+
+pub fn door_locked_x(ctx: Ctx, fb: &mut FrameBuffer) {
+    door_closed_x(ctx, fb);
+    door_lock(ctx, fb);
+}
+
 pub fn map_locked(ctx: Ctx, fb: &mut FrameBuffer) {
     let str_style = StrStyle::new(
         Style::new()
@@ -1219,6 +1571,93 @@ pub fn chainsaw(ctx: Ctx, fb: &mut FrameBuffer) {
     );
 }
 
+pub fn crowbar(ctx: Ctx, fb: &mut FrameBuffer) {
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 2, y: 0 },
+        0,
+        RenderCell::default()
+            .with_character('┐')
+            .with_foreground(colours::CROWBAR)
+            .with_bold(true),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('/')
+            .with_foreground(colours::CROWBAR)
+            .with_bold(true),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 0, y: 2 },
+        0,
+        RenderCell::default()
+            .with_character('└')
+            .with_foreground(colours::CROWBAR)
+            .with_bold(true),
+    );
+}
+
+pub fn shock_baton(ctx: Ctx, fb: &mut FrameBuffer) {
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 0, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character(' ')
+            .with_background(colours::GUN_METAL),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('═')
+            .with_foreground(colours::GUN_METAL),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 2, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('☼')
+            .with_foreground(colours::SHOCK_BATON)
+            .with_bold(true),
+    );
+}
+
+pub fn fire_axe(ctx: Ctx, fb: &mut FrameBuffer) {
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 2, y: 0 },
+        0,
+        RenderCell::default()
+            .with_character('■')
+            .with_foreground(colours::FIRE_AXE),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('/')
+            .with_foreground(colours::WOOD)
+            .with_bold(true),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 0, y: 2 },
+        0,
+        RenderCell::default()
+            .with_character('/')
+            .with_foreground(colours::WOOD)
+            .with_bold(true),
+    );
+}
+
 pub fn shotgun(ctx: Ctx, fb: &mut FrameBuffer) {
     fb.set_cell_relative_to_ctx(
         ctx,
@@ -1561,3 +2000,211 @@ pub fn medkit(ctx: Ctx, fb: &mut FrameBuffer) {
             .with_background(colours::MEDKIT_TOP),
     );
 }
+
+pub fn keycard(ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(3, 1).coord_iter_row_major() {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord + Coord { x: 0, y: 1 },
+            0,
+            RenderCell::default()
+                .with_character(' ')
+                .with_background(colours::KEYCARD),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 0, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('▪')
+            .with_foreground(colours::GUN_METAL)
+            .with_background(colours::KEYCARD),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 2, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('▬')
+            .with_foreground(colours::GUN_METAL)
+            .with_background(colours::KEYCARD),
+    );
+}
+
+pub fn flashlight(ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(2, 1).coord_iter_row_major() {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord + Coord { x: 0, y: 1 },
+            0,
+            RenderCell::default()
+                .with_character(' ')
+                .with_background(colours::GUN_METAL),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 2, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('▌')
+            .with_foreground(colours::FLASHLIGHT)
+            .with_background(colours::GUN_METAL),
+    );
+}
+
+pub fn magnetic_boots(ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(1, 2).coord_iter_row_major() {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord + Coord { x: 0, y: 1 },
+            0,
+            RenderCell::default()
+                .with_character(' ')
+                .with_background(colours::MAGNETIC_BOOTS),
+        );
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord + Coord { x: 2, y: 1 },
+            0,
+            RenderCell::default()
+                .with_character(' ')
+                .with_background(colours::MAGNETIC_BOOTS),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 2 },
+        0,
+        RenderCell::default()
+            .with_character('▄')
+            .with_foreground(colours::GUN_METAL),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 0 },
+        0,
+        RenderCell::default()
+            .with_bold(true)
+            .with_character('U')
+            .with_foreground(colours::HEALTH),
+    );
+}
+
+pub fn oxygen_canister(ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(1, 2).coord_iter_row_major() {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord + Coord { x: 1, y: 1 },
+            0,
+            RenderCell::default()
+                .with_character(' ')
+                .with_background(colours::OXYGEN),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 0 },
+        0,
+        RenderCell::default()
+            .with_character('▄')
+            .with_foreground(colours::GUN_METAL),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 2 },
+        0,
+        RenderCell::default()
+            .with_bold(true)
+            .with_character('O')
+            .with_foreground(colours::GUN_METAL),
+    );
+}
+
+pub fn ammo(ammo_type: AmmoType, ctx: Ctx, fb: &mut FrameBuffer) {
+    let colour = ui::ammo_type_colour(ammo_type);
+    for x in 0..3 {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord { x, y: 1 },
+            0,
+            RenderCell::default()
+                .with_character('▄')
+                .with_foreground(colour),
+        );
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord { x, y: 2 },
+            0,
+            RenderCell::default()
+                .with_character(' ')
+                .with_background(colours::GUN_METAL),
+        );
+    }
+}
+
+pub fn repair_kit(ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(3, 2).coord_iter_row_major() {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord + Coord { x: 0, y: 1 },
+            0,
+            RenderCell::default()
+                .with_character(' ')
+                .with_background(colours::REPAIR_KIT),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 0 },
+        0,
+        RenderCell::default()
+            .with_character('▄')
+            .with_foreground(colours::GUN_METAL),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 2 },
+        0,
+        RenderCell::default()
+            .with_bold(true)
+            .with_character('=')
+            .with_foreground(colours::GUN_METAL),
+    );
+}
+
+fn grenade_with_cap(cap_colour: Rgba32, ctx: Ctx, fb: &mut FrameBuffer) {
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character(' ')
+            .with_background(colours::GRENADE),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 1, y: 0 },
+        0,
+        RenderCell::default()
+            .with_character('▄')
+            .with_foreground(cap_colour),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord { x: 2, y: 1 },
+        0,
+        RenderCell::default()
+            .with_character('▌')
+            .with_foreground(colours::GUN_METAL),
+    );
+}
+
+pub fn grenade(ctx: Ctx, fb: &mut FrameBuffer) {
+    grenade_with_cap(colours::GUN_METAL, ctx, fb);
+}
+
+pub fn live_grenade(ctx: Ctx, fb: &mut FrameBuffer) {
+    grenade_with_cap(colours::GRENADE_FUSE, ctx, fb);
+}
@@ -4,8 +4,10 @@ use gridbugs::chargrid::{
     text::{StyledString, Text},
 };
 use orbital_decay_game::{
-    player::{self, Player, Weapon, WeaponAbility, WeaponName},
-    CharacterInfo, Enemy, MeleeWeapon, Message, RangedWeapon,
+    perk::{self, PerkRule},
+    player::{self, Player, Weapon, WeaponAbility, WeaponMod, WeaponName},
+    AmmoType, CharacterInfo, Consumable, Enemy, HitPoints, MeleeWeapon, Message, RangedWeapon,
+    StatusEffect, StatusEffects, Ware,
 };
 
 pub fn render_message_log(messages: &[Message], ctx: Ctx, fb: &mut FrameBuffer) {
@@ -33,11 +35,124 @@ pub fn render_message_log(messages: &[Message], ctx: Ctx, fb: &mut FrameBuffer)
                     t(".", plain),
                 ]
             }
+            Message::ModWeapon(weapon, weapon_mod) => {
+                vec![
+                    t("You fit ", plain),
+                    weapon_mod_text(*weapon_mod),
+                    t(" to your ", plain),
+                    weapon_name_text(*weapon),
+                    t(".", plain),
+                ]
+            }
+            Message::Buy(ware) => {
+                vec![t("You buy ", plain), ware_text(*ware), t(".", plain)]
+            }
+            Message::EnemyHitSurvivor(enemy) => {
+                vec![
+                    t("The ", plain),
+                    enemy_text(*enemy),
+                    t(" hits the ", plain),
+                    survivor_text(),
+                    t("!", plain),
+                ]
+            }
+            Message::PryDoor => {
+                vec![t("You wedge the door open.", plain)]
+            }
+            Message::TakeKeycard => {
+                vec![t("You pick up a ", plain), keycard_text(), t(".", plain)]
+            }
+            Message::UnlockDoor => {
+                vec![
+                    t("You swipe a ", plain),
+                    keycard_text(),
+                    t(" and the lock clicks open.", plain),
+                ]
+            }
+            Message::PryLock => {
+                vec![t("You lever the lock open.", plain)]
+            }
+            Message::HackDoor { progress, total } => {
+                vec![t(
+                    &format!("You work at the lock... ({}/{})", progress, total),
+                    plain,
+                )]
+            }
+            Message::DoorHacked => {
+                vec![t("The lock gives way!", plain)]
+            }
+            Message::EnemyDropsKeycard(enemy) => {
+                vec![
+                    t("The ", plain),
+                    enemy_text(*enemy),
+                    t(" drops a ", plain),
+                    keycard_text(),
+                    t(".", plain),
+                ]
+            }
+            Message::TerminalUnlocksDoors => {
+                vec![t("Every lock on this floor disengages.", plain)]
+            }
+            Message::TerminalVentsRoom => {
+                vec![t("The room's windows blow out into space!", plain)]
+            }
+            Message::TerminalLightsOff => {
+                vec![t("The station lights go dark.", plain)]
+            }
+            Message::TerminalRevealsEnemies => {
+                vec![t(
+                    "The security feed shows every hostile on this floor.",
+                    plain,
+                )]
+            }
+            Message::TakeFlashlight => {
+                vec![t("You pick up a ", plain), flashlight_text(), t(".", plain)]
+            }
+            Message::FlashlightBatteryFlat => {
+                vec![
+                    t("Your ", plain),
+                    flashlight_text(),
+                    t(" flickers and dies.", plain),
+                ]
+            }
+            Message::LightShotOut => vec![t("A wall light shatters.", plain)],
+            Message::Blackout => vec![t("The power is out on this floor!", plain)],
+            Message::ShoveEnemy(enemy) => {
+                vec![
+                    t("You shove the ", plain),
+                    enemy_text(*enemy),
+                    t(".", plain),
+                ]
+            }
+            Message::SurvivorDies => {
+                vec![t("The ", plain), survivor_text(), t(" dies.", plain)]
+            }
+            Message::SurvivorAdrift => {
+                vec![
+                    t("The ", plain),
+                    survivor_text(),
+                    t(" falls into the void.", plain),
+                ]
+            }
+            Message::SurvivorsRescued { count, credit } => {
+                let crew = if *count == 1 {
+                    "crew member".to_string()
+                } else {
+                    format!("{} crew members", count)
+                };
+                vec![t(
+                    &format!(
+                        "You escort {} to safety and gain ${} of credit.",
+                        crew, credit
+                    ),
+                    plain,
+                )]
+            }
             Message::PulledByVacuum => {
                 vec![t("You are pulled towards the vacuum of space.", plain)]
             }
             Message::Descend => {
-                vec![t("You descend to the next floor. Ammo refilled!", plain)]
+                vec![t("You descend to the next floor.", plain)]
             }
             Message::Suffocating => {
                 vec![
@@ -60,6 +175,43 @@ pub fn render_message_log(messages: &[Message], ctx: Ctx, fb: &mut FrameBuffer)
             Message::TakeCredit(_) => {
                 vec![]
             }
+            Message::GrenadeExplodes => {
+                vec![t("The grenade explodes!", plain)]
+            }
+            Message::TakeItem(consumable) => {
+                vec![
+                    t("You pick up the ", plain),
+                    consumable_text(*consumable),
+                    t(".", plain),
+                ]
+            }
+            Message::DropItem(consumable) => {
+                vec![
+                    t("You drop the ", plain),
+                    consumable_text(*consumable),
+                    t(".", plain),
+                ]
+            }
+            Message::TakeAmmo(ammo_type, amount) => {
+                vec![
+                    t(&format!("You pick up {} ", amount), plain),
+                    ammo_type_text(*ammo_type),
+                    t(".", plain),
+                ]
+            }
+            Message::Reload(weapon) => {
+                vec![
+                    t("You reload your ", plain),
+                    weapon_name_text(*weapon),
+                    t(".", plain),
+                ]
+            }
+            Message::RefillOxygen => {
+                vec![t("You refill your oxygen.", plain)]
+            }
+            Message::RepairStructures => {
+                vec![t("You patch up the damage around you.", plain)]
+            }
             Message::BoomerExplodes => {
                 vec![
                     t("The ", plain),
@@ -70,6 +222,13 @@ pub fn render_message_log(messages: &[Message], ctx: Ctx, fb: &mut FrameBuffer)
             Message::EnemyHitPlayer(enemy) => {
                 vec![t("The ", plain), enemy_text(*enemy), t(" hits you!", plain)]
             }
+            Message::EnemyShootsPlayer(enemy) => {
+                vec![
+                    t("The ", plain),
+                    enemy_text(*enemy),
+                    t(" shoots you!", plain),
+                ]
+            }
             Message::PlayerHitEnemy { enemy, weapon } => {
                 vec![
                     t("You hit the ", plain),
@@ -111,6 +270,79 @@ pub fn render_message_log(messages: &[Message], ctx: Ctx, fb: &mut FrameBuffer)
                     t(".", plain),
                 ]
             }
+            Message::PutOnMagneticBoots => {
+                vec![
+                    t("You put on the ", plain),
+                    t(
+                        "Magnetic Boots",
+                        bold.with_foreground(colours::MAGNETIC_BOOTS),
+                    ),
+                    t(". You can walk on the outside of the hull.", plain),
+                ]
+            }
+            Message::HitByDebris => {
+                vec![t(
+                    "You are struck by debris flying out of the station!",
+                    plain,
+                )]
+            }
+            Message::GuardianArmourBuckles => {
+                vec![
+                    t("The ", plain),
+                    enemy_text(Enemy::Guardian),
+                    t("'s armour buckles. It starts venting the room!", plain),
+                ]
+            }
+            Message::GuardianOverloads => {
+                vec![
+                    t("The ", plain),
+                    enemy_text(Enemy::Guardian),
+                    t("'s reactor overloads. ", plain),
+                    t(
+                        "The dead begin to stir!",
+                        bold.with_foreground(colours::SKELETON),
+                    ),
+                ]
+            }
+            Message::GuardianVentsRoom => {
+                vec![
+                    t("The ", plain),
+                    enemy_text(Enemy::Guardian),
+                    t(" blows out a window!", plain),
+                ]
+            }
+            Message::GuardianSummons => {
+                vec![
+                    t("The ", plain),
+                    enemy_text(Enemy::Guardian),
+                    t(" raises skeletons from the station's dead.", plain),
+                ]
+            }
+            Message::CriticalHit => vec![t("Critical hit!", bold)],
+            Message::GuardianWeakPointHit => {
+                vec![t(
+                    "You hit the exposed reactor!",
+                    bold.with_foreground(colours::GUARDIAN_WEAK_POINT),
+                )]
+            }
+            Message::PlayerStatusEffect(effect) => {
+                let text = match effect {
+                    StatusEffect::Burning => "You catch fire!",
+                    StatusEffect::Stunned => "You are stunned!",
+                    StatusEffect::Bleeding => "You are bleeding!",
+                    StatusEffect::Slowed => "You are slowed!",
+                };
+                vec![t(text, bold.with_foreground(status_effect_colour(*effect)))]
+            }
+            Message::EnemyStatusEffect(enemy, effect) => {
+                let text = match effect {
+                    StatusEffect::Burning => " catches fire.",
+                    StatusEffect::Stunned => " is stunned.",
+                    StatusEffect::Bleeding => " is bleeding.",
+                    StatusEffect::Slowed => " is slowed.",
+                };
+                vec![t("The ", plain), enemy_text(*enemy), t(text, plain)]
+            }
         };
         Text::from(text).render(&(), ctx.add_y(i as i32), fb);
     }
@@ -127,6 +359,9 @@ pub fn weapon_name_text(weapon_name: WeaponName) -> StyledString {
             "Chainsaw",
             colours::CHAINSAW.saturating_scalar_mul_div(3, 2),
         ),
+        WeaponName::MeleeWeapon(MeleeWeapon::Crowbar) => t("Crowbar", colours::CROWBAR),
+        WeaponName::MeleeWeapon(MeleeWeapon::ShockBaton) => t("Shock Baton", colours::SHOCK_BATON),
+        WeaponName::MeleeWeapon(MeleeWeapon::FireAxe) => t("Fire Axe", colours::FIRE_AXE),
         WeaponName::RangedWeapon(RangedWeapon::Shotgun) => {
             t("Shotgun", colours::WOOD.saturating_scalar_mul_div(3, 2))
         }
@@ -140,6 +375,73 @@ pub fn weapon_name_text(weapon_name: WeaponName) -> StyledString {
     }
 }
 
+pub fn ware_text(ware: Ware) -> StyledString {
+    match ware {
+        Ware::RangedWeapon(ranged_weapon) => {
+            weapon_name_text(WeaponName::RangedWeapon(ranged_weapon))
+        }
+        Ware::Consumable(consumable) => consumable_text(consumable),
+        Ware::Ammo(ammo_type) => {
+            let mut text = ammo_type_text(ammo_type);
+            text.string = format!("{} x{}", text.string, ammo_type.pickup_amount());
+            text
+        }
+    }
+}
+
+pub fn weapon_mod_name(weapon_mod: WeaponMod) -> &'static str {
+    match weapon_mod {
+        WeaponMod::ArmourPiercing => "Armour Piercing Rounds",
+        WeaponMod::Dampener => "Hull Dampener",
+        WeaponMod::ExtendedMagazine => "Extended Magazine",
+        WeaponMod::Ability(WeaponAbility::KnockBack) => "Recoil Booster",
+        WeaponMod::Ability(WeaponAbility::LifeSteal) => "Leech Module",
+        WeaponMod::Ability(WeaponAbility::Oxidise) => "Oxygen Scrubber",
+        WeaponMod::Ability(WeaponAbility::Stun) => "Shock Emitter",
+        WeaponMod::Ability(WeaponAbility::Ignite) => "Incendiary Module",
+        WeaponMod::Ability(WeaponAbility::Slow) => "Cryo Module",
+        WeaponMod::Ability(WeaponAbility::Bleed) => "Serrated Edge",
+        WeaponMod::Ability(WeaponAbility::Pry) => "Pry Bar",
+        WeaponMod::Ability(WeaponAbility::Breach) => "Breaching Head",
+    }
+}
+
+fn weapon_mod_text(weapon_mod: WeaponMod) -> StyledString {
+    StyledString {
+        string: weapon_mod_name(weapon_mod).to_string(),
+        style: Style::new()
+            .with_foreground(colours::WEAPON_MOD)
+            .with_bold(true),
+    }
+}
+
+pub fn ammo_type_name(ammo_type: AmmoType) -> &'static str {
+    match ammo_type {
+        AmmoType::Shells => "Shells",
+        AmmoType::Bullets => "Bullets",
+        AmmoType::Slugs => "Slugs",
+        AmmoType::Cells => "Energy Cells",
+    }
+}
+
+pub fn ammo_type_colour(ammo_type: AmmoType) -> Rgba32 {
+    match ammo_type {
+        AmmoType::Shells => colours::AMMO_SHELLS,
+        AmmoType::Bullets => colours::AMMO_BULLETS,
+        AmmoType::Slugs => colours::AMMO_SLUGS,
+        AmmoType::Cells => colours::AMMO_CELLS,
+    }
+}
+
+fn ammo_type_text(ammo_type: AmmoType) -> StyledString {
+    StyledString {
+        string: ammo_type_name(ammo_type).to_string(),
+        style: Style::new()
+            .with_foreground(ammo_type_colour(ammo_type))
+            .with_bold(true),
+    }
+}
+
 fn enemy_text(enemy: Enemy) -> StyledString {
     let t = |s: &str, c| StyledString {
         string: s.to_string(),
@@ -150,6 +452,60 @@ fn enemy_text(enemy: Enemy) -> StyledString {
         Enemy::Skeleton => t("Skeleton", colours::SKELETON),
         Enemy::Boomer => t("Boomer", colours::BOOMER),
         Enemy::Tank => t("Tank", colours::TANK.saturating_scalar_mul_div(3, 2)),
+        Enemy::Drone => t(
+            "Security Drone",
+            colours::DRONE.saturating_scalar_mul_div(3, 2),
+        ),
+        Enemy::Guardian => t("Reactor Guardian", colours::GUARDIAN_WEAK_POINT),
+    }
+}
+
+fn survivor_text() -> StyledString {
+    StyledString {
+        string: "Crew Member".to_string(),
+        style: Style::new()
+            .with_foreground(colours::SURVIVOR.saturating_scalar_mul_div(3, 2))
+            .with_bold(true),
+    }
+}
+
+fn keycard_text() -> StyledString {
+    StyledString {
+        string: "Keycard".to_string(),
+        style: Style::new()
+            .with_foreground(colours::KEYCARD)
+            .with_bold(true),
+    }
+}
+
+fn flashlight_text() -> StyledString {
+    StyledString {
+        string: "Flashlight".to_string(),
+        style: Style::new()
+            .with_foreground(colours::FLASHLIGHT)
+            .with_bold(true),
+    }
+}
+
+pub fn consumable_name(consumable: Consumable) -> &'static str {
+    match consumable {
+        Consumable::Medkit => "Medkit",
+        Consumable::OxygenCanister => "Oxygen Canister",
+        Consumable::Grenade => "Grenade",
+        Consumable::RepairKit => "Repair Kit",
+    }
+}
+
+pub fn consumable_text(consumable: Consumable) -> StyledString {
+    let colour = match consumable {
+        Consumable::Medkit => colours::HEALTH,
+        Consumable::OxygenCanister => colours::OXYGEN,
+        Consumable::Grenade => colours::GRENADE_FUSE,
+        Consumable::RepairKit => colours::REPAIR_KIT,
+    };
+    StyledString {
+        string: consumable_name(consumable).to_string(),
+        style: Style::new().with_foreground(colour).with_bold(true),
     }
 }
 
@@ -186,11 +542,53 @@ pub fn render_hud(player: &Player, player_info: &CharacterInfo, ctx: Ctx, fb: &m
                 .with_foreground(colours::CREDIT_FOREGROUND)
                 .with_bold(true),
         },
+        StyledString {
+            string: if player.keycards > 0 {
+                format!("  Keycards: {}", player.keycards)
+            } else {
+                String::new()
+            },
+            style: Style::new()
+                .with_foreground(colours::KEYCARD)
+                .with_bold(true),
+        },
+        plain_str("\n"),
+        plain_str("Items: "),
+        StyledString {
+            string: format!("{}/{}", player.inventory.len(), player::INVENTORY_CAPACITY),
+            style: plain.with_bold(true),
+        },
+        StyledString {
+            string: match player.flashlight {
+                Some(flashlight) => format!(
+                    "  Light: {}/{}",
+                    flashlight.battery,
+                    player::FLASHLIGHT_BATTERY
+                ),
+                None => String::new(),
+            },
+            style: Style::new()
+                .with_foreground(match player.flashlight {
+                    Some(flashlight) if flashlight.on => colours::FLASHLIGHT,
+                    _ => colours::WALL_LIGHT_BROKEN,
+                })
+                .with_bold(true),
+        },
         plain_str("\n"),
     ];
     Text::from(text).render(&(), ctx, fb);
-    render_weapon("Melee:", &player.melee_weapon, &player, ctx.add_y(5), fb);
-    let ctx = ctx.add_y(15);
+    if player.magnetic_boots {
+        StyledString {
+            string: "Magnetic Boots".to_string(),
+            style: Style::new()
+                .with_foreground(colours::MAGNETIC_BOOTS)
+                .with_bold(true),
+        }
+        .render(&(), ctx.add_y(4), fb);
+    }
+    render_status_effects(&player_info.status_effects, ctx.add_y(5), fb);
+    render_weapon("Melee:", &player.melee_weapon, &player, ctx.add_y(6), fb);
+    let ctx = ctx.add_y(16);
     for (i, ranged_slot) in player.ranged_weapons.iter().enumerate() {
         if let Some(weapon) = ranged_slot {
             render_weapon(
@@ -211,7 +609,21 @@ pub fn render_hud(player: &Player, player_info: &CharacterInfo, ctx: Ctx, fb: &m
     render_upgrades(player, ctx.add_y(32), fb);
 }
 
-fn weapon_ability_text(weapon_ability: WeaponAbility) -> StyledString {
+pub fn render_boss_hud(hit_points: HitPoints, ctx: Ctx, fb: &mut FrameBuffer) {
+    const BAR_WIDTH: u32 = 15;
+    enemy_text(Enemy::Guardian).render(&(), ctx, fb);
+    let filled = (hit_points.current * BAR_WIDTH).div_ceil(hit_points.max);
+    let bar = (0..BAR_WIDTH)
+        .map(|i| if i < filled { '█' } else { '░' })
+        .collect::<String>();
+    StyledString {
+        string: bar,
+        style: Style::new().with_foreground(colours::HEALTH),
+    }
+    .render(&(), ctx.add_y(1), fb);
+}
+
+pub fn weapon_ability_text(weapon_ability: WeaponAbility) -> StyledString {
     match weapon_ability {
         WeaponAbility::KnockBack => StyledString {
             string: "Knocks Back".to_string(),
@@ -225,9 +637,64 @@ fn weapon_ability_text(weapon_ability: WeaponAbility) -> StyledString {
             string: "Restores Oxygen".to_string(),
             style: Style::new().with_foreground(colours::OXYGEN),
         },
+        WeaponAbility::Stun => StyledString {
+            string: "Stuns".to_string(),
+            style: Style::new().with_foreground(status_effect_colour(StatusEffect::Stunned)),
+        },
+        WeaponAbility::Ignite => StyledString {
+            string: "Sets On Fire".to_string(),
+            style: Style::new().with_foreground(status_effect_colour(StatusEffect::Burning)),
+        },
+        WeaponAbility::Slow => StyledString {
+            string: "Slows".to_string(),
+            style: Style::new().with_foreground(status_effect_colour(StatusEffect::Slowed)),
+        },
+        WeaponAbility::Bleed => StyledString {
+            string: "Causes Bleeding".to_string(),
+            style: Style::new().with_foreground(status_effect_colour(StatusEffect::Bleeding)),
+        },
+        WeaponAbility::Pry => StyledString {
+            string: "Wedges Doors Open".to_string(),
+            style: Style::new().with_foreground(colours::DOOR),
+        },
+        WeaponAbility::Breach => StyledString {
+            string: "Breaches Walls".to_string(),
+            style: Style::new().with_foreground(colours::STRIPE),
+        },
     }
 }
 
+fn status_effect_colour(effect: StatusEffect) -> Rgba32 {
+    match effect {
+        StatusEffect::Burning => colours::BURNING,
+        StatusEffect::Stunned => colours::STUNNED,
+        StatusEffect::Bleeding => colours::BLOOD,
+        StatusEffect::Slowed => colours::SLOWED,
+    }
+}
+
+pub fn status_effect_name(effect: StatusEffect) -> &'static str {
+    match effect {
+        StatusEffect::Burning => "Burning",
+        StatusEffect::Stunned => "Stunned",
+        StatusEffect::Bleeding => "Bleeding",
+        StatusEffect::Slowed => "Slowed",
+    }
+}
+
+fn render_status_effects(status_effects: &StatusEffects, ctx: Ctx, fb: &mut FrameBuffer) {
+    let text = status_effects
+        .iter()
+        .map(|active| StyledString {
+            string: format!("{} ", status_effect_name(active.effect)),
+            style: Style::new()
+                .with_foreground(status_effect_colour(active.effect))
+                .with_bold(true),
+        })
+        .collect::<Vec<_>>();
+    Text::from(text).render(&(), ctx, fb);
+}
+
 fn render_empty_weapon_slot(title: &str, ctx: Ctx, fb: &mut FrameBuffer) {
     let style = Style::new()
         .with_foreground(Rgba32::new_grey(255))
@@ -254,10 +721,24 @@ fn render_weapon(title: &str, weapon: &Weapon, player: &Player, ctx: Ctx, fb: &m
     }
     .render(&(), ctx, fb);
     weapon_name_text(weapon.name).render(&(), ctx.add_y(1), fb);
+    // stats changed by a workbench mod are highlighted
+    let modded = |weapon_mod| {
+        if weapon.mods.contains(&weapon_mod) {
+            plain.with_foreground(colours::WEAPON_MOD)
+        } else {
+            plain
+        }
+    };
     if let Some(ammo) = weapon.ammo.as_ref() {
+        // spare ammunition is shown after the magazine for weapons which can be reloaded
+        let reserve = weapon
+            .name
+            .ammo_type()
+            .map(|ammo_type| format!(" +{}", player.ammo_reserve.get(ammo_type)))
+            .unwrap_or_default();
         StyledString {
-            string: format!("AMMO: {}/{}\n", ammo.current, ammo.max),
-            style: plain,
+            string: format!("AMMO: {}/{}{}\n", ammo.current, ammo.max, reserve),
+            style: modded(WeaponMod::ExtendedMagazine),
         }
         .render(&(), ctx.add_y(2), fb);
     } else {
@@ -269,10 +750,10 @@ fn render_weapon(title: &str, weapon: &Weapon, player: &Player, ctx: Ctx, fb: &m
     }
     StyledString {
         string: format!("PEN(♦): {}\n", weapon.pen),
-        style: plain,
+        style: modded(WeaponMod::ArmourPiercing),
     }
     .render(&(), ctx.add_y(3), fb);
-    let extra = if player.traits.double_damage {
+    let extra = if player.perks.has_rule(PerkRule::DoubleDamage) {
         "x2"
     } else {
         ""
@@ -282,18 +763,28 @@ fn render_weapon(title: &str, weapon: &Weapon, player: &Player, ctx: Ctx, fb: &m
         style: plain,
     }
     .render(&(), ctx.add_y(4), fb);
-    let extra = if player.traits.reduce_hull_pen {
+    let extra = if player.perks.has_rule(PerkRule::HalveHullPen) {
         "/2"
     } else {
         ""
     };
     StyledString {
         string: format!("HULL PEN: {}%{}\n", weapon.hull_pen_percent, extra),
-        style: plain,
+        style: modded(WeaponMod::Dampener),
     }
     .render(&(), ctx.add_y(5), fb);
-    for &ability in weapon.abilities.iter() {
-        weapon_ability_text(ability).render(&(), ctx.add_y(6), fb);
+    for (i, &ability) in weapon.abilities.iter().enumerate() {
+        let ctx = ctx.add_y(6 + i as i32);
+        if weapon.mods.contains(&WeaponMod::Ability(ability)) {
+            StyledString {
+                string: "+".to_string(),
+                style: plain.with_foreground(colours::WEAPON_MOD),
+            }
+            .render(&(), ctx, fb);
+            weapon_ability_text(ability).render(&(), ctx.add_x(1), fb);
+        } else {
+            weapon_ability_text(ability).render(&(), ctx, fb);
+        }
     }
 }
 
@@ -306,26 +797,16 @@ fn render_upgrades(player: &Player, ctx: Ctx, fb: &mut FrameBuffer) {
         string: "Upgrades:".to_string(),
     }
     .render(&(), ctx, fb);
-    let mut upgrades = Vec::new();
-    use player::UpgradeLevel::*;
-    if let Some(level) = player.upgrade_table.toughness {
-        upgrades.push("T1: Strong Back");
-        if level == Level2 {
-            upgrades.push("T2: Hardy");
-        }
-    }
-    if let Some(level) = player.upgrade_table.accuracy {
-        upgrades.push("A1: Careful");
-        if level == Level2 {
-            upgrades.push("A2: Kill Shot");
-        }
-    }
-    if let Some(level) = player.upgrade_table.endurance {
-        upgrades.push("E1: Sure-Footed");
-        if level == Level2 {
-            upgrades.push("E2: Big Lungs");
-        }
-    }
+    // perks are listed in tree order, tagged with the initial of their branch
+    let upgrades = perk::PERK_TREE
+        .iter()
+        .flat_map(|branch| {
+            std::iter::once(branch.root)
+                .chain(branch.children.iter().cloned())
+                .filter(|&perk| player.perks.has(perk))
+                .map(move |perk| format!("{}: {}", &branch.name[..1], perk.spec().name))
+        })
+        .collect::<Vec<_>>();
     if upgrades.is_empty() {
         StyledString {
             style: plain,
@@ -336,7 +817,7 @@ fn render_upgrades(player: &Player, ctx: Ctx, fb: &mut FrameBuffer) {
         for (i, upgrade) in upgrades.into_iter().enumerate() {
             StyledString {
                 style: plain,
-                string: upgrade.to_string(),
+                string: upgrade,
             }
             .render(&(), ctx.add_y(i as i32 + 1), fb);
         }
@@ -1,9 +1,10 @@
 use crate::{
     visibility::Visibility,
-    world::{Disposition, NpcAction, World},
-    Entity,
+    world::{Disposition, Hearing, NpcAction, World},
+    Entity, MovementRuleset,
 };
 use gridbugs::{
+    direction::{CardinalDirection, CardinalDirections, Direction, Directions},
     grid_2d::{Coord, Grid, Size},
     grid_search_cardinal::{
         best::{BestSearch, Context as BestSearchContext, Depth},
@@ -19,9 +20,98 @@ use gridbugs::{
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::{cmp::Reverse, collections::BinaryHeap};
 
 const FLEE_DISTANCE: Distance = 5;
 
+/// Npcs with ranged attacks try to stay at least this far from the player
+const KEEP_DISTANCE: Distance = 4;
+
+/// Npcs are wary of cells within this many steps of vacuum or a decompressing area
+const VACUUM_DANGER_DISTANCE: Distance = 3;
+
+/// How much each step closer to vacuum is weighed against the distance to the player
+const VACUUM_COST_PER_STEP: Distance = 3;
+
+/// Npcs with ranged attacks will go this far out of their way to lure the player near a window
+const WINDOW_LURE_DISTANCE: Distance = 4;
+
+/// Friendly npcs stop following the player once they are this close
+const FOLLOW_DISTANCE: Distance = 2;
+
+/// How many extra steps an npc will take to avoid the routes of other npcs chasing the player
+const MAX_FLANK_DETOUR: usize = 6;
+
+fn vacuum_cost(vacuum_approach: &DistanceMap, coord: Coord) -> Distance {
+    match vacuum_approach.distance(coord) {
+        Some(distance) if distance < VACUUM_DANGER_DISTANCE => {
+            (VACUUM_DANGER_DISTANCE - distance) * VACUUM_COST_PER_STEP
+        }
+        _ => 0,
+    }
+}
+
+/// With the eight direction ruleset, replaces a step towards the goal of a distance map with a
+/// diagonal step if that gets the npc closer to the goal
+fn cut_corner_on_distance_map(
+    movement_ruleset: MovementRuleset,
+    world: &World,
+    coord: Coord,
+    first: CardinalDirection,
+    distance_map: &DistanceMap,
+) -> Direction {
+    if movement_ruleset == MovementRuleset::Cardinal {
+        return first.direction();
+    }
+    let first_distance = match distance_map.distance(coord + first.coord()) {
+        Some(distance) => distance,
+        None => return first.direction(),
+    };
+    let can_enter = WorldCanEnterAvoidNpcs { world };
+    [first.left45(), first.right45()]
+        .into_iter()
+        .map(|ordinal_direction| ordinal_direction.direction())
+        .filter(|&direction| {
+            can_enter.can_enter(coord + direction.coord())
+                && !world.is_diagonal_step_blocked(coord, direction)
+        })
+        .filter_map(|direction| {
+            distance_map
+                .distance(coord + direction.coord())
+                .filter(|&distance| distance < first_distance)
+                .map(|distance| (distance, direction))
+        })
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, direction)| direction)
+        .unwrap_or_else(|| first.direction())
+}
+
+/// With the eight direction ruleset, takes a diagonal step if the first two steps of the path
+/// turn a corner
+fn first_step_of_path(
+    movement_ruleset: MovementRuleset,
+    world: &World,
+    coord: Coord,
+    path: &Path,
+) -> Option<Direction> {
+    let mut nodes = path.iter();
+    let first = nodes.next()?.in_direction;
+    if movement_ruleset == MovementRuleset::EightDirection {
+        if let Some(ordinal_direction) = nodes
+            .next()
+            .and_then(|second| first.combine(second.in_direction))
+        {
+            let direction = ordinal_direction.direction();
+            if (WorldCanEnterAvoidNpcs { world }).can_enter(coord + direction.coord())
+                && !world.is_diagonal_step_blocked(coord, direction)
+            {
+                return Some(direction);
+            }
+        }
+    }
+    Some(first.direction())
+}
+
 fn has_line_of_sight(
     eye: Coord,
     dest: Coord,
@@ -101,6 +191,111 @@ impl LastSeenGrid {
     }
 }
 
+/// Keeps track of the cells around the player which npcs are heading for, and the routes they are
+/// taking to get there, so that a group of npcs surrounds the player rather than approaching in
+/// single file
+#[derive(Serialize, Deserialize)]
+struct Squad {
+    count: u64,
+    claimed: Grid<u64>,
+    claimed_goals: Vec<Coord>,
+}
+
+impl Squad {
+    fn new(size: Size) -> Self {
+        Self {
+            count: 1,
+            claimed: Grid::new_copy(size, 0),
+            claimed_goals: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.count += 1;
+        self.claimed_goals.clear();
+    }
+
+    fn is_claimed(&self, coord: Coord) -> bool {
+        self.claimed.get(coord) == Some(&self.count)
+    }
+
+    fn claim_path(&mut self, path: &Path) {
+        for node in path.iter() {
+            if let Some(cell) = self.claimed.get_mut(node.to_coord) {
+                *cell = self.count;
+            }
+        }
+    }
+}
+
+struct SquadCanEnter<'a> {
+    world: &'a World,
+    squad: &'a Squad,
+}
+
+impl<'a> CanEnter for SquadCanEnter<'a> {
+    fn can_enter(&self, coord: Coord) -> bool {
+        self.world.can_npc_traverse_feature_at_coord(coord)
+            && !self.world.is_npc_at_coord(coord)
+            && !self.squad.is_claimed(coord)
+    }
+    fn can_step(&self, step: Step) -> bool {
+        self.can_enter(step.to_coord)
+    }
+}
+
+/// Like a distance map, but each step costs extra the closer it takes an npc to vacuum or a
+/// decompressing area. Npcs following it will take a longer route to keep clear of a breach, but
+/// will still cross one if the detour would be too long.
+#[derive(Serialize, Deserialize)]
+struct CostMap {
+    grid: Grid<Option<Distance>>,
+}
+
+impl CostMap {
+    fn new(size: Size) -> Self {
+        Self {
+            grid: Grid::new_copy(size, None),
+        }
+    }
+
+    fn clear(&mut self) {
+        for cell in self.grid.iter_mut() {
+            *cell = None;
+        }
+    }
+
+    fn cost(&self, coord: Coord) -> Option<Distance> {
+        self.grid.get(coord).cloned().flatten()
+    }
+
+    fn populate<C: CanEnter>(&mut self, can_enter: &C, goal: Coord, vacuum_approach: &DistanceMap) {
+        self.clear();
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((0, goal.x, goal.y)));
+        while let Some(Reverse((cost, x, y))) = queue.pop() {
+            let coord = Coord::new(x, y);
+            match self.grid.get_mut(coord) {
+                Some(cell @ None) => *cell = Some(cost),
+                _ => continue,
+            }
+            for direction in CardinalDirections {
+                let neighbour_coord = coord + direction.coord();
+                if can_enter.can_enter(neighbour_coord)
+                    && self.grid.get(neighbour_coord) == Some(&None)
+                {
+                    let neighbour_cost = cost + 1 + vacuum_cost(vacuum_approach, neighbour_coord);
+                    queue.push(Reverse((
+                        neighbour_cost,
+                        neighbour_coord.x,
+                        neighbour_coord.y,
+                    )));
+                }
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct BehaviourContext {
     best_search_context: BestSearchContext,
@@ -109,11 +304,20 @@ pub struct BehaviourContext {
     distance_map_search_context: DistanceMapSearchContext,
     player_approach: DistanceMap,
     player_flee: DistanceMap,
+    vacuum_approach: DistanceMap,
+    player_approach_cost: CostMap,
+    window_approach: DistanceMap,
+    survivor_approach: DistanceMap,
+    hearing: Hearing,
+    squad: Squad,
     wander_path: Path,
+    flank_path: Path,
+    step_path: Path,
+    movement_ruleset: MovementRuleset,
 }
 
 impl BehaviourContext {
-    pub fn new(size: Size) -> Self {
+    pub fn new(size: Size, movement_ruleset: MovementRuleset) -> Self {
         Self {
             best_search_context: BestSearchContext::new(size),
             point_to_point_search_context: PointToPointSearchContext::new(size),
@@ -121,10 +325,20 @@ impl BehaviourContext {
             distance_map_search_context: DistanceMapSearchContext::new(size),
             player_approach: DistanceMap::new(size),
             player_flee: DistanceMap::new(size),
+            vacuum_approach: DistanceMap::new(size),
+            player_approach_cost: CostMap::new(size),
+            window_approach: DistanceMap::new(size),
+            survivor_approach: DistanceMap::new(size),
+            hearing: Hearing::new(size),
+            squad: Squad::new(size),
             wander_path: Path::default(),
+            flank_path: Path::default(),
+            step_path: Path::default(),
+            movement_ruleset,
         }
     }
     pub fn update(&mut self, player: Entity, world: &World) {
+        self.update_vacuum(world);
         if let Some(player_coord) = world.entity_coord(player) {
             let can_enter = WorldCanEnterIgnoreCharacters { world };
             self.distance_map_populate_context.add(player_coord);
@@ -136,13 +350,220 @@ impl BehaviourContext {
             self.distance_map_populate_context.add(player_coord);
             self.distance_map_populate_context
                 .populate_flee(&can_enter, 20, &mut self.player_flee);
+            self.player_approach_cost
+                .populate(&can_enter, player_coord, &self.vacuum_approach);
         } else {
             self.player_approach.clear();
             self.player_flee.clear();
+            self.player_approach_cost.clear();
+        }
+        self.update_windows(world);
+        self.update_survivors(world);
+        self.hearing.update(world);
+        self.squad.clear();
+    }
+
+    fn update_vacuum(&mut self, world: &World) {
+        let can_enter = WorldCanEnterIgnoreCharacters { world };
+        let mut any_vacuum = false;
+        for coord in world.size().coord_iter_row_major() {
+            if world.can_npc_traverse_feature_at_coord(coord)
+                && (world.air.has_flow(coord) || !world.air.has_air(coord))
+            {
+                self.distance_map_populate_context.add(coord);
+                any_vacuum = true;
+            }
+        }
+        if !any_vacuum {
+            self.vacuum_approach.clear();
+            return;
+        }
+        self.distance_map_populate_context.populate_approach(
+            &can_enter,
+            VACUUM_DANGER_DISTANCE,
+            &mut self.vacuum_approach,
+        );
+    }
+
+    fn update_windows(&mut self, world: &World) {
+        let can_enter = WorldCanEnterIgnoreCharacters { world };
+        let mut any_window = false;
+        for coord in world.size().coord_iter_row_major() {
+            if world.can_npc_traverse_feature_at_coord(coord) && world.is_next_to_window(coord) {
+                self.distance_map_populate_context.add(coord);
+                any_window = true;
+            }
+        }
+        if any_window {
+            self.distance_map_populate_context.populate_approach(
+                &can_enter,
+                WINDOW_LURE_DISTANCE + 1,
+                &mut self.window_approach,
+            );
+        } else {
+            self.window_approach.clear();
+        }
+    }
+
+    fn update_survivors(&mut self, world: &World) {
+        let can_enter = WorldCanEnterIgnoreCharacters { world };
+        let mut any_survivor = false;
+        for coord in world.friendly_npc_coords() {
+            self.distance_map_populate_context.add(coord);
+            any_survivor = true;
+        }
+        if any_survivor {
+            self.distance_map_populate_context.populate_approach(
+                &can_enter,
+                20,
+                &mut self.survivor_approach,
+            );
+        } else {
+            self.survivor_approach.clear();
+        }
+    }
+
+    /// Chooses a free cell next to the player that no other npc is heading for, and a route there
+    /// which avoids the routes already claimed by other npcs this turn. Npcs are allowed to take a
+    /// longer route to reach the player from a different side, but if the detour is too long
+    /// they'll share a route with another npc.
+    fn flank_player(
+        &mut self,
+        world: &World,
+        coord: Coord,
+        player_coord: Coord,
+    ) -> Option<Direction> {
+        let distance_to_player = self.player_approach.distance(coord)? as usize;
+        let to_player = player_coord - coord;
+        let diagonally_adjacent = to_player.x.abs() == 1 && to_player.y.abs() == 1;
+        if distance_to_player <= 1
+            || (diagonally_adjacent && self.movement_ruleset == MovementRuleset::EightDirection)
+        {
+            // already next to the player
+            return None;
+        }
+        let goal = Directions
+            .into_iter()
+            .filter(|&direction| self.movement_ruleset.allows(direction))
+            .map(|direction| player_coord + direction.coord())
+            .filter(|&goal| {
+                world.can_npc_traverse_feature_at_coord(goal)
+                    && !world.is_npc_at_coord(goal)
+                    && !self.squad.claimed_goals.contains(&goal)
+            })
+            .min_by_key(|&goal| goal.manhattan_distance(coord))?;
+        self.squad.claimed_goals.push(goal);
+        let found_flanking_route = self
+            .point_to_point_search_context
+            .point_to_point_search_path(
+                expand::JumpPoint,
+                &SquadCanEnter {
+                    world,
+                    squad: &self.squad,
+                },
+                coord,
+                goal,
+                &mut self.flank_path,
+            )
+            .is_ok()
+            && self.flank_path.len() <= distance_to_player + MAX_FLANK_DETOUR;
+        if !found_flanking_route {
+            self.point_to_point_search_context
+                .point_to_point_search_path(
+                    expand::JumpPoint,
+                    &WorldCanEnterAvoidNpcs { world },
+                    coord,
+                    goal,
+                    &mut self.flank_path,
+                )
+                .ok()?;
+        }
+        self.squad.claim_path(&self.flank_path);
+        first_step_of_path(self.movement_ruleset, world, coord, &self.flank_path)
+    }
+
+    /// Chooses the first step of the shortest route to the goal which avoids other npcs. Returns
+    /// `Ok(None)` if the npc is already at the goal.
+    fn step_towards(
+        &mut self,
+        world: &World,
+        coord: Coord,
+        goal: Coord,
+    ) -> Result<Option<Direction>, NoPath> {
+        self.point_to_point_search_context
+            .point_to_point_search_path(
+                expand::JumpPoint,
+                &WorldCanEnterAvoidNpcs { world },
+                coord,
+                goal,
+                &mut self.step_path,
+            )?;
+        Ok(first_step_of_path(
+            self.movement_ruleset,
+            world,
+            coord,
+            &self.step_path,
+        ))
+    }
+
+    /// Chooses a step towards the player. Npcs whose shortest route to the player keeps clear of
+    /// vacuum flank the player as normal, otherwise they follow the cheapest route on the
+    /// air-aware cost map, waiting if every neighbouring cell is more costly than their current
+    /// one. Returns `None` if the player can't be reached.
+    fn approach_player(
+        &mut self,
+        world: &World,
+        coord: Coord,
+        player_coord: Coord,
+    ) -> Option<NpcAction> {
+        let distance = self.player_approach.distance(coord)?;
+        match self.player_approach_cost.cost(coord) {
+            Some(cost) if cost > distance => {
+                let can_enter = WorldCanEnterAvoidNpcs { world };
+                let best = Directions
+                    .into_iter()
+                    .filter(|&direction| {
+                        self.movement_ruleset.allows(direction)
+                            && !world.is_diagonal_step_blocked(coord, direction)
+                            && can_enter.can_enter(coord + direction.coord())
+                    })
+                    .filter_map(|direction| {
+                        self.player_approach_cost
+                            .cost(coord + direction.coord())
+                            .map(|cost| (cost, direction))
+                    })
+                    .min_by_key(|&(cost, _)| cost);
+                Some(match best {
+                    Some((best_cost, direction)) if best_cost < cost => NpcAction::Walk(direction),
+                    _ => NpcAction::Wait,
+                })
+            }
+            _ => {
+                let first = self.distance_map_search_context.search_first(
+                    &WorldCanEnterAvoidNpcs { world },
+                    coord,
+                    5,
+                    &self.player_approach,
+                )?;
+                let first = cut_corner_on_distance_map(
+                    self.movement_ruleset,
+                    world,
+                    coord,
+                    first,
+                    &self.player_approach,
+                );
+                Some(NpcAction::Walk(
+                    self.flank_player(world, coord, player_coord)
+                        .unwrap_or(first),
+                ))
+            }
         }
     }
 }
 
+/// How far npcs can see when the station lights are out and the player's flashlight is off
+const DARK_VISION_DISTANCE: vision_distance::Circle = vision_distance::Circle::new_squared(10);
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Agent {
     last_seen_grid: LastSeenGrid,
@@ -159,7 +580,16 @@ enum Behaviour {
         last_seen_player_coord: Coord,
         accurate: bool,
     },
+    Investigate {
+        noise_coord: Coord,
+    },
     Flee,
+    Follow {
+        last_seen_player_coord: Coord,
+    },
+    Hunt {
+        survivor_coord: Coord,
+    },
 }
 
 struct Wander<'a, R> {
@@ -169,6 +599,7 @@ struct Wander<'a, R> {
     min_last_seen_count: u64,
     entity: Entity,
     avoid: bool,
+    vacuum_approach: &'a DistanceMap,
     rng: &'a mut R,
 }
 
@@ -199,7 +630,10 @@ impl<'a, R: Rng> BestSearch for Wander<'a, R> {
                 return false;
             }
             let last_seen_count = last_seen_cell.count;
-            if last_seen_count < self.min_last_seen_count {
+            // it's fine to pass near vacuum on the way somewhere, but don't go there on purpose
+            if last_seen_count < self.min_last_seen_count
+                && vacuum_cost(self.vacuum_approach, coord) == 0
+            {
                 self.min_last_seen_count = last_seen_count;
                 self.min_last_seen_coord = Some(coord);
             }
@@ -262,16 +696,20 @@ impl Agent {
             return NpcAction::Wait;
         };
         let npc = world.entity_npc(entity);
+        let vision_distance = if world.is_in_darkness(player) {
+            DARK_VISION_DISTANCE
+        } else {
+            self.vision_distance
+        };
         self.behaviour = if let Some(player_coord) = world.entity_coord(player) {
-            let can_see_player =
-                if has_line_of_sight(coord, player_coord, world, self.vision_distance) {
-                    Some(CanSeePlayer)
-                } else {
-                    None
-                };
+            let can_see_player = if has_line_of_sight(coord, player_coord, world, vision_distance) {
+                Some(CanSeePlayer)
+            } else {
+                None
+            };
             self.last_seen_grid.update(
                 coord,
-                self.vision_distance,
+                vision_distance,
                 world,
                 can_see_player,
                 behaviour_context,
@@ -292,35 +730,85 @@ impl Agent {
                             Behaviour::Wander { avoid: true }
                         }
                     }
+                    Disposition::Friendly => Behaviour::Follow {
+                        last_seen_player_coord: player_coord,
+                    },
                 }
             } else {
-                match self.behaviour {
-                    Behaviour::Chase {
-                        last_seen_player_coord,
-                        ..
-                    } => {
-                        if last_seen_player_coord == coord {
-                            // walk up to where the player was last seen, then go back to wandering
-                            let avoid = npc.disposition == Disposition::Afraid;
-                            Behaviour::Wander { avoid }
-                        } else {
-                            Behaviour::Chase {
-                                last_seen_player_coord,
-                                accurate: last_seen_player_coord == player_coord,
+                let heard_noise_coord = if npc.disposition == Disposition::Hostile {
+                    behaviour_context.hearing.heard(coord)
+                } else {
+                    None
+                };
+                // hostile npcs who can't see the player go after any survivors they can see
+                let visible_survivor_coord = if npc.disposition == Disposition::Hostile {
+                    world
+                        .friendly_npc_coords()
+                        .filter(|&survivor_coord| {
+                            has_line_of_sight(coord, survivor_coord, world, vision_distance)
+                        })
+                        .min_by_key(|&survivor_coord| survivor_coord.manhattan_distance(coord))
+                } else {
+                    None
+                };
+                if let Some(survivor_coord) = visible_survivor_coord {
+                    Behaviour::Hunt { survivor_coord }
+                } else {
+                    match self.behaviour {
+                        Behaviour::Chase {
+                            last_seen_player_coord,
+                            ..
+                        } => {
+                            if last_seen_player_coord == coord {
+                                // walk up to where the player was last seen, then go back to wandering
+                                let avoid = npc.disposition == Disposition::Afraid;
+                                Behaviour::Wander { avoid }
+                            } else {
+                                Behaviour::Chase {
+                                    last_seen_player_coord,
+                                    accurate: last_seen_player_coord == player_coord,
+                                }
+                            }
+                        }
+                        Behaviour::Wander { avoid } => {
+                            if let Some(noise_coord) = heard_noise_coord {
+                                Behaviour::Investigate { noise_coord }
+                            } else {
+                                Behaviour::Wander { avoid }
+                            }
+                        }
+                        Behaviour::Investigate { noise_coord } => {
+                            if let Some(noise_coord) = heard_noise_coord {
+                                // head towards the most recent noise
+                                Behaviour::Investigate { noise_coord }
+                            } else if noise_coord == coord {
+                                Behaviour::Wander { avoid: false }
+                            } else {
+                                Behaviour::Investigate { noise_coord }
+                            }
+                        }
+                        Behaviour::Flee => {
+                            // stop fleeing the player if you can't see them
+                            Behaviour::Wander { avoid: true }
+                        }
+                        Behaviour::Follow {
+                            last_seen_player_coord,
+                        } => Behaviour::Follow {
+                            last_seen_player_coord,
+                        },
+                        Behaviour::Hunt { survivor_coord } => {
+                            // check where the survivor was last seen
+                            Behaviour::Investigate {
+                                noise_coord: survivor_coord,
                             }
                         }
-                    }
-                    Behaviour::Wander { avoid } => Behaviour::Wander { avoid },
-                    Behaviour::Flee => {
-                        // stop fleeing the player if you can't see them
-                        Behaviour::Wander { avoid: true }
                     }
                 }
             }
         } else {
             self.last_seen_grid.update(
                 coord,
-                self.vision_distance,
+                vision_distance,
                 world,
                 None,
                 behaviour_context,
@@ -350,6 +838,7 @@ impl Agent {
                                 .count,
                             entity,
                             avoid,
+                            vacuum_approach: &behaviour_context.vacuum_approach,
                             rng,
                         },
                         coord,
@@ -358,7 +847,21 @@ impl Agent {
                     path_node = behaviour_context.wander_path.pop();
                 }
                 if let Some(path_node) = path_node {
-                    NpcAction::Walk(path_node.in_direction)
+                    let mut direction = path_node.in_direction.direction();
+                    if behaviour_context.movement_ruleset == MovementRuleset::EightDirection {
+                        if let Some(next_node) = behaviour_context.wander_path.iter().next() {
+                            if let Some(ordinal_direction) =
+                                path_node.in_direction.combine(next_node.in_direction)
+                            {
+                                let diagonal = ordinal_direction.direction();
+                                if !world.is_diagonal_step_blocked(coord, diagonal) {
+                                    behaviour_context.wander_path.pop();
+                                    direction = diagonal;
+                                }
+                            }
+                        }
+                    }
+                    NpcAction::Walk(direction)
                 } else {
                     NpcAction::Wait
                 }
@@ -376,7 +879,93 @@ impl Agent {
                         self.behaviour = Behaviour::Wander { avoid: true };
                         NpcAction::Wait
                     }
-                    Some(cardinal_direction) => NpcAction::Walk(cardinal_direction),
+                    Some(cardinal_direction) => NpcAction::Walk(cut_corner_on_distance_map(
+                        behaviour_context.movement_ruleset,
+                        world,
+                        coord,
+                        cardinal_direction,
+                        &behaviour_context.player_flee,
+                    )),
+                }
+            }
+            Behaviour::Follow {
+                last_seen_player_coord,
+            } => {
+                if world.entity_coord(player) == Some(last_seen_player_coord) {
+                    let close_enough = behaviour_context
+                        .player_approach
+                        .distance(coord)
+                        .map(|distance| distance <= FOLLOW_DISTANCE)
+                        .unwrap_or(false);
+                    if close_enough {
+                        return NpcAction::Wait;
+                    }
+                    match behaviour_context.distance_map_search_context.search_first(
+                        &WorldCanEnterAvoidNpcs { world },
+                        coord,
+                        5,
+                        &behaviour_context.player_approach,
+                    ) {
+                        None => NpcAction::Wait,
+                        Some(cardinal_direction) => NpcAction::Walk(cut_corner_on_distance_map(
+                            behaviour_context.movement_ruleset,
+                            world,
+                            coord,
+                            cardinal_direction,
+                            &behaviour_context.player_approach,
+                        )),
+                    }
+                } else {
+                    // head to where the player was last seen and wait there for them to return
+                    match behaviour_context.step_towards(world, coord, last_seen_player_coord) {
+                        Err(NoPath) | Ok(None) => NpcAction::Wait,
+                        Ok(Some(direction)) => NpcAction::Walk(direction),
+                    }
+                }
+            }
+            Behaviour::Hunt { survivor_coord } => {
+                let to_survivor = survivor_coord - coord;
+                if to_survivor.x.abs() <= 1 && to_survivor.y.abs() <= 1 {
+                    let direction = Direction::from_unit_coord(to_survivor);
+                    if behaviour_context.movement_ruleset.allows(direction)
+                        && !world.is_diagonal_step_blocked(coord, direction)
+                    {
+                        return NpcAction::Walk(direction);
+                    }
+                }
+                if let Some(true) = world.npc_ranged_attack_ready(entity) {
+                    let clear_shot = !world
+                        .is_solid_feature_in_line_segment(LineSegment::new(coord, survivor_coord));
+                    if clear_shot {
+                        return NpcAction::Fire(survivor_coord);
+                    }
+                }
+                match behaviour_context.distance_map_search_context.search_first(
+                    &WorldCanEnterAvoidNpcs { world },
+                    coord,
+                    5,
+                    &behaviour_context.survivor_approach,
+                ) {
+                    None => {
+                        self.behaviour = Behaviour::Wander { avoid: false };
+                        NpcAction::Wait
+                    }
+                    Some(cardinal_direction) => NpcAction::Walk(cut_corner_on_distance_map(
+                        behaviour_context.movement_ruleset,
+                        world,
+                        coord,
+                        cardinal_direction,
+                        &behaviour_context.survivor_approach,
+                    )),
+                }
+            }
+            Behaviour::Investigate { noise_coord } => {
+                match behaviour_context.step_towards(world, coord, noise_coord) {
+                    Err(NoPath) | Ok(None) => {
+                        self.behaviour = Behaviour::Wander { avoid: false };
+                        NpcAction::Wait
+                    }
+                    Ok(Some(direction)) => NpcAction::Walk(direction),
                 }
             }
             Behaviour::Chase {
@@ -384,35 +973,76 @@ impl Agent {
                 accurate,
             } => {
                 if accurate {
-                    let maybe_cardinal_direction =
-                        behaviour_context.distance_map_search_context.search_first(
-                            &WorldCanEnterAvoidNpcs { world },
+                    if let Some(ready) = world.npc_ranged_attack_ready(entity) {
+                        let too_close = behaviour_context
+                            .player_approach
+                            .distance(coord)
+                            .map(|distance| distance < KEEP_DISTANCE)
+                            .unwrap_or(false);
+                        if too_close {
+                            if let Some(cardinal_direction) =
+                                behaviour_context.distance_map_search_context.search_first(
+                                    &WorldCanEnterAvoidNpcs { world },
+                                    coord,
+                                    5,
+                                    &behaviour_context.player_flee,
+                                )
+                            {
+                                return NpcAction::Walk(cut_corner_on_distance_map(
+                                    behaviour_context.movement_ruleset,
+                                    world,
+                                    coord,
+                                    cardinal_direction,
+                                    &behaviour_context.player_flee,
+                                ));
+                            }
+                        }
+                        let clear_shot = !world.is_solid_feature_in_line_segment(LineSegment::new(
                             coord,
-                            5,
-                            &behaviour_context.player_approach,
-                        );
-                    match maybe_cardinal_direction {
+                            last_seen_player_coord,
+                        ));
+                        if clear_shot {
+                            if ready {
+                                return NpcAction::Fire(last_seen_player_coord);
+                            }
+                            // while reloading, move next to a window so the player's return fire
+                            // risks breaching it
+                            let window_distance = behaviour_context.window_approach.distance(coord);
+                            if window_distance.map(|d| d > 0).unwrap_or(false) {
+                                if let Some(cardinal_direction) =
+                                    behaviour_context.distance_map_search_context.search_first(
+                                        &WorldCanEnterAvoidNpcs { world },
+                                        coord,
+                                        WINDOW_LURE_DISTANCE,
+                                        &behaviour_context.window_approach,
+                                    )
+                                {
+                                    return NpcAction::Walk(cut_corner_on_distance_map(
+                                        behaviour_context.movement_ruleset,
+                                        world,
+                                        coord,
+                                        cardinal_direction,
+                                        &behaviour_context.window_approach,
+                                    ));
+                                }
+                            }
+                            return NpcAction::Wait;
+                        }
+                    }
+                    match behaviour_context.approach_player(world, coord, last_seen_player_coord) {
                         None => {
                             self.behaviour = Behaviour::Wander { avoid: true };
                             NpcAction::Wait
                         }
-                        Some(cardinal_direction) => NpcAction::Walk(cardinal_direction),
+                        Some(action) => action,
                     }
                 } else {
-                    let result = behaviour_context
-                        .point_to_point_search_context
-                        .point_to_point_search_first(
-                            expand::JumpPoint,
-                            &WorldCanEnterAvoidNpcs { world },
-                            coord,
-                            last_seen_player_coord,
-                        );
-                    match result {
+                    match behaviour_context.step_towards(world, coord, last_seen_player_coord) {
                         Err(NoPath) | Ok(None) => {
                             self.behaviour = Behaviour::Wander { avoid: true };
                             NpcAction::Wait
                         }
-                        Ok(Some(cardinal_direction)) => NpcAction::Walk(cardinal_direction),
+                        Ok(Some(direction)) => NpcAction::Walk(direction),
                     }
                 }
             }
@@ -1,5 +1,6 @@
+use gridbugs::line_2d::StepIter;
 pub use gridbugs::{
-    direction::CardinalDirection,
+    direction::{CardinalDirection, Direction},
     entity_table::ComponentTable,
     grid_2d::{Coord, Grid, Size},
     shadowcast::Context as ShadowcastContext,
@@ -22,12 +23,17 @@ use terrain::{SpaceStationSpec, Terrain, TerrainState};
 pub use visibility::{CellVisibility, EntityTile, Omniscient, VisibilityCell, VisibilityGrid};
 use world::{make_player, AnimationContext, World, ANIMATION_FRAME_DURATION};
 pub use world::{
-    player, ActionError, CharacterInfo, Enemy, EntityData, HitPoints, Item, Layer, MeleeWeapon,
-    NpcAction, PlayerDied, RangedWeapon, Tile, ToRenderEntity, ToRenderEntityRealtime,
+    perk, player, ActionError, ActiveStatusEffect, AmmoType, CharacterInfo, Consumable, Enemy,
+    EntityData, HitPoints, Item, Layer, Mass, MeleeWeapon, NpcAction, PlayerDied, RangedWeapon,
+    StatusEffect, StatusEffects, TerminalCommand, Tile, ToRenderEntity, ToRenderEntityRealtime,
+    Ware,
 };
 
 pub const MAP_SIZE: Size = Size::new_u16(20, 14);
 
+/// Credit awarded for each survivor escorted to the stairs
+const SURVIVOR_RESCUE_CREDIT: u32 = 3;
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub enum Message {
     MaybeThisWasntSuchAGoodIdea,
@@ -48,6 +54,70 @@ pub enum Message {
     PlayerAdrift,
     EnemyAdrift(Enemy),
     MapTerminal,
+    PutOnMagneticBoots,
+    HitByDebris,
+    EnemyShootsPlayer(Enemy),
+    GuardianArmourBuckles,
+    GuardianOverloads,
+    GuardianVentsRoom,
+    GuardianSummons,
+    GuardianWeakPointHit,
+    CriticalHit,
+    PlayerStatusEffect(StatusEffect),
+    EnemyStatusEffect(Enemy, StatusEffect),
+    GrenadeExplodes,
+    TakeItem(Consumable),
+    DropItem(Consumable),
+    RefillOxygen,
+    RepairStructures,
+    TakeAmmo(AmmoType, u32),
+    Reload(player::WeaponName),
+    ModWeapon(player::WeaponName, player::WeaponMod),
+    Buy(Ware),
+    EnemyHitSurvivor(Enemy),
+    SurvivorDies,
+    SurvivorAdrift,
+    SurvivorsRescued {
+        count: u32,
+        credit: u32,
+    },
+    PryDoor,
+    ShoveEnemy(Enemy),
+    TakeKeycard,
+    UnlockDoor,
+    PryLock,
+    HackDoor {
+        progress: u32,
+        total: u32,
+    },
+    DoorHacked,
+    EnemyDropsKeycard(Enemy),
+    TerminalUnlocksDoors,
+    TerminalVentsRoom,
+    TerminalLightsOff,
+    TerminalRevealsEnemies,
+    TakeFlashlight,
+    FlashlightBatteryFlat,
+    LightShotOut,
+    Blackout,
+}
+
+/// Which directions the player and npcs can walk and shoot in. This is chosen at the start of a
+/// run and can't be changed until the next run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementRuleset {
+    #[default]
+    Cardinal,
+    EightDirection,
+}
+
+impl MovementRuleset {
+    pub fn allows(self, direction: Direction) -> bool {
+        match self {
+            Self::Cardinal => direction.is_cardinal(),
+            Self::EightDirection => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -55,6 +125,10 @@ pub struct Config {
     pub omniscient: Option<Omniscient>,
     pub demo: bool,
     pub debug: bool,
+    pub movement_ruleset: MovementRuleset,
+    /// Allows ranged weapons to be aimed at any visible cell rather than only along the
+    /// directions allowed by the movement ruleset
+    pub free_aim: bool,
 }
 impl Config {
     pub const OMNISCIENT: Option<Omniscient> = Some(Omniscient);
@@ -65,6 +139,8 @@ impl Default for Config {
             omniscient: None,
             demo: false,
             debug: false,
+            movement_ruleset: MovementRuleset::default(),
+            free_aim: false,
         }
     }
 }
@@ -89,6 +165,7 @@ pub enum SoundEffect {
     DoorOpen,
     Heal,
     Die,
+    WindowShatter,
 }
 
 /// Events which the game can report back to the io layer so it can
@@ -107,20 +184,58 @@ pub enum GameControlFlow {
     LevelChange,
     Upgrade,
     UnlockMap,
+    Workbench(player::WeaponAbility),
+    Vendor(Entity),
+    Terminal(Entity),
 }
 
 #[derive(Clone, Copy, Debug)]
 pub enum Input {
-    Walk(CardinalDirection),
+    Walk(Direction),
     Wait,
     Fire {
-        direction: CardinalDirection,
+        target: Coord,
         slot: player::RangedWeaponSlot,
     },
-    Upgrade(player::Upgrade),
+    Upgrade(perk::Perk),
     EquipMeleeWeapon,
     EquipRangedWeapon(player::RangedWeaponSlot),
     UnlockMap,
+    ThrowGrenade {
+        target: Coord,
+    },
+    PickUpItem,
+    UseItem(usize),
+    DropItem(usize),
+    Reload(player::RangedWeaponSlot),
+    ModWeapon {
+        weapon: player::HeldWeapon,
+        weapon_mod: player::WeaponMod,
+    },
+    Buy {
+        vendor: Entity,
+        index: usize,
+    },
+    Shove(Direction),
+    UseTerminal {
+        terminal: Entity,
+        command: TerminalCommand,
+    },
+    ToggleFlashlight,
+}
+
+/// The cells a shot aimed at a target would pass through
+#[derive(Debug, Clone)]
+pub struct ShotPreview {
+    /// Every cell the shot passes through before it hits a solid feature or leaves the map
+    pub path: Vec<Coord>,
+    /// Walls, doors and windows the shot would damage. All but the last are broken through.
+    pub breach: Vec<Coord>,
+}
+
+/// Shots aimed at a cell carry on past it until they hit something
+fn aim_past(start: Coord, target: Coord) -> Coord {
+    start + ((target - start) * 100)
 }
 
 pub enum WarningLight {
@@ -160,6 +275,8 @@ pub struct Game {
     adrift: bool,
     terrain_state: TerrainState,
     message_log: Vec<Message>,
+    movement_ruleset: MovementRuleset,
+    free_aim: bool,
 }
 
 impl Game {
@@ -204,13 +321,7 @@ impl Game {
                 .unwrap()
                 .melee_weapon = player::Weapon::new_chainsaw();
 
-            let _ = world.apply_upgrade(
-                player,
-                player::Upgrade {
-                    typ: player::UpgradeType::Toughness,
-                    level: player::UpgradeLevel::Level1,
-                },
-            );*/
+            let _ = world.apply_upgrade(player, perk::Perk::StrongBack);*/
             world.components.player.get_mut(player).unwrap().credit = 100;
         }
         world.air.init(&world.spatial_table, &world.components);
@@ -228,7 +339,7 @@ impl Game {
             animation_rng,
             events,
             shadowcast_context: ShadowcastContext::default(),
-            behaviour_context: BehaviourContext::new(world.size()),
+            behaviour_context: BehaviourContext::new(world.size(), config.movement_ruleset),
             animation_context: AnimationContext::default(),
             agents,
             agents_to_remove: Vec::new(),
@@ -245,6 +356,8 @@ impl Game {
             adrift: false,
             terrain_state,
             message_log: Vec::new(),
+            movement_ruleset: config.movement_ruleset,
+            free_aim: config.free_aim,
         };
         game.update_visibility(config);
         game.prime_npcs();
@@ -253,6 +366,29 @@ impl Game {
     pub fn message_log(&self) -> &[Message] {
         &self.message_log
     }
+    pub fn movement_ruleset(&self) -> MovementRuleset {
+        self.movement_ruleset
+    }
+    pub fn free_aim(&self) -> bool {
+        self.free_aim
+    }
+    /// Whether the player may fire at the target. Without free aim, shots are limited to the
+    /// straight lines in directions allowed by the movement ruleset.
+    pub fn can_aim_at(&self, target: Coord) -> bool {
+        let delta = target - self.player_coord();
+        if delta == Coord::new(0, 0) {
+            return false;
+        }
+        if self.free_aim {
+            return true;
+        }
+        let straight = delta.x == 0 || delta.y == 0 || delta.x.abs() == delta.y.abs();
+        let unit = Coord::new(delta.x.signum(), delta.y.signum());
+        straight
+            && self
+                .movement_ruleset
+                .allows(Direction::from_unit_coord(unit))
+    }
     pub fn player_has_usable_weapon_in_slot(&self, slot: player::RangedWeaponSlot) -> bool {
         let player = self.world.components.player.get(self.player).unwrap();
         if slot.index() >= player.ranged_weapons.len() {
@@ -264,6 +400,27 @@ impl Game {
             false
         }
     }
+    /// Slots holding a weapon which isn't fully loaded and has spare ammunition to reload from
+    pub fn reloadable_slots(&self) -> Vec<player::RangedWeaponSlot> {
+        use player::RangedWeaponSlot::*;
+        let player = self.player();
+        [Slot1, Slot2, Slot3]
+            .into_iter()
+            .filter(|&slot| {
+                player
+                    .ranged_weapons
+                    .get(slot.index())
+                    .and_then(|weapon| weapon.as_ref())
+                    .map(|weapon| match (weapon.ammo, weapon.name.ammo_type()) {
+                        (Some(ammo), Some(ammo_type)) => {
+                            ammo.current < ammo.max && player.ammo_reserve.get(ammo_type) > 0
+                        }
+                        _ => false,
+                    })
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
     pub fn player_has_weapon_in_slot(&self, slot: player::RangedWeaponSlot) -> bool {
         let player = self.world.components.player.get(self.player).unwrap();
         if slot.index() >= player.ranged_weapons.len() {
@@ -289,14 +446,36 @@ impl Game {
                     .and_then(|item_entity| self.world.components.weapon.get(item_entity))
             })
     }
-    pub fn available_upgrades(&self) -> Vec<player::Upgrade> {
-        let player = self
-            .world
-            .components
-            .player
-            .get(self.player)
-            .expect("no player");
-        player.available_upgrades()
+    pub fn consumable_under_player(&self) -> Option<Consumable> {
+        self.world
+            .spatial_table
+            .layers_at(self.player_coord())
+            .and_then(|layers| layers.item)
+            .and_then(
+                |item_entity| match self.world.components.item.get(item_entity) {
+                    Some(&Item::Consumable(consumable)) => Some(consumable),
+                    _ => None,
+                },
+            )
+    }
+    pub fn available_upgrades(&self) -> Vec<perk::Perk> {
+        self.player().perks.available()
+    }
+    pub fn moddable_weapons(&self) -> Vec<player::HeldWeapon> {
+        self.player().moddable_weapons()
+    }
+    pub fn available_weapon_mods(
+        &self,
+        weapon: player::HeldWeapon,
+        ability: player::WeaponAbility,
+    ) -> Vec<player::WeaponMod> {
+        self.player()
+            .held_weapon(weapon)
+            .map(|weapon| weapon.available_mods(ability))
+            .unwrap_or_default()
+    }
+    pub fn vendor_stock(&self, vendor: Entity) -> &[Ware] {
+        self.world.vendor_stock(vendor)
     }
     pub fn warning_light(&self, coord: Coord) -> Option<WarningLight> {
         if let Some(layers) = self.world.spatial_table.layers_at(coord) {
@@ -318,6 +497,10 @@ impl Game {
     pub fn is_adrift(&self) -> bool {
         self.adrift
     }
+    /// Whether the station lights on this floor are powered
+    pub fn lights_on(&self) -> bool {
+        self.world.lights_on
+    }
     pub fn star_rng_seed(&self) -> u64 {
         self.star_rng_seed
     }
@@ -344,6 +527,7 @@ impl Game {
             }
             self.visibility_grid.update(
                 player_coord,
+                self.world.flashlight_beam(self.player),
                 &self.world,
                 &mut self.shadowcast_context,
                 config.omniscient.or(map),
@@ -456,6 +640,33 @@ impl Game {
             self.world.apply_upgrade(self.player, upgrade)?;
             return Ok(None);
         }
+        if let Input::ModWeapon { weapon, weapon_mod } = input {
+            self.world
+                .apply_weapon_mod(self.player, weapon, weapon_mod, &mut self.message_log)?;
+            return Ok(None);
+        }
+        if let Input::Buy { vendor, index } = input {
+            self.world
+                .character_buy(self.player, vendor, index, &mut self.message_log)?;
+            return Ok(None);
+        }
+        if let Input::UseTerminal { terminal, command } = input {
+            self.world.character_use_terminal(
+                self.player,
+                terminal,
+                command,
+                &mut self.rng,
+                &mut self.events,
+                &mut self.message_log,
+            )?;
+            self.update_visibility(config);
+            return Ok(None);
+        }
+        if let Input::ToggleFlashlight = input {
+            self.world.character_toggle_flashlight(self.player)?;
+            self.update_visibility(config);
+            return Ok(None);
+        }
         if let Input::UnlockMap = input {
             self.world.unlock_map(self.player);
             return Ok(None);
@@ -492,7 +703,18 @@ impl Game {
     }
 
     fn player_turn(&mut self, input: Input) -> Result<Option<GameControlFlow>, ActionError> {
+        // a stunned player loses whatever they were trying to do this turn
+        let input = if self.world.consume_stun(self.player) {
+            Input::Wait
+        } else {
+            input
+        };
         let result = match input {
+            Input::Walk(direction) | Input::Shove(direction)
+                if !self.movement_ruleset.allows(direction) =>
+            {
+                Err(ActionError::CannotMoveThatWay)
+            }
             Input::Walk(direction) => self.world.character_walk_in_direction(
                 self.player,
                 direction,
@@ -501,10 +723,13 @@ impl Game {
                 &mut self.message_log,
             ),
             Input::Wait => Ok(None),
-            Input::Fire { direction, slot } => {
+            Input::Fire { target, .. } if !self.can_aim_at(target) => {
+                Err(ActionError::CannotAimThere)
+            }
+            Input::Fire { target, slot } => {
                 self.world.character_fire_bullet(
                     self.player,
-                    self.player_coord() + (direction.coord() * 100),
+                    aim_past(self.player_coord(), target),
                     slot,
                     &mut self.events,
                     &mut self.rng,
@@ -512,6 +737,8 @@ impl Game {
                 Ok(None)
             }
             Input::Upgrade(_upgrade) => Ok(None),
+            Input::ModWeapon { .. } => Ok(None),
+            Input::Buy { .. } => Ok(None),
             Input::EquipMeleeWeapon => {
                 self.world
                     .equip_melee_weapon_from_ground(self.player, &mut self.message_log);
@@ -526,8 +753,41 @@ impl Game {
                 Ok(None)
             }
             Input::UnlockMap => Ok(None),
+            Input::UseTerminal { .. } => Ok(None),
+            Input::ToggleFlashlight => Ok(None),
+            Input::ThrowGrenade { target } => self
+                .world
+                .character_throw_grenade(self.player, target)
+                .map(|()| None),
+            Input::PickUpItem => self
+                .world
+                .character_pick_up_item(self.player, &mut self.message_log)
+                .map(|()| None),
+            Input::UseItem(index) => self
+                .world
+                .character_use_item(self.player, index, &mut self.events, &mut self.message_log)
+                .map(|()| None),
+            Input::DropItem(index) => self
+                .world
+                .character_drop_item(self.player, index, &mut self.message_log)
+                .map(|()| None),
+            Input::Reload(slot) => self
+                .world
+                .character_reload(self.player, slot, &mut self.message_log)
+                .map(|()| None),
+            Input::Shove(direction) => self
+                .world
+                .character_shove(
+                    self.player,
+                    direction,
+                    &mut self.events,
+                    &mut self.message_log,
+                )
+                .map(|()| None),
         };
         if result.is_ok() {
+            self.world
+                .drain_flashlight(self.player, &mut self.message_log);
             if self.is_gameplay_blocked() {
                 self.after_player_turn_countdown = Some(Duration::from_millis(0));
                 self.before_npc_turn_cooldown = Some(Duration::from_millis(100));
@@ -545,14 +805,10 @@ impl Game {
         self.turn_during_animation = Some(Turn::Player);
     }
 
-    pub fn player_fire_weapon(
-        &mut self,
-        slot: player::RangedWeaponSlot,
-        direction: CardinalDirection,
-    ) {
+    pub fn player_fire_weapon(&mut self, slot: player::RangedWeaponSlot, target: Coord) {
         self.world.character_fire_bullet(
             self.player,
-            self.player_coord() + (direction.coord() * 100),
+            aim_past(self.player_coord(), target),
             slot,
             &mut self.events,
             &mut self.rng,
@@ -578,18 +834,29 @@ impl Game {
                 .world
                 .air
                 .update(&self.world.spatial_table, &self.world.components);
-            for (entity, direction) in to_move {
-                if let Some(player) = self.world.components.player.get(entity) {
+            for pull in to_move {
+                if self.world.is_held_to_hull(pull.entity) {
+                    continue;
+                }
+                if let Some(player) = self.world.components.player.get(pull.entity) {
                     if i == 0 {
                         self.message_log.push(Message::PulledByVacuum);
                     }
-                    if player.traits.half_vacuum_pull && i == 1 {
+                    if player.perks.has_rule(perk::PerkRule::HalveVacuumPull) && i == 1 {
                         continue;
                     }
                 }
-                let _ = self
-                    .world
-                    .character_pull_in_direction(entity, direction, &mut self.rng);
+                let num_cells = pull.num_cells(
+                    self.world.mass(pull.entity),
+                    self.world.is_anchored(pull.entity),
+                );
+                for _ in 0..num_cells {
+                    self.world.character_pull_in_direction(
+                        pull.entity,
+                        pull.direction,
+                        &mut self.rng,
+                    );
+                }
             }
             self.update_last_player_info();
         }
@@ -600,6 +867,10 @@ impl Game {
             &mut self.events,
             &mut self.message_log,
         );
+        self.world
+            .process_status_effects(&mut self.rng, &mut self.events, &mut self.message_log);
+        self.world
+            .process_grenades(&mut self.rng, &mut self.events, &mut self.message_log);
         self.world
             .process_skeleton_respawn(&mut self.rng, &mut self.agents, &mut self.events);
         if let Some(layers) = self.world.spatial_table.layers_at(self.player_coord()) {
@@ -616,24 +887,72 @@ impl Game {
                         }
                         Item::RangedWeapon(_) => {}
                         Item::MeleeWeapon(_) => {}
-                        Item::Medkit => {
-                            self.world.heal_fully(
-                                self.player,
-                                &mut self.events,
-                                &mut self.message_log,
-                            );
+                        Item::Consumable(_) => {}
+                        &Item::Ammo { ammo_type, amount } => {
+                            if let Some(player) = self.world.components.player.get_mut(self.player)
+                            {
+                                let added = player.ammo_reserve.add(ammo_type, amount);
+                                if added > 0 {
+                                    self.message_log.push(Message::TakeAmmo(ammo_type, added));
+                                    if added < amount {
+                                        // leave whatever doesn't fit on the floor
+                                        self.world.components.item.insert(
+                                            item_entity,
+                                            Item::Ammo {
+                                                ammo_type,
+                                                amount: amount - added,
+                                            },
+                                        );
+                                    } else {
+                                        self.world.components.to_remove.insert(item_entity, ());
+                                    }
+                                }
+                            }
+                        }
+                        Item::MagneticBoots => {
+                            if let Some(player) = self.world.components.player.get_mut(self.player)
+                            {
+                                self.message_log.push(Message::PutOnMagneticBoots);
+                                player.magnetic_boots = true;
+                            }
+                            self.world.components.to_remove.insert(item_entity, ());
+                        }
+                        Item::Keycard => {
+                            if let Some(player) = self.world.components.player.get_mut(self.player)
+                            {
+                                self.message_log.push(Message::TakeKeycard);
+                                player.keycards += 1;
+                            }
+                            self.world.components.to_remove.insert(item_entity, ());
+                        }
+                        Item::Flashlight => {
+                            if let Some(player) = self.world.components.player.get_mut(self.player)
+                            {
+                                self.message_log.push(Message::TakeFlashlight);
+                                player.flashlight = Some(player::Flashlight::new_charged());
+                            }
                             self.world.components.to_remove.insert(item_entity, ());
                         }
                     }
                 }
             }
         }
+        self.world.process_boss(
+            self.player,
+            &mut self.rng,
+            &mut self.events,
+            &mut self.message_log,
+        );
         self.update_behaviour();
+        self.world.process_ranged_attack_reload();
         for (entity, agent) in self.agents.iter_mut() {
             if !self.world.entity_exists(entity) {
                 self.agents_to_remove.push(entity);
                 continue;
             }
+            if self.world.consume_stun(entity) {
+                continue;
+            }
             let input = agent.act(
                 entity,
                 &self.world,
@@ -652,9 +971,14 @@ impl Game {
                         &mut self.message_log,
                     );
                 }
+                NpcAction::Fire(target) => {
+                    self.world
+                        .npc_fire(entity, target, &mut self.events, &mut self.rng);
+                }
                 NpcAction::Wait => (),
             }
         }
+        self.world.noises.clear();
         self.update_last_player_info();
         for entity in self.agents_to_remove.drain(..) {
             self.agents.remove(entity);
@@ -664,18 +988,14 @@ impl Game {
     fn generate_level(&mut self, config: &Config) {
         self.message_log.push(Message::Descend);
         let mut player_data = self.world.clone_entity_data(self.player);
-        for weapon in player_data
-            .player
-            .as_mut()
-            .unwrap()
-            .ranged_weapons
-            .iter_mut()
-        {
-            if let Some(weapon) = weapon.as_mut() {
-                if let Some(ammo) = weapon.ammo.as_mut() {
-                    ammo.current = ammo.max;
-                }
-            }
+        let rescued = self.world.num_survivors_escorted_by(self.player);
+        if rescued > 0 {
+            let credit = rescued * SURVIVOR_RESCUE_CREDIT;
+            player_data.player.as_mut().unwrap().credit += credit;
+            self.message_log.push(Message::SurvivorsRescued {
+                count: rescued,
+                credit,
+            });
         }
         if let Some(ammo) = player_data
             .player
@@ -687,6 +1007,9 @@ impl Game {
         {
             ammo.current = ammo.max;
         }
+        if let Some(flashlight) = player_data.player.as_mut().unwrap().flashlight.as_mut() {
+            flashlight.battery = player::FLASHLIGHT_BATTERY;
+        }
         let Terrain {
             mut world,
             agents,
@@ -703,6 +1026,9 @@ impl Game {
         self.world = world;
         self.agents = agents;
         self.player = player;
+        if !self.world.lights_on {
+            self.message_log.push(Message::Blackout);
+        }
         self.update_last_player_info();
         self.update_visibility(config);
         self.prime_npcs();
@@ -713,7 +1039,7 @@ impl Game {
 
     fn after_turn(&mut self) {
         if let Some(layers) = self.world.spatial_table.layers_at(self.player_coord()) {
-            if layers.floor.is_none() {
+            if layers.floor.is_none() && !self.world.is_held_to_hull(self.player) {
                 self.world.components.to_remove.insert(self.player, ());
                 if !self.adrift {
                     self.message_log.push(Message::PlayerAdrift);
@@ -721,32 +1047,44 @@ impl Game {
                 self.adrift = true;
             }
         }
-        for npc in self.world.components.npc.entities() {
+        for npc in self.world.components.npc.entities().collect::<Vec<_>>() {
             if let Some(coord) = self.world.spatial_table.coord_of(npc) {
-                if let Some(layers) = self.world.spatial_table.layers_at(coord) {
+                if let Some(&layers) = self.world.spatial_table.layers_at(coord) {
                     if layers.floor.is_none() {
-                        self.world.components.to_remove.insert(npc, ());
+                        if self.world.is_friendly_npc(npc) {
+                            self.message_log.push(Message::SurvivorAdrift);
+                        }
                         if let Some(enemy) = self.world.components.enemy.get(npc) {
                             self.message_log.push(Message::EnemyAdrift(*enemy));
                         }
+                        // the npc stops acting but remains in the world until it drifts away
+                        self.world.components.npc.remove(npc);
+                        self.agents.remove(npc);
+                        self.world.start_drift(npc, 2, &mut self.rng);
                     }
                 }
             }
         }
-        for item in self.world.components.item.entities() {
+        for item in self.world.components.item.entities().collect::<Vec<_>>() {
             if let Some(coord) = self.world.spatial_table.coord_of(item) {
                 if let Some(layers) = self.world.spatial_table.layers_at(coord) {
                     if layers.floor.is_none() {
-                        self.world.components.to_remove.insert(item, ());
+                        self.world.start_drift(item, 1, &mut self.rng);
                     }
                 }
             }
         }
-        for skeleton_respawn in self.world.components.skeleton_respawn.entities() {
+        for skeleton_respawn in self
+            .world
+            .components
+            .skeleton_respawn
+            .entities()
+            .collect::<Vec<_>>()
+        {
             if let Some(coord) = self.world.spatial_table.coord_of(skeleton_respawn) {
                 if let Some(layers) = self.world.spatial_table.layers_at(coord) {
                     if layers.floor.is_none() {
-                        self.world.components.to_remove.insert(skeleton_respawn, ());
+                        self.world.start_drift(skeleton_respawn, 2, &mut self.rng);
                     }
                 }
             }
@@ -804,6 +1142,139 @@ impl Game {
     pub fn contains_floor(&self, coord: Coord) -> bool {
         self.world.is_floor_at_coord(coord)
     }
+    pub fn character_mass(&self, coord: Coord) -> Option<Mass> {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.character)
+            .map(|character| self.world.mass(character))
+    }
+    pub fn is_character_anchored(&self, coord: Coord) -> bool {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.character)
+            .map(|character| self.world.is_anchored(character))
+            .unwrap_or(false)
+    }
+    /// Whether the character at the coord has been pulled out of the station and is drifting away
+    pub fn is_character_adrift(&self, coord: Coord) -> bool {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.character)
+            .is_some_and(|character| self.world.components.debris.contains(character))
+    }
+    pub fn character_status_effects(&self, coord: Coord) -> Option<&StatusEffects> {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.character)
+            .and_then(|character| self.world.components.status_effects.get(character))
+    }
+    /// The closest enemy the player can currently see, for choosing an initial target
+    pub fn nearest_visible_enemy_coord(&self) -> Option<Coord> {
+        let player_coord = self.player_coord();
+        self.world
+            .components
+            .enemy
+            .entities()
+            .filter_map(|entity| self.world.entity_coord(entity))
+            .filter(|&coord| self.visibility_grid.is_coord_currently_visible(coord))
+            .min_by_key(|&coord| coord.distance2(player_coord))
+    }
+    /// Traces a shot along the same steps its projectile would take, stopping where the
+    /// projectile would be stopped
+    pub fn shot_preview(&self, slot: player::RangedWeaponSlot, target: Coord) -> ShotPreview {
+        let start = self.player_coord();
+        let mut preview = ShotPreview {
+            path: Vec::new(),
+            breach: Vec::new(),
+        };
+        let weapon = match self.player().weapon_in_slot(slot) {
+            Some(weapon) => weapon,
+            None => return preview,
+        };
+        if start == target {
+            return preview;
+        }
+        let structure_damage = self.world.structure_damage(weapon.hull_pen_percent);
+        // once a projectile's penetration is used up it carries on harmlessly until it hits a
+        // solid feature
+        let mut pen = Some(weapon.pen);
+        let mut coord = start;
+        for direction in StepIter::new(aim_past(start, target) - start) {
+            coord += direction.coord();
+            let layers = match self.world.spatial_table.layers_at(coord) {
+                Some(layers) => layers,
+                None => break,
+            };
+            if let (Some(character), Some(current_pen)) = (layers.character, pen) {
+                if let Some(armour) = self.world.armour_against(character, direction.coord()) {
+                    pen = current_pen
+                        .checked_sub(armour)
+                        .filter(|&remaining_pen| remaining_pen > 0);
+                }
+            }
+            if let Some(feature) = layers.feature {
+                if self.world.components.solid.contains(feature) {
+                    if pen.is_none()
+                        || structure_damage == 0
+                        || !self.world.components.destructible.contains(feature)
+                    {
+                        break;
+                    }
+                    preview.breach.push(coord);
+                    let breaks_through = self
+                        .world
+                        .components
+                        .integrity
+                        .get(feature)
+                        .is_some_and(|integrity| structure_damage >= integrity.current);
+                    if !breaks_through {
+                        break;
+                    }
+                    continue;
+                }
+            }
+            preview.path.push(coord);
+        }
+        preview
+    }
+    /// The cells a grenade thrown at the target would pass through, ending where it lands
+    pub fn throw_preview(&self, target: Coord) -> ShotPreview {
+        ShotPreview {
+            path: self.world.grenade_throw_path(self.player_coord(), target),
+            breach: Vec::new(),
+        }
+    }
+    /// The room which would be vented from a terminal by targeting the given cell
+    pub fn vent_preview(&self, target: Coord) -> ShotPreview {
+        ShotPreview {
+            path: self.world.room_cells(target),
+            breach: Vec::new(),
+        }
+    }
+    /// Every enemy on the floor, if a terminal has revealed their positions
+    pub fn revealed_enemies(&self) -> impl '_ + Iterator<Item = ToRenderEntity> {
+        self.world
+            .components
+            .enemy
+            .entities()
+            .filter(move |_| self.world.enemies_revealed)
+            .filter_map(move |entity| self.world.to_render_entity(entity))
+    }
+    /// Health of the boss, if the player can currently see it
+    pub fn visible_boss_hit_points(&self) -> Option<HitPoints> {
+        self.world.components.boss.entities().find_map(|entity| {
+            let coord = self.world.entity_coord(entity)?;
+            if self.visibility_grid.is_coord_currently_visible(coord) {
+                self.world.components.hit_points.get(entity).cloned()
+            } else {
+                None
+            }
+        })
+    }
     fn update_last_player_info(&mut self) {
         if let Some(character_info) = self.world.character_info(self.player) {
             self.last_player_info = character_info;
@@ -829,3 +1300,120 @@ impl Game {
         self.world.level
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ROOM: &str = "
+#########
+#@......#
+#.......#
+#.......#
+#########
+";
+
+    /// Starts a game on the debug floor, then swaps in a small room for the test
+    fn game_in_room(config: &Config) -> Game {
+        let config = Config {
+            debug: true,
+            ..*config
+        };
+        let mut game = Game::new(&config, &mut Isaac64Rng::seed_from_u64(0));
+        let Terrain {
+            mut world,
+            agents,
+            player,
+        } = terrain::from_str(ROOM, make_player());
+        world.air.init(&world.spatial_table, &world.components);
+        game.world = world;
+        game.agents = agents;
+        game.player = player;
+        game.update_last_player_info();
+        game.update_visibility(&config);
+        game
+    }
+
+    #[test]
+    fn the_cardinal_ruleset_rejects_diagonal_moves_and_shoves() {
+        let config = Config::default();
+        let mut game = game_in_room(&config);
+        let start = game.player_coord();
+        let result = game.handle_input(Input::Walk(Direction::SouthEast), &config);
+        assert!(matches!(result, Err(ActionError::CannotMoveThatWay)));
+        let result = game.handle_input(Input::Shove(Direction::SouthEast), &config);
+        assert!(matches!(result, Err(ActionError::CannotMoveThatWay)));
+        assert_eq!(game.player_coord(), start);
+        assert!(game
+            .handle_input(Input::Walk(Direction::East), &config)
+            .is_ok());
+        assert_eq!(game.player_coord(), start + Coord::new(1, 0));
+    }
+
+    #[test]
+    fn the_eight_direction_ruleset_allows_diagonal_moves() {
+        let config = Config {
+            movement_ruleset: MovementRuleset::EightDirection,
+            ..Config::default()
+        };
+        let mut game = game_in_room(&config);
+        let start = game.player_coord();
+        assert!(game
+            .handle_input(Input::Walk(Direction::SouthEast), &config)
+            .is_ok());
+        assert_eq!(game.player_coord(), start + Coord::new(1, 1));
+    }
+
+    #[test]
+    fn without_free_aim_only_the_ruleset_directions_can_be_aimed_at() {
+        let game = game_in_room(&Config::default());
+        assert!(game.can_aim_at(Coord::new(4, 1)));
+        assert!(!game.can_aim_at(Coord::new(3, 3)));
+        assert!(!game.can_aim_at(Coord::new(4, 2)));
+        assert!(!game.can_aim_at(game.player_coord()));
+        let game = game_in_room(&Config {
+            movement_ruleset: MovementRuleset::EightDirection,
+            ..Config::default()
+        });
+        assert!(game.can_aim_at(Coord::new(3, 3)));
+        assert!(!game.can_aim_at(Coord::new(4, 2)));
+    }
+
+    #[test]
+    fn free_aim_allows_any_target_but_the_player() {
+        let config = Config {
+            free_aim: true,
+            ..Config::default()
+        };
+        let game = game_in_room(&config);
+        assert!(game.can_aim_at(Coord::new(4, 2)));
+        assert!(!game.can_aim_at(game.player_coord()));
+    }
+
+    #[test]
+    fn shot_preview_follows_an_off_axis_shot_to_the_wall() {
+        let config = Config {
+            free_aim: true,
+            ..Config::default()
+        };
+        let mut game = game_in_room(&config);
+        let slot = player::RangedWeaponSlot::Slot1;
+        game.world
+            .components
+            .player
+            .get_mut(game.player)
+            .unwrap()
+            .ranged_weapons[slot.index()] = Some(player::Weapon::new_rifle());
+        let preview = game.shot_preview(slot, Coord::new(4, 2));
+        let mut previous = game.player_coord();
+        for &coord in preview.path.iter() {
+            let step = coord - previous;
+            assert!(step.x.abs() <= 1 && step.y.abs() <= 1 && step != Coord::new(0, 0));
+            assert!(game.world.is_floor_at_coord(coord));
+            previous = coord;
+        }
+        assert!(preview.path.contains(&Coord::new(4, 2)));
+        assert!(preview.path.contains(&Coord::new(7, 3)));
+        assert_eq!(preview.breach, vec![Coord::new(8, 3)]);
+    }
+}
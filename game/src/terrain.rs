@@ -2,22 +2,31 @@ use crate::behaviour::Agent;
 use crate::visibility::Light;
 use crate::{
     world::EntityData,
-    world::{Layer, Location, MeleeWeapon, RangedWeapon},
+    world::{
+        player::WeaponAbility, AmmoType, Consumable, Enemy, Layer, Location, MeleeWeapon,
+        RangedWeapon, Ware,
+    },
     Tile, World,
 };
 use gridbugs::{
     coord_2d::{Coord, Size},
-    direction::{Axis, Directions},
+    direction::{Axis, CardinalDirection, Directions},
     entity_table::{ComponentTable, Entity},
     rgb_int::Rgb24,
     shadowcast::vision_distance::Circle,
 };
 use rand::{seq::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 const AREA_SIZE: Size = Size::new_u16(27, 20);
 
+/// The flashlight is found before any of the blackout floors
+const FLASHLIGHT_FLOOR: u32 = 1;
+const NUM_WALL_LIGHTS: usize = 8;
+/// Minimum manhattan distance between wall lights
+const WALL_LIGHT_SPACING: u32 = 6;
+
 pub struct Terrain {
     pub world: World,
     pub player: Entity,
@@ -27,7 +36,11 @@ pub struct Terrain {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TerrainState {
     ranged_weapons: Vec<RangedWeapon>,
-    chainsaw_floors: HashSet<u32>,
+    /// The melee weapon found on each floor
+    melee_weapons: HashMap<u32, MeleeWeapon>,
+    magnetic_boots_floor: u32,
+    /// Floors where the station lights have no power
+    blackout_floors: Vec<u32>,
 }
 
 impl TerrainState {
@@ -49,13 +62,28 @@ impl TerrainState {
         ranged_weapons.shuffle(rng);
         let mut floors = (1..=5).collect::<Vec<_>>();
         floors.shuffle(rng);
-        let mut chainsaw_floors = HashSet::new();
-        for _ in 0..2 {
-            chainsaw_floors.insert(floors.pop().unwrap());
-        }
+        let melee_weapons = floors
+            .into_iter()
+            .zip([
+                MeleeWeapon::Chainsaw,
+                MeleeWeapon::Chainsaw,
+                MeleeWeapon::Crowbar,
+                MeleeWeapon::ShockBaton,
+                MeleeWeapon::FireAxe,
+            ])
+            .collect();
+        let magnetic_boots_floor = rng.gen_range(1..=3);
+        let num_blackout_floors = rng.gen_range(1..=2);
+        let blackout_floors = (FLASHLIGHT_FLOOR + 1..FINAL_LEVEL)
+            .collect::<Vec<_>>()
+            .choose_multiple(rng, num_blackout_floors)
+            .cloned()
+            .collect();
         Self {
             ranged_weapons,
-            chainsaw_floors,
+            melee_weapons,
+            magnetic_boots_floor,
+            blackout_floors,
         }
     }
 }
@@ -114,6 +142,16 @@ pub fn from_str(s: &str, player_data: EntityData) -> Terrain {
                     agents.insert(entity, Agent::new(size));
                     world.spawn_floor(coord);
                 }
+                'd' => {
+                    let entity = world.spawn_drone(coord);
+                    agents.insert(entity, Agent::new(size));
+                    world.spawn_floor(coord);
+                }
+                'G' => {
+                    let entity = world.spawn_guardian(coord, CardinalDirection::South);
+                    agents.insert(entity, Agent::new(size));
+                    world.spawn_floor(coord);
+                }
                 'u' => {
                     world.spawn_upgrade(coord);
                     world.spawn_floor(coord);
@@ -127,7 +165,11 @@ pub fn from_str(s: &str, player_data: EntityData) -> Terrain {
                     world.spawn_floor(coord);
                 }
                 'h' => {
-                    world.spawn_medkit(coord);
+                    world.spawn_consumable(coord, Consumable::Medkit);
+                    world.spawn_floor(coord);
+                }
+                'e' => {
+                    world.spawn_magnetic_boots(coord);
                     world.spawn_floor(coord);
                 }
                 '0'..='5' => {
@@ -144,10 +186,13 @@ pub fn from_str(s: &str, player_data: EntityData) -> Terrain {
                     world.spawn_ranged_weapon(coord, weapon);
                     world.spawn_floor(coord);
                 }
-                '6'..='6' => {
+                '6'..='9' => {
                     use MeleeWeapon::*;
                     let weapon = match ch {
                         '6' => Chainsaw,
+                        '7' => Crowbar,
+                        '8' => ShockBaton,
+                        '9' => FireAxe,
                         _ => panic!(),
                     };
                     world.spawn_melee_weapon(coord, weapon);
@@ -211,11 +256,11 @@ pub fn space_station_first_floor<R: Rng>(
                 world.spawn_floor(coord);
                 world.spawn_wall(coord);
             }
-            GameCell::Floor => {
+            GameCell::Floor | GameCell::VaultFloor => {
                 world.spawn_floor(coord);
             }
             GameCell::Space => {}
-            GameCell::Door(axis) => {
+            GameCell::Door(axis) | GameCell::LockedDoor(axis) => {
                 above_door = Some(coord - Coord::new(0, 1));
                 door_coord = Some(coord);
                 world.spawn_floor(coord);
@@ -327,6 +372,7 @@ pub fn space_station<R: Rng>(
     let mut player_data = Some(player_data);
     let mut player = None;
     let mut empty_coords = Vec::new();
+    let mut vault_coords = Vec::new();
     for (coord, cell) in grid.enumerate() {
         let coord = coord + SHIP_OFFSET;
         use procgen::GameCell;
@@ -339,11 +385,19 @@ pub fn space_station<R: Rng>(
                 empty_coords.push(coord);
                 world.spawn_floor(coord);
             }
+            GameCell::VaultFloor => {
+                vault_coords.push(coord);
+                world.spawn_floor(coord);
+            }
             GameCell::Space => {}
             GameCell::Door(axis) => {
                 world.spawn_floor(coord);
                 world.spawn_door(coord, *axis);
             }
+            GameCell::LockedDoor(axis) => {
+                world.spawn_floor(coord);
+                world.spawn_locked_door(coord, *axis);
+            }
             GameCell::Window(axis) => {
                 world.spawn_floor(coord);
                 world.spawn_window(coord, *axis);
@@ -372,6 +426,7 @@ pub fn space_station<R: Rng>(
             }
         }
     }
+    spawn_wall_lights(&mut world, rng);
     empty_coords.shuffle(rng);
     if spec.demo {
         for _ in 0..2 {
@@ -382,6 +437,8 @@ pub fn space_station<R: Rng>(
         }
     } else {
         spawn_items(level, &mut empty_coords, &mut world, terrain_state, rng);
+        spawn_vault(vault_coords, &mut empty_coords, &mut world, rng);
+        world.lights_on = !terrain_state.blackout_floors.contains(&level);
     }
     let player = player.expect("didn't create player");
     Terrain {
@@ -412,6 +469,7 @@ fn space_station_last_level<R: Rng>(
     let mut player_data = Some(player_data);
     let mut player = None;
     let mut empty_coords = Vec::new();
+    let mut vault_coords = Vec::new();
     let mut stairs_coord = None;
     for (coord, cell) in grid.enumerate() {
         let coord = coord + SHIP_OFFSET;
@@ -425,11 +483,19 @@ fn space_station_last_level<R: Rng>(
                 empty_coords.push(coord);
                 world.spawn_floor(coord);
             }
+            GameCell::VaultFloor => {
+                vault_coords.push(coord);
+                world.spawn_floor(coord);
+            }
             GameCell::Space => {}
             GameCell::Door(axis) => {
                 world.spawn_floor(coord);
                 world.spawn_door(coord, *axis);
             }
+            GameCell::LockedDoor(axis) => {
+                world.spawn_floor(coord);
+                world.spawn_locked_door(coord, *axis);
+            }
             GameCell::Window(axis) => {
                 world.spawn_floor(coord);
                 world.spawn_window(coord, *axis);
@@ -458,6 +524,7 @@ fn space_station_last_level<R: Rng>(
         }
     }
     let stairs_coord = stairs_coord.unwrap();
+    spawn_wall_lights(&mut world, rng);
     empty_coords.shuffle(rng);
     let mut empty_coords = empty_coords
         .into_iter()
@@ -501,7 +568,9 @@ fn space_station_last_level<R: Rng>(
             .unwrap(),
         fuel_light,
     );
+    spawn_guardian(stairs_coord, &mut empty_coords, &mut world);
     spawn_items(level, &mut empty_coords, &mut world, terrain_state, rng);
+    spawn_vault(vault_coords, &mut empty_coords, &mut world, rng);
     let player = player.expect("didn't create player");
     Terrain {
         world,
@@ -510,11 +579,38 @@ fn space_station_last_level<R: Rng>(
     }
 }
 
+/// Places the reactor guardian on the free cell closest to the fuel hatch, with its back to the
+/// hatch
+fn spawn_guardian(stairs_coord: Coord, empty_coords: &mut Vec<Coord>, world: &mut World) {
+    let index = empty_coords
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &coord)| coord.manhattan_distance(stairs_coord))
+        .map(|(index, _)| index);
+    if let Some(index) = index {
+        let coord = empty_coords.swap_remove(index);
+        let away_from_hatch = coord - stairs_coord;
+        let facing = if away_from_hatch.x.abs() > away_from_hatch.y.abs() {
+            if away_from_hatch.x > 0 {
+                CardinalDirection::East
+            } else {
+                CardinalDirection::West
+            }
+        } else if away_from_hatch.y > 0 {
+            CardinalDirection::South
+        } else {
+            CardinalDirection::North
+        };
+        world.spawn_guardian(coord, facing);
+    }
+}
+
 struct EnemyCounts {
     zombie: Vec<usize>,
     skeleton: Vec<usize>,
     boomer: Vec<usize>,
     tank: Vec<usize>,
+    drone: Vec<usize>,
 }
 
 impl EnemyCounts {
@@ -524,16 +620,83 @@ impl EnemyCounts {
             skeleton: vec![1, 1, 1, 2, 2],
             boomer: vec![0, 0, 1, 1, 2],
             tank: vec![0, 0, 0, 1, 2],
+            drone: vec![0, 1, 1, 2, 2],
+        }
+    }
+}
+
+/// Mounts lights on a spread out selection of the walls which have open floor below them
+fn spawn_wall_lights<R: Rng>(world: &mut World, rng: &mut R) {
+    let mut candidates = world
+        .components
+        .tile
+        .iter()
+        .filter(|(_, &tile)| tile == Tile::Wall)
+        .filter_map(|(entity, _)| {
+            let coord = world.spatial_table.coord_of(entity)?;
+            let layers = world.spatial_table.layers_at(coord + Coord::new(0, 1))?;
+            if layers.floor.is_some() && layers.feature.is_none() {
+                Some((entity, coord))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    candidates.shuffle(rng);
+    let mut placed = Vec::new();
+    for (wall, coord) in candidates {
+        if placed.len() >= NUM_WALL_LIGHTS {
+            break;
+        }
+        if placed
+            .iter()
+            .all(|&other: &Coord| other.manhattan_distance(coord) >= WALL_LIGHT_SPACING)
+        {
+            world.spawn_wall_light(wall, coord + Coord::new(0, 1));
+            placed.push(coord);
         }
     }
 }
 
+/// Removes and returns the first coord with no features around it, so that solid features placed
+/// there won't block corridors or doorways
+fn pop_coord_clear_of_features(empty_coords: &mut Vec<Coord>, world: &World) -> Option<Coord> {
+    let index = empty_coords.iter().position(|&coord| {
+        Directions.into_iter().all(|direction| {
+            world
+                .spatial_table
+                .layers_at(coord + direction.coord())
+                .map(|layers| layers.feature.is_none())
+                .unwrap_or(true)
+        })
+    })?;
+    Some(empty_coords.swap_remove(index))
+}
+
+/// A random selection of wares for a vendor terminal. The weapon on sale is drawn from the same
+/// pool as the weapons lying around the station, but stays in the pool.
+fn vendor_stock<R: Rng>(terrain_state: &TerrainState, rng: &mut R) -> Vec<Ware> {
+    let mut stock = Vec::new();
+    if let Some(&ranged_weapon) = terrain_state.ranged_weapons.choose(rng) {
+        stock.push(Ware::RangedWeapon(ranged_weapon));
+    }
+    stock.push(Ware::Consumable(Consumable::Medkit));
+    stock.push(Ware::Consumable(Consumable::OxygenCanister));
+    if rng.gen::<bool>() {
+        stock.push(Ware::Consumable(Consumable::Grenade));
+    }
+    for &ammo_type in AmmoType::ALL.choose_multiple(rng, 2) {
+        stock.push(Ware::Ammo(ammo_type));
+    }
+    stock
+}
+
 fn spawn_items<R: Rng>(
     level: u32,
     empty_coords: &mut Vec<Coord>,
     world: &mut World,
     terrain_state: &mut TerrainState,
-    _rng: &mut R,
+    rng: &mut R,
 ) {
     for _ in 0..2 {
         if let Some(coord) = empty_coords.pop() {
@@ -545,9 +708,34 @@ fn spawn_items<R: Rng>(
             world.spawn_credit(coord, 1);
         }
     }
-    for _ in 0..1 {
+    // ammo for each weapon the player is carrying, and some for a weapon they might find
+    let mut ammo_types = world
+        .components
+        .player
+        .iter()
+        .flat_map(|(_, player)| player.ranged_weapons.iter().flatten())
+        .filter_map(|weapon| weapon.name.ammo_type())
+        .collect::<Vec<_>>();
+    ammo_types.push(*AmmoType::ALL.choose(rng).unwrap());
+    let mut unique_ammo_types = Vec::new();
+    for ammo_type in ammo_types {
+        if !unique_ammo_types.contains(&ammo_type) {
+            unique_ammo_types.push(ammo_type);
+        }
+    }
+    for ammo_type in unique_ammo_types {
+        if let Some(coord) = empty_coords.pop() {
+            world.spawn_ammo(coord, ammo_type);
+        }
+    }
+    for consumable in [
+        Consumable::Medkit,
+        Consumable::Grenade,
+        Consumable::OxygenCanister,
+        Consumable::RepairKit,
+    ] {
         if let Some(coord) = empty_coords.pop() {
-            world.spawn_medkit(coord);
+            world.spawn_consumable(coord, consumable);
         }
     }
     for _ in 0..2 {
@@ -555,36 +743,41 @@ fn spawn_items<R: Rng>(
             world.spawn_ranged_weapon(coord, terrain_state.ranged_weapons.pop().unwrap());
         }
     }
-    if terrain_state.chainsaw_floors.contains(&level) {
+    if let Some(&melee_weapon) = terrain_state.melee_weapons.get(&level) {
         if let Some(coord) = empty_coords.pop() {
-            world.spawn_melee_weapon(coord, MeleeWeapon::Chainsaw);
+            world.spawn_melee_weapon(coord, melee_weapon);
         }
     }
-    'outer1: for (i, &coord) in empty_coords.iter().enumerate() {
-        for direction in Directions {
-            let nei = coord + direction.coord();
-            if let Some(layers) = world.spatial_table.layers_at(nei) {
-                if layers.feature.is_some() {
-                    continue 'outer1;
-                }
-            }
+    if terrain_state.magnetic_boots_floor == level {
+        if let Some(coord) = empty_coords.pop() {
+            world.spawn_magnetic_boots(coord);
         }
-        world.spawn_upgrade(coord);
-        empty_coords.swap_remove(i);
-        break;
-    }
-    'outer2: for (i, &coord) in empty_coords.iter().enumerate() {
-        for direction in Directions {
-            let nei = coord + direction.coord();
-            if let Some(layers) = world.spatial_table.layers_at(nei) {
-                if layers.feature.is_some() {
-                    continue 'outer2;
-                }
-            }
+    }
+    if level == FLASHLIGHT_FLOOR {
+        if let Some(coord) = empty_coords.pop() {
+            world.spawn_flashlight(coord);
         }
+    }
+    if let Some(coord) = pop_coord_clear_of_features(empty_coords, world) {
+        world.spawn_upgrade(coord);
+    }
+    if let Some(coord) = pop_coord_clear_of_features(empty_coords, world) {
         world.spawn_map(coord);
-        empty_coords.swap_remove(i);
-        break;
+    }
+    if let Some(coord) = pop_coord_clear_of_features(empty_coords, world) {
+        world.spawn_vendor(coord, vendor_stock(terrain_state, rng));
+    }
+    if let Some(coord) = pop_coord_clear_of_features(empty_coords, world) {
+        world.spawn_workbench(coord, *WeaponAbility::FITTABLE.choose(rng).unwrap());
+    }
+    // venting the reactor room would let the player skip the guardian
+    if level != FINAL_LEVEL {
+        if let Some(coord) = pop_coord_clear_of_features(empty_coords, world) {
+            world.spawn_terminal(coord);
+        }
+    }
+    if let Some(coord) = empty_coords.pop() {
+        world.spawn_survivor(coord);
     }
     let index = level as usize - 1;
     let enemy_count = EnemyCounts::new();
@@ -608,6 +801,93 @@ fn spawn_items<R: Rng>(
             world.spawn_tank(coord);
         }
     }
+    for _ in 0..enemy_count.drone[index] {
+        if let Some(coord) = empty_coords.pop() {
+            world.spawn_drone(coord);
+        }
+    }
+}
+
+/// Fills the vault room with loot and places the keycard which opens it, either lying on the
+/// floor or carried by one of the enemies. Call this after the enemies have been spawned.
+fn spawn_vault<R: Rng>(
+    mut vault_coords: Vec<Coord>,
+    empty_coords: &mut Vec<Coord>,
+    world: &mut World,
+    rng: &mut R,
+) {
+    if vault_coords.is_empty() {
+        return;
+    }
+    vault_coords.shuffle(rng);
+    for _ in 0..2 {
+        if let Some(coord) = vault_coords.pop() {
+            world.spawn_credit(coord, 2);
+        }
+    }
+    if let Some(coord) = vault_coords.pop() {
+        let consumable = *[
+            Consumable::Medkit,
+            Consumable::Grenade,
+            Consumable::OxygenCanister,
+            Consumable::RepairKit,
+        ]
+        .choose(rng)
+        .unwrap();
+        world.spawn_consumable(coord, consumable);
+    }
+    if let Some(coord) = vault_coords.pop() {
+        world.spawn_ammo(coord, *AmmoType::ALL.choose(rng).unwrap());
+    }
+    let carriers = world
+        .components
+        .enemy
+        .iter()
+        .filter(|(_, &enemy)| !matches!(enemy, Enemy::Skeleton | Enemy::Guardian))
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    match carriers.choose(rng) {
+        Some(&carrier) if rng.gen::<bool>() => {
+            world.components.carries_keycard.insert(carrier, ());
+        }
+        _ => {
+            if let Some(coord) = empty_coords.pop() {
+                world.spawn_keycard(coord);
+            }
+        }
+    }
 }
 
 pub const FINAL_LEVEL: u32 = 5;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    #[test]
+    fn vendors_always_stock_the_basics_and_two_kinds_of_ammo() {
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        let terrain_state = TerrainState::new(&mut rng);
+        for _ in 0..20 {
+            let stock = vendor_stock(&terrain_state, &mut rng);
+            assert!(stock.contains(&Ware::Consumable(Consumable::Medkit)));
+            assert!(stock.contains(&Ware::Consumable(Consumable::OxygenCanister)));
+            let ammo = stock
+                .iter()
+                .filter_map(|&ware| match ware {
+                    Ware::Ammo(ammo_type) => Some(ammo_type),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(ammo.len(), 2);
+            assert_ne!(ammo[0], ammo[1]);
+            for ware in stock {
+                if let Ware::RangedWeapon(ranged_weapon) = ware {
+                    assert!(terrain_state.ranged_weapons.contains(&ranged_weapon));
+                }
+            }
+        }
+    }
+}
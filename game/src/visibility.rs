@@ -3,6 +3,7 @@ use crate::{
     Entity,
 };
 use gridbugs::{
+    direction::Direction,
     grid_2d::{Coord, CoordIter, Grid, GridEnumerate, Size},
     rgb_int::Rgb24,
     shadowcast::{vision_distance, Context as ShadowcastContext, DirectionBitmap, InputGrid},
@@ -10,10 +11,30 @@ use gridbugs::{
 use serde::{Deserialize, Serialize};
 
 const AMBIENT_COL: Rgb24 = Rgb24::new_grey(31);
+/// Ambient light once the station lights have been switched off
+const AMBIENT_COL_DARK: Rgb24 = Rgb24::new_grey(0);
 const VISION_DISTANCE_SQUARED: u32 = 400;
 pub const VISION_DISTANCE: vision_distance::Circle =
     vision_distance::Circle::new_squared(VISION_DISTANCE_SQUARED);
 
+const FLASHLIGHT: Light = Light {
+    colour: Rgb24::new(255, 250, 220),
+    vision_distance: vision_distance::Circle::new_squared(150),
+    diminish_numerator: 1,
+    diminish_denominator: 12,
+};
+
+/// Whether a cell is lit by a flashlight beam shining from the origin in the given direction.
+/// The beam is about 70 degrees wide.
+fn in_flashlight_beam(origin: Coord, direction: Direction, coord: Coord) -> bool {
+    let delta = coord - origin;
+    let facing = direction.coord();
+    let dot = delta.x * facing.x + delta.y * facing.y;
+    let delta_magnitude2 = delta.x * delta.x + delta.y * delta.y;
+    let facing_magnitude2 = facing.x * facing.x + facing.y * facing.y;
+    delta_magnitude2 == 0 || (dot > 0 && 3 * dot * dot >= 2 * delta_magnitude2 * facing_magnitude2)
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Light {
     pub colour: Rgb24,
@@ -161,6 +182,7 @@ impl VisibilityGrid {
     pub fn update(
         &mut self,
         player_coord: Coord,
+        flashlight_beam: Option<Direction>,
         world: &World,
         shadowcast_context: &mut ShadowcastContext<u8>,
         omniscient: Option<Omniscient>,
@@ -207,6 +229,11 @@ impl VisibilityGrid {
                 }
             }
         } else {
+            let ambient_col = if world.lights_on {
+                AMBIENT_COL
+            } else {
+                AMBIENT_COL_DARK
+            };
             shadowcast_context.for_each_visible(
                 player_coord,
                 &Visibility,
@@ -218,11 +245,17 @@ impl VisibilityGrid {
                     cell.last_seen_next = count;
                     cell.visible_directions = visible_directions;
                     cell.last_lit = count;
-                    cell.light_colour = AMBIENT_COL;
+                    cell.light_colour = ambient_col;
                 },
             );
         }
-        for (light_coord, light) in world.all_lights_by_coord() {
+        let flashlight =
+            flashlight_beam.map(|direction| (player_coord, &FLASHLIGHT, Some(direction)));
+        for (light_coord, light, beam) in world
+            .all_lights_by_coord()
+            .map(|(light_coord, light)| (light_coord, light, None))
+            .chain(flashlight)
+        {
             shadowcast_context.for_each_visible(
                 light_coord,
                 &Visibility,
@@ -230,6 +263,11 @@ impl VisibilityGrid {
                 light.vision_distance,
                 255,
                 |cell_coord, visible_directions, visibility| {
+                    if !beam.is_none_or(|direction| {
+                        in_flashlight_beam(light_coord, direction, cell_coord)
+                    }) {
+                        return;
+                    }
                     let cell = grid.get_checked_mut(cell_coord);
                     if cell.last_seen_next == count
                         && !(visible_directions & cell.visible_directions).is_empty()
@@ -1,5 +1,8 @@
-use crate::{player, ActionError, Config, ExternalEvent, GameControlFlow, Input};
-use gridbugs::direction::CardinalDirection;
+use crate::{
+    perk, player, ActionError, Config, Consumable, Entity, ExternalEvent, GameControlFlow, Input,
+    TerminalCommand, Ware,
+};
+use gridbugs::{coord_2d::Coord, direction::Direction};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -45,6 +48,21 @@ pub struct Running(Private);
 #[derive(Debug)]
 pub struct Upgrade(Private);
 #[derive(Debug)]
+pub struct Workbench {
+    private: Private,
+    ability: player::WeaponAbility,
+}
+#[derive(Debug)]
+pub struct Vendor {
+    private: Private,
+    vendor: Entity,
+}
+#[derive(Debug)]
+pub struct Terminal {
+    private: Private,
+    terminal: Entity,
+}
+#[derive(Debug)]
 pub struct GetRangedWeapon(Private);
 #[derive(Debug)]
 pub struct GetMeleeWeapon(Private);
@@ -55,6 +73,14 @@ pub struct FireWeapon {
 }
 #[derive(Debug)]
 pub struct UnlockMap(Private);
+#[derive(Debug)]
+pub struct ThrowGrenade(Private);
+#[derive(Debug)]
+pub struct Inventory(Private);
+#[derive(Debug)]
+pub struct Reload(Private);
+#[derive(Debug)]
+pub struct Shove(Private);
 
 #[derive(Debug)]
 pub struct GameOver {
@@ -66,9 +92,16 @@ pub struct GameOver {
 pub enum Witness {
     Running(Running),
     Upgrade(Upgrade),
+    Workbench(Workbench),
+    Vendor(Vendor),
+    Terminal(Terminal),
     GetRangedWeapon(GetRangedWeapon),
     GetMeleeWeapon(GetMeleeWeapon),
     FireWeapon(FireWeapon),
+    ThrowGrenade(ThrowGrenade),
+    Inventory(Inventory),
+    Reload(Reload),
+    Shove(Shove),
     GameOver(GameOver),
     UnlockMap(UnlockMap),
     Win,
@@ -81,13 +114,22 @@ impl Witness {
     fn upgrade(private: Private) -> Self {
         Self::Upgrade(Upgrade(private))
     }
+    fn workbench(private: Private, ability: player::WeaponAbility) -> Self {
+        Self::Workbench(Workbench { private, ability })
+    }
+    fn vendor(private: Private, vendor: Entity) -> Self {
+        Self::Vendor(Vendor { private, vendor })
+    }
+    fn terminal(private: Private, terminal: Entity) -> Self {
+        Self::Terminal(Terminal { private, terminal })
+    }
     fn unlock_map(private: Private) -> Self {
         Self::UnlockMap(UnlockMap(private))
     }
 }
 
 pub enum ControlInput {
-    Walk(CardinalDirection),
+    Walk(Direction),
     Wait,
 }
 
@@ -115,7 +157,7 @@ impl Running {
     pub fn walk(
         self,
         game: &mut Game,
-        direction: CardinalDirection,
+        direction: Direction,
         config: &Config,
     ) -> (Witness, Result<(), ActionError>) {
         let Self(private) = self;
@@ -127,7 +169,17 @@ impl Running {
         game.witness_handle_input(Input::Wait, config, private)
     }
 
-    pub fn get(self, game: &Game) -> (Witness, Result<(), ActionError>) {
+    /// Switching the flashlight on or off doesn't take a turn
+    pub fn toggle_flashlight(
+        self,
+        game: &mut Game,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self(private) = self;
+        game.witness_handle_input(Input::ToggleFlashlight, config, private)
+    }
+
+    pub fn get(self, game: &mut Game, config: &Config) -> (Witness, Result<(), ActionError>) {
         if let Some(weapon) = game.inner_ref().weapon_under_player() {
             if weapon.is_ranged() {
                 let Self(private) = self;
@@ -138,16 +190,26 @@ impl Running {
                 return (Witness::GetMeleeWeapon(GetMeleeWeapon(private)), Ok(()));
             }
         }
+        if game.inner_ref().consumable_under_player().is_some() {
+            let Self(private) = self;
+            return game.witness_handle_input(Input::PickUpItem, config, private);
+        }
         (self.into_witness(), Err(ActionError::NoItemToGet))
     }
 
+    /// Firing a weapon with an empty magazine reloads it instead, if there's ammo to spare
     pub fn fire_weapon(
         self,
-        game: &Game,
+        game: &mut Game,
         slot: player::RangedWeaponSlot,
+        config: &Config,
     ) -> (Witness, Result<(), ActionError>) {
         if let Some(weapon) = game.inner_game.player().weapon_in_slot(slot) {
             if weapon.ammo.unwrap().current == 0 {
+                if game.inner_game.reloadable_slots().contains(&slot) {
+                    let Self(private) = self;
+                    return game.witness_handle_input(Input::Reload(slot), config, private);
+                }
                 return (
                     self.into_witness(),
                     Err(ActionError::WeaponOutOfAmmo(weapon.name)),
@@ -159,13 +221,48 @@ impl Running {
         }
         (self.into_witness(), Err(ActionError::NoWeaponInSlot(slot)))
     }
+
+    pub fn throw_grenade(self, game: &Game) -> (Witness, Result<(), ActionError>) {
+        if game
+            .inner_game
+            .player()
+            .inventory
+            .count(Consumable::Grenade)
+            == 0
+        {
+            return (self.into_witness(), Err(ActionError::NoGrenades));
+        }
+        let Self(private) = self;
+        (Witness::ThrowGrenade(ThrowGrenade(private)), Ok(()))
+    }
+
+    pub fn reload(self, game: &Game) -> (Witness, Result<(), ActionError>) {
+        if game.inner_game.reloadable_slots().is_empty() {
+            return (self.into_witness(), Err(ActionError::NothingToReload));
+        }
+        let Self(private) = self;
+        (Witness::Reload(Reload(private)), Ok(()))
+    }
+
+    pub fn shove(self) -> Witness {
+        let Self(private) = self;
+        Witness::Shove(Shove(private))
+    }
+
+    pub fn inventory(self, game: &Game) -> (Witness, Result<(), ActionError>) {
+        if game.inner_game.player().inventory.is_empty() {
+            return (self.into_witness(), Err(ActionError::InventoryEmpty));
+        }
+        let Self(private) = self;
+        (Witness::Inventory(Inventory(private)), Ok(()))
+    }
 }
 
 impl Upgrade {
     pub fn commit(
         self,
         game: &mut Game,
-        upgrade: player::Upgrade,
+        upgrade: perk::Perk,
         config: &Config,
     ) -> (Witness, Result<(), ActionError>) {
         let Self(private) = self;
@@ -179,6 +276,71 @@ impl Upgrade {
     }
 }
 
+impl Workbench {
+    /// The ability this workbench can add to a weapon
+    pub fn ability(&self) -> player::WeaponAbility {
+        self.ability
+    }
+
+    pub fn commit(
+        self,
+        game: &mut Game,
+        weapon: player::HeldWeapon,
+        weapon_mod: player::WeaponMod,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self { private, .. } = self;
+        let input = Input::ModWeapon { weapon, weapon_mod };
+        game.witness_handle_input(input, config, private)
+    }
+
+    pub fn cancel(self) -> Witness {
+        let Self { private, .. } = self;
+        Witness::running(private)
+    }
+}
+
+impl Vendor {
+    /// The wares the vendor has left to sell
+    pub fn stock<'a>(&self, game: &'a Game) -> &'a [Ware] {
+        game.inner_game.vendor_stock(self.vendor)
+    }
+
+    pub fn commit(
+        self,
+        game: &mut Game,
+        index: usize,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self { private, vendor } = self;
+        let input = Input::Buy { vendor, index };
+        game.witness_handle_input(input, config, private)
+    }
+
+    pub fn cancel(self) -> Witness {
+        let Self { private, .. } = self;
+        Witness::running(private)
+    }
+}
+
+impl Terminal {
+    pub fn commit(
+        self,
+        game: &mut Game,
+        command: TerminalCommand,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self { private, terminal } = self;
+        let input = Input::UseTerminal { terminal, command };
+        game.witness_handle_input(input, config, private)
+    }
+
+    pub fn cancel(self) -> Witness {
+        let Self { private, .. } = self;
+        Witness::running(private)
+    }
+}
+
 impl GetRangedWeapon {
     pub fn commit(
         self,
@@ -219,12 +381,15 @@ impl FireWeapon {
         self.slot
     }
 
-    pub fn commit(self, game: &mut Game, direction: CardinalDirection, config: &Config) -> Witness {
+    pub fn commit(
+        self,
+        game: &mut Game,
+        target: Coord,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
         let Self { private, slot } = self;
-        let input = Input::Fire { direction, slot };
-        let (witness, result) = game.witness_handle_input(input, config, private);
-        let _ = result.unwrap();
-        witness
+        let input = Input::Fire { target, slot };
+        game.witness_handle_input(input, config, private)
     }
 
     pub fn cancel(self) -> Witness {
@@ -233,6 +398,89 @@ impl FireWeapon {
     }
 }
 
+impl ThrowGrenade {
+    pub fn commit(
+        self,
+        game: &mut Game,
+        target: Coord,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self(private) = self;
+        let input = Input::ThrowGrenade { target };
+        game.witness_handle_input(input, config, private)
+    }
+
+    pub fn cancel(self) -> Witness {
+        let Self(private) = self;
+        Witness::running(private)
+    }
+}
+
+impl Inventory {
+    /// Grenades can't be used in place, so choosing one starts aiming a throw instead
+    pub fn use_item(
+        self,
+        game: &mut Game,
+        index: usize,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self(private) = self;
+        if game.inner_game.player().inventory.get(index) == Some(Consumable::Grenade) {
+            return (Witness::ThrowGrenade(ThrowGrenade(private)), Ok(()));
+        }
+        game.witness_handle_input(Input::UseItem(index), config, private)
+    }
+
+    pub fn drop_item(
+        self,
+        game: &mut Game,
+        index: usize,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self(private) = self;
+        game.witness_handle_input(Input::DropItem(index), config, private)
+    }
+
+    pub fn cancel(self) -> Witness {
+        let Self(private) = self;
+        Witness::running(private)
+    }
+}
+
+impl Reload {
+    pub fn commit(
+        self,
+        game: &mut Game,
+        slot: player::RangedWeaponSlot,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self(private) = self;
+        game.witness_handle_input(Input::Reload(slot), config, private)
+    }
+
+    pub fn cancel(self) -> Witness {
+        let Self(private) = self;
+        Witness::running(private)
+    }
+}
+
+impl Shove {
+    pub fn commit(
+        self,
+        game: &mut Game,
+        direction: Direction,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self(private) = self;
+        game.witness_handle_input(Input::Shove(direction), config, private)
+    }
+
+    pub fn cancel(self) -> Witness {
+        let Self(private) = self;
+        Witness::running(private)
+    }
+}
+
 impl GameOver {
     pub fn typ(&self) -> GameOverType {
         self.typ
@@ -272,6 +520,13 @@ impl Game {
             Err(e) => (Witness::running(private), Err(e)),
             Ok(None) => (Witness::running(private), Ok(())),
             Ok(Some(GameControlFlow::Upgrade)) => (Witness::upgrade(private), Ok(())),
+            Ok(Some(GameControlFlow::Vendor(vendor))) => (Witness::vendor(private, vendor), Ok(())),
+            Ok(Some(GameControlFlow::Terminal(terminal))) => {
+                (Witness::terminal(private, terminal), Ok(()))
+            }
+            Ok(Some(GameControlFlow::Workbench(ability))) => {
+                (Witness::workbench(private, ability), Ok(()))
+            }
             Ok(Some(GameControlFlow::GameOver)) => {
                 let game_over = if self.inner_game.is_adrift() {
                     GameOver {
@@ -301,6 +556,9 @@ impl Game {
         match self.inner_game.handle_tick(since_last_tick, config) {
             None => Witness::running(private),
             Some(GameControlFlow::Upgrade) => Witness::upgrade(private),
+            Some(GameControlFlow::Workbench(ability)) => Witness::workbench(private, ability),
+            Some(GameControlFlow::Vendor(vendor)) => Witness::vendor(private, vendor),
+            Some(GameControlFlow::Terminal(terminal)) => Witness::terminal(private, terminal),
             Some(GameControlFlow::GameOver) => {
                 let game_over = if self.inner_game.is_adrift() {
                     GameOver {
@@ -1,28 +1,70 @@
 use crate::{
     behaviour::Agent,
     world::{
-        data::{DoorState, MeleeWeapon, OnCollision, ProjectileDamage, RangedWeapon, Tile},
-        explosion, player,
+        data::{
+            AmmoType, CollidesWith, DoorState, Location, MeleeWeapon, OnCollision,
+            ProjectileDamage, RangedWeapon, Tile,
+        },
+        explosion, noise,
+        perk::PerkRule,
+        player,
         player::WeaponName,
-        realtime, ActionError, ExternalEvent, World,
+        realtime, ExternalEvent, World,
     },
     Message, SoundEffect,
 };
 use gridbugs::{
     coord_2d::Coord,
-    direction::{CardinalDirection, Direction},
+    direction::{CardinalDirection, CardinalDirections, Direction},
     entity_table::{ComponentTable, Entity},
 };
 use rand::Rng;
 use std::time::Duration;
 
+/// A projectile's hull penetration percentage is converted into damage to the structure it hits
+/// at this rate
+const HULL_PEN_PER_DAMAGE: u32 = 20;
+
+/// Damage dealt to each structure next to a window when it shatters
+const WINDOW_SHATTER_DAMAGE: u32 = 3;
+
 #[derive(Clone, Copy, Debug)]
 pub enum Error {
     WalkIntoSolidCell,
     CannotAffordUpgrade,
+    PerkUnavailable,
     NoItemToGet,
     NoWeaponInSlot(player::RangedWeaponSlot),
     WeaponOutOfAmmo(player::WeaponName),
+    WalkOffHull,
+    NoGrenades,
+    InventoryEmpty,
+    InventoryFull,
+    NoItemInInventorySlot,
+    NoSpaceToDrop,
+    NothingToRepair,
+    AlreadyAtFullHealth,
+    AlreadyAtFullOxygen,
+    NothingToReload,
+    MagazineFull(player::WeaponName),
+    NoReserveAmmo(player::WeaponName),
+    CannotAffordWeaponMod,
+    CannotFitWeaponMod,
+    CannotAffordWare,
+    SoldOut,
+    NoSpaceToDispense,
+    AmmoReserveFull(AmmoType),
+    NoRoomForAmmo(AmmoType),
+    NothingToShove,
+    TooHeavyToShove,
+    NoSpaceToShove,
+    TerminalOffline,
+    NothingToUnlock,
+    NoWindowsToVent,
+    NoFlashlight,
+    FlashlightBatteryFlat,
+    CannotAimThere,
+    CannotMoveThatWay,
 }
 
 const KNOCKBACK: usize = 3;
@@ -34,6 +76,9 @@ impl World {
         direction: CardinalDirection,
         _rng: &mut R,
     ) {
+        if self.is_held_to_hull(character) {
+            return;
+        }
         let current_coord = if let Some(coord) = self.spatial_table.coord_of(character) {
             coord
         } else {
@@ -46,6 +91,13 @@ impl World {
                     return;
                 }
             }
+            if cell.floor.is_none()
+                && self.has_magnetic_boots(character)
+                && !self.is_hull_exterior_at_coord(target_coord)
+            {
+                // magnetic boots keep the character attached to the hull
+                return;
+            }
         } else {
             return;
         }
@@ -58,27 +110,38 @@ impl World {
     pub fn character_walk_in_direction<R: Rng>(
         &mut self,
         character: Entity,
-        direction: CardinalDirection,
+        direction: Direction,
         rng: &mut R,
         external_events: &mut Vec<ExternalEvent>,
         message_log: &mut Vec<Message>,
     ) -> Result<Option<crate::GameControlFlow>, Error> {
-        if let Some(move_half_speed) = self.components.move_half_speed.get_mut(character) {
-            if move_half_speed.skip_next_move {
-                move_half_speed.skip_next_move = false;
-                return Ok(None);
-            }
-            move_half_speed.skip_next_move = true;
+        if self.skip_move_due_to_status_effect(character) {
+            return Ok(None);
         }
+        self.turn_character(character, direction);
         let current_coord = if let Some(coord) = self.spatial_table.coord_of(character) {
             coord
         } else {
             panic!("failed to find coord for {:?}", character);
         };
+        if self.is_diagonal_step_blocked(current_coord, direction) {
+            return Err(Error::WalkIntoSolidCell);
+        }
         let target_coord = current_coord + direction.coord();
         if let Some(&cell) = self.spatial_table.layers_at(target_coord) {
             if let Some(feature_entity) = cell.feature {
                 if self.components.solid.contains(feature_entity) {
+                    if let Some(DoorState::Locked) =
+                        self.components.door_state.get(feature_entity).cloned()
+                    {
+                        if !self.character_try_unlock_door(
+                            character,
+                            feature_entity,
+                            message_log,
+                        )? {
+                            return Ok(None);
+                        }
+                    }
                     if let Some(DoorState::Closed) =
                         self.components.door_state.get(feature_entity).cloned()
                     {
@@ -89,6 +152,11 @@ impl World {
                             }
                         }
                         self.open_door(feature_entity);
+                        if self.has_melee_ability(character, player::WeaponAbility::Pry) {
+                            // the door is wedged open and won't close by itself
+                            self.components.door_close_countdown.remove(feature_entity);
+                            message_log.push(Message::PryDoor);
+                        }
                         return Ok(None);
                     }
                     if self.components.upgrade.contains(feature_entity) {
@@ -98,6 +166,29 @@ impl World {
                             return Err(Error::WalkIntoSolidCell);
                         }
                     }
+                    if let Some(&online) = self.components.terminal.get(feature_entity) {
+                        if !self.components.player.contains(character) {
+                            return Err(Error::WalkIntoSolidCell);
+                        } else if online {
+                            return Ok(Some(crate::GameControlFlow::Terminal(feature_entity)));
+                        } else {
+                            return Err(Error::TerminalOffline);
+                        }
+                    }
+                    if self.components.vendor.contains(feature_entity) {
+                        if self.components.player.contains(character) {
+                            return Ok(Some(crate::GameControlFlow::Vendor(feature_entity)));
+                        } else {
+                            return Err(Error::WalkIntoSolidCell);
+                        }
+                    }
+                    if let Some(&ability) = self.components.workbench.get(feature_entity) {
+                        if self.components.player.contains(character) {
+                            return Ok(Some(crate::GameControlFlow::Workbench(ability)));
+                        } else {
+                            return Err(Error::WalkIntoSolidCell);
+                        }
+                    }
                     if let Some(&locked) = self.components.map.get(feature_entity) {
                         if locked {
                             return Ok(Some(crate::GameControlFlow::UnlockMap));
@@ -105,6 +196,17 @@ impl World {
                             message_log.push(Message::MapTerminal);
                         }
                     }
+                    if self.components.destructible.contains(feature_entity)
+                        && self.has_melee_ability(character, player::WeaponAbility::Breach)
+                    {
+                        self.character_hack_at_structure(
+                            character,
+                            feature_entity,
+                            rng,
+                            external_events,
+                        );
+                        return Ok(None);
+                    }
                     return Err(Error::WalkIntoSolidCell);
                 }
                 if let Some(&locked) = self.components.map.get(feature_entity) {
@@ -115,6 +217,12 @@ impl World {
                     }
                 }
             }
+            if cell.floor.is_none()
+                && self.has_magnetic_boots(character)
+                && !self.is_hull_exterior_at_coord(target_coord)
+            {
+                return Err(Error::WalkOffHull);
+            }
         } else {
             return Err(Error::WalkIntoSolidCell);
         }
@@ -135,15 +243,26 @@ impl World {
         Ok(None)
     }
 
+    fn turn_character(&mut self, character: Entity, direction: Direction) {
+        self.components.facing.insert(character, direction);
+        if let Some(tile @ Tile::Guardian(_)) = self.components.tile.get_mut(character) {
+            *tile = Tile::Guardian(direction);
+        }
+    }
+
     fn player_melee_attack<R: Rng>(
         &mut self,
         attacker: Entity,
         victim: Entity,
-        direction: CardinalDirection,
+        direction: Direction,
         rng: &mut R,
         external_events: &mut Vec<ExternalEvent>,
         message_log: &mut Vec<Message>,
     ) {
+        let weak_point_hit = self.is_boss_weak_point_hit(victim, direction.coord());
+        let armour = self
+            .armour_against(victim, direction.coord())
+            .expect("npc lacks armour");
         let player = self.components.player.get_mut(attacker).unwrap();
         let remove = if let Some(ammo) = player.melee_weapon.ammo.as_mut() {
             ammo.current = ammo.current.saturating_sub(1);
@@ -162,17 +281,18 @@ impl World {
                 weapon: player.melee_weapon.name,
             });
         }
-        let pen = player.melee_pen();
-        if pen
-            >= self
-                .components
-                .armour
-                .get(victim)
-                .expect("npc lacks armour")
-                .value
-        {
+        let penetrated = player.melee_pen() >= armour;
+        if penetrated {
             let mut dmg = player.melee_dmg();
-            if player.traits.double_damage {
+            if player.perks.has_rule(PerkRule::DoubleDamage) {
+                dmg *= 2;
+            }
+            if rng.gen_range(0..100) < player.critical_hit_percent() {
+                message_log.push(Message::CriticalHit);
+                dmg *= 2;
+            }
+            if weak_point_hit {
+                message_log.push(Message::GuardianWeakPointHit);
                 dmg *= 2;
             }
             self.damage_character(victim, dmg, rng, external_events, message_log);
@@ -193,7 +313,13 @@ impl World {
                         .build(),
                     );
                 }
-                _ => (),
+                _ => {
+                    if let Some(effect) = ability.status_effect() {
+                        if penetrated {
+                            self.apply_status_effect(victim, effect, message_log);
+                        }
+                    }
+                }
             }
         }
         let player = self.components.player.get_mut(attacker).unwrap();
@@ -218,21 +344,24 @@ impl World {
         if self.components.push_back.contains(attacker) {
             let attacker_coord = self.spatial_table.coord_of(attacker).unwrap();
             let victim_coord = self.spatial_table.coord_of(victim).unwrap();
-            let direction = match victim_coord - attacker_coord {
-                Coord { x: 1, y: 0 } => Some(CardinalDirection::East),
-                Coord { x: 0, y: -1 } => Some(CardinalDirection::North),
-                Coord { x: -1, y: 0 } => Some(CardinalDirection::West),
-                Coord { x: 0, y: 1 } => Some(CardinalDirection::South),
-                _ => None,
-            };
-            if let Some(direction) = direction {
-                self.character_push_in_direction(victim, direction.direction());
-                self.character_push_in_direction(victim, direction.direction());
+            let delta = victim_coord - attacker_coord;
+            if delta != Coord::new(0, 0) && delta.x.abs() <= 1 && delta.y.abs() <= 1 {
+                let direction = Direction::from_unit_coord(delta);
+                self.character_push_in_direction(victim, direction);
+                self.character_push_in_direction(victim, direction);
             }
         }
-        if let Some(enemy) = self.components.enemy.get(attacker) {
-            message_log.push(Message::EnemyHitPlayer(*enemy));
+        if let Some(&enemy) = self.components.enemy.get(attacker) {
+            if self.components.player.contains(victim) {
+                message_log.push(Message::EnemyHitPlayer(enemy));
+            } else {
+                message_log.push(Message::EnemyHitSurvivor(enemy));
+            }
+        }
+        if let Some(&effect) = self.components.attack_status_effect.get(attacker) {
+            self.apply_status_effect(victim, effect, message_log);
         }
+        let damage = self.damage_from_enemy_attack(victim, damage);
         self.damage_character(victim, damage, rng, external_events, message_log);
     }
 
@@ -240,25 +369,57 @@ impl World {
         &mut self,
         attacker: Entity,
         victim: Entity,
-        direction: CardinalDirection,
+        direction: Direction,
         rng: &mut R,
         external_events: &mut Vec<ExternalEvent>,
         message_log: &mut Vec<Message>,
     ) {
+        if self.components.debris.contains(victim) {
+            return;
+        }
         if self.components.player.get(attacker).is_some() {
-            self.player_melee_attack(
-                attacker,
-                victim,
-                direction,
-                rng,
-                external_events,
-                message_log,
-            );
-        } else if self.components.player.get(victim).is_some() {
+            if self.is_friendly_npc(victim) {
+                self.swap_places(attacker, victim);
+            } else {
+                self.player_melee_attack(
+                    attacker,
+                    victim,
+                    direction,
+                    rng,
+                    external_events,
+                    message_log,
+                );
+            }
+        } else if self.is_hostile_npc(attacker)
+            && (self.components.player.get(victim).is_some() || self.is_friendly_npc(victim))
+        {
             self.npc_melee_attack(attacker, victim, rng, external_events, message_log);
         }
     }
 
+    /// Lets the player walk through friendly npcs rather than attacking them
+    fn swap_places(&mut self, a: Entity, b: Entity) {
+        let (a_coord, b_coord) = match (
+            self.spatial_table.coord_of(a),
+            self.spatial_table.coord_of(b),
+        ) {
+            (Some(a_coord), Some(b_coord)) => (a_coord, b_coord),
+            _ => return,
+        };
+        let b_location = *self.spatial_table.location_of(b).unwrap();
+        self.spatial_table.remove(b);
+        self.spatial_table.update_coord(a, b_coord).unwrap();
+        self.spatial_table
+            .update(
+                b,
+                Location {
+                    coord: a_coord,
+                    ..b_location
+                },
+            )
+            .unwrap();
+    }
+
     fn open_door(&mut self, door: Entity) {
         self.components.solid.remove(door);
         self.components.opacity.remove(door);
@@ -273,6 +434,9 @@ impl World {
         };
         self.components.tile.insert(door, Tile::DoorOpen(axis));
         self.components.door_close_countdown.insert(door, 4);
+        if let Some(coord) = self.spatial_table.coord_of(door) {
+            self.make_noise(coord, noise::loudness::DOOR);
+        }
     }
 
     fn close_door(&mut self, door: Entity) {
@@ -366,6 +530,53 @@ impl World {
         }
     }
 
+    pub fn process_ranged_attack_reload(&mut self) {
+        for (_, ranged_attack) in self.components.ranged_attack.iter_mut() {
+            ranged_attack.turns_until_ready = ranged_attack.turns_until_ready.saturating_sub(1);
+        }
+    }
+
+    pub fn npc_fire<R: Rng>(
+        &mut self,
+        npc: Entity,
+        target: Coord,
+        external_events: &mut Vec<ExternalEvent>,
+        rng: &mut R,
+    ) {
+        let npc_coord = self.spatial_table.coord_of(npc).unwrap();
+        if npc_coord == target {
+            return;
+        }
+        let ranged_attack = match self.components.ranged_attack.get_mut(npc) {
+            Some(ranged_attack) => ranged_attack,
+            None => return,
+        };
+        if ranged_attack.turns_until_ready > 0 {
+            return;
+        }
+        ranged_attack.turns_until_ready = ranged_attack.reload;
+        let ranged_attack = *ranged_attack;
+        // npcs aren't perfect shots, and a missed shot keeps going until it hits something
+        let mut aim = target - npc_coord;
+        if rng.gen_range(0..3) == 0 {
+            aim += Coord::new(rng.gen_range(-1..=1), rng.gen_range(-1..=1));
+        }
+        if aim.is_zero() {
+            aim = target - npc_coord;
+        }
+        external_events.push(ExternalEvent::SoundEffect(SoundEffect::Rifle));
+        self.make_noise(npc_coord, noise::loudness::QUIET_GUNSHOT);
+        self.spawn_projectile(
+            npc_coord,
+            npc_coord + (aim * 100),
+            ranged_attack.projectile_damage,
+            ranged_attack.light_colour,
+            false,
+            rng,
+        );
+        self.spawn_flash(npc_coord);
+    }
+
     pub fn character_fire_bullet<R: Rng>(
         &mut self,
         character: Entity,
@@ -402,12 +613,55 @@ impl World {
             };
             if let Some(sound_effect) = sound_effect {
                 external_events.push(ExternalEvent::SoundEffect(sound_effect));
+                let loudness = match sound_effect {
+                    SoundEffect::LifeStealer | SoundEffect::Oxidiser => {
+                        noise::loudness::QUIET_GUNSHOT
+                    }
+                    _ => noise::loudness::GUNSHOT,
+                };
+                self.make_noise(character_coord, loudness);
             }
             self.spawn_bullet(character_coord, target, &weapon, rng);
             self.spawn_flash(character_coord);
         }
     }
 
+    /// Refills the magazine of the weapon in a slot from the character's spare ammunition
+    pub fn character_reload(
+        &mut self,
+        character: Entity,
+        slot: player::RangedWeaponSlot,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        let player::Player {
+            ranged_weapons,
+            ammo_reserve,
+            ..
+        } = self
+            .components
+            .player
+            .get_mut(character)
+            .ok_or(Error::NoWeaponInSlot(slot))?;
+        let weapon = ranged_weapons
+            .get_mut(slot.index())
+            .and_then(|weapon| weapon.as_mut())
+            .ok_or(Error::NoWeaponInSlot(slot))?;
+        let (ammo, ammo_type) = match (weapon.ammo.as_mut(), weapon.name.ammo_type()) {
+            (Some(ammo), Some(ammo_type)) => (ammo, ammo_type),
+            _ => return Err(Error::NoWeaponInSlot(slot)),
+        };
+        if ammo.current >= ammo.max {
+            return Err(Error::MagazineFull(weapon.name));
+        }
+        let taken = ammo_reserve.take(ammo_type, ammo.max - ammo.current);
+        if taken == 0 {
+            return Err(Error::NoReserveAmmo(weapon.name));
+        }
+        ammo.current += taken;
+        message_log.push(Message::Reload(weapon.name));
+        Ok(())
+    }
+
     pub fn projectile_stop<R: Rng>(
         &mut self,
         projectile_entity: Entity,
@@ -481,6 +735,24 @@ impl World {
                             message_log,
                         );
                     }
+                    if let Some(&debris_damage) = self.components.debris.get(projectile_entity) {
+                        // debris is only carried with enough force to hurt while the air is
+                        // still rushing out of the station
+                        if self.air.is_equalising()
+                            && self.components.character.contains(character_entity)
+                        {
+                            if self.components.player.contains(character_entity) {
+                                message_log.push(Message::HitByDebris);
+                            }
+                            self.damage_character(
+                                character_entity,
+                                debris_damage,
+                                rng,
+                                external_events,
+                                message_log,
+                            );
+                        }
+                    }
                 }
                 if let Some(entity_in_cell) = spatial_cell.feature.or(spatial_cell.character) {
                     if (collides_with.solid && self.components.solid.contains(entity_in_cell))
@@ -491,17 +763,18 @@ impl World {
                         if let Some(&projectile_damage) =
                             self.components.projectile_damage.get(projectile_entity)
                         {
+                            if self.components.light_fixture.contains(entity_in_cell) {
+                                self.shoot_out_light(entity_in_cell, external_events, message_log);
+                            }
                             if self.components.destructible.contains(entity_in_cell) {
-                                let mut hull_pen_percent = projectile_damage.hull_pen_percent;
-                                for (_, player) in self.components.player.iter() {
-                                    if player.traits.reduce_hull_pen {
-                                        hull_pen_percent /= 2;
-                                    }
-                                    break;
-                                }
-                                if rng.gen_range(0..100) < hull_pen_percent {
-                                    self.components.remove_entity(entity_in_cell);
-                                    self.spatial_table.remove(entity_in_cell);
+                                let structure_damage =
+                                    self.structure_damage(projectile_damage.hull_pen_percent);
+                                if self.damage_structure(
+                                    entity_in_cell,
+                                    structure_damage,
+                                    rng,
+                                    external_events,
+                                ) {
                                     stop = false;
                                 }
                             }
@@ -517,12 +790,16 @@ impl World {
                         }
                     }
                 }
-                let _ignore_err = self
+                if self
                     .spatial_table
-                    .update_coord(projectile_entity, next_coord);
+                    .update_coord(projectile_entity, next_coord)
+                    .is_err()
+                {
+                    // debris occupies a layer, so it can run into other debris
+                    self.projectile_stop(projectile_entity, external_events, message_log, rng);
+                }
             } else {
                 self.projectile_stop(projectile_entity, external_events, message_log, rng);
-                return;
             }
         } else {
             self.components.remove_entity(projectile_entity);
@@ -531,6 +808,187 @@ impl World {
         }
     }
 
+    /// Sends an entity which has been pulled out of the station tumbling away into space. While
+    /// air is still escaping it's carried along with the stream of air, otherwise it drifts away
+    /// from the station. It keeps moving until it hits something or leaves the map.
+    pub fn start_drift<R: Rng>(&mut self, entity: Entity, damage: u32, rng: &mut R) {
+        if self.components.debris.contains(entity) {
+            return;
+        }
+        let coord = match self.spatial_table.coord_of(entity) {
+            Some(coord) => coord,
+            None => return,
+        };
+        let flow_direction = if self.air.is_equalising() {
+            self.air.flow_direction(coord)
+        } else {
+            None
+        };
+        let mut path = if let Some(direction) = flow_direction {
+            let jitter = Coord::new(rng.gen_range(-1..=1), rng.gen_range(-1..=1));
+            (direction.coord() * 8) + jitter
+        } else {
+            let away_from_centre = coord - (self.size() / 2).to_coord().unwrap();
+            let jitter = Coord::new(rng.gen_range(-3..=3), rng.gen_range(-3..=3));
+            (away_from_centre * 4) + jitter
+        };
+        if path == Coord::new(0, 0) {
+            path = CardinalDirection::North.coord();
+        }
+        // a character pulled out of the station is just debris now, so it can no longer be
+        // fought, targeted or hurt
+        self.components.character.remove(entity);
+        self.components.enemy.remove(entity);
+        self.components.hit_points.remove(entity);
+        self.components.armour.remove(entity);
+        self.components.status_effects.remove(entity);
+        self.components.realtime.insert(entity, ());
+        self.components.debris.insert(entity, damage);
+        self.components.collides_with.insert(
+            entity,
+            CollidesWith {
+                solid: true,
+                character: true,
+            },
+        );
+        self.components
+            .on_collision
+            .insert(entity, OnCollision::Remove);
+        self.realtime_components.movement.insert(
+            entity,
+            realtime::movement::spec::Movement {
+                path,
+                cardinal_step_duration: Duration::from_millis(rng.gen_range(80..=200)),
+                repeat: realtime::movement::spec::Repeat::Forever,
+            }
+            .build(),
+        );
+    }
+
+    /// Damage dealt to a wall, door or window by a projectile with the given hull penetration.
+    /// Any hull penetration at all does at least 1 damage.
+    pub fn structure_damage(&self, hull_pen_percent: u32) -> u32 {
+        if hull_pen_percent == 0 {
+            return 0;
+        }
+        let halved = self
+            .components
+            .player
+            .iter()
+            .next()
+            .is_some_and(|(_, player)| player.perks.has_rule(PerkRule::HalveHullPen));
+        let hull_pen_percent = if halved {
+            hull_pen_percent / 2
+        } else {
+            hull_pen_percent
+        };
+        (hull_pen_percent / HULL_PEN_PER_DAMAGE).max(1)
+    }
+
+    /// Reduces the structural integrity of a wall, window or door, cracking walls once they're
+    /// down to half integrity and windows after any hit. Returns true if the structure failed.
+    /// When a structure fails, any cracked structures next to it fail along with it, and a
+    /// shattering window damages the structures around it.
+    pub fn damage_structure<R: Rng>(
+        &mut self,
+        entity: Entity,
+        damage: u32,
+        rng: &mut R,
+        external_events: &mut Vec<ExternalEvent>,
+    ) -> bool {
+        if damage == 0 {
+            return false;
+        }
+        let mut failed = false;
+        let mut to_damage = vec![(entity, damage)];
+        while let Some((current_entity, damage)) = to_damage.pop() {
+            if !self.components.destructible.contains(current_entity) {
+                continue;
+            }
+            let is_window = self.is_window(current_entity);
+            let integrity = match self.components.integrity.get_mut(current_entity) {
+                Some(integrity) => integrity,
+                None => continue,
+            };
+            integrity.current = integrity.current.saturating_sub(damage);
+            if integrity.current > 0 {
+                if self.is_structure_cracked(current_entity) {
+                    self.crack_structure(current_entity);
+                }
+                continue;
+            }
+            if current_entity == entity {
+                failed = true;
+            }
+            let coord = self.spatial_table.coord_of(current_entity);
+            self.break_light_fixture(current_entity);
+            self.components.remove_entity(current_entity);
+            self.spatial_table.remove(current_entity);
+            if let Some(coord) = coord {
+                if is_window {
+                    self.spawn_window_shatter_emitter(coord, rng);
+                    external_events.push(ExternalEvent::SoundEffect(SoundEffect::WindowShatter));
+                }
+                for direction in CardinalDirections {
+                    if let Some(neighbour) = self
+                        .spatial_table
+                        .layers_at(coord + direction.coord())
+                        .and_then(|layers| layers.feature)
+                    {
+                        if self.is_structure_cracked(neighbour) {
+                            to_damage.push((neighbour, u32::MAX));
+                        } else if is_window {
+                            to_damage.push((neighbour, WINDOW_SHATTER_DAMAGE));
+                        }
+                    }
+                }
+            }
+        }
+        failed
+    }
+
+    pub fn is_window(&self, entity: Entity) -> bool {
+        matches!(
+            self.components.tile.get(entity),
+            Some(Tile::Window(_) | Tile::WindowCracked(_))
+        )
+    }
+
+    /// Windows crack after the first hit while walls hold until they're down to half their
+    /// integrity. Doors are never considered cracked as they have no cracked appearance.
+    fn is_structure_cracked(&self, entity: Entity) -> bool {
+        if self.components.door_state.contains(entity) {
+            return false;
+        }
+        let is_window = self.is_window(entity);
+        self.components
+            .integrity
+            .get(entity)
+            .map(|integrity| {
+                if is_window {
+                    integrity.current < integrity.max
+                } else {
+                    integrity.current * 2 <= integrity.max
+                }
+            })
+            .unwrap_or(false)
+    }
+
+    fn crack_structure(&mut self, entity: Entity) {
+        self.break_light_fixture(entity);
+        if let Some(tile) = self.components.tile.get_mut(entity) {
+            *tile = match *tile {
+                Tile::Wall | Tile::WallLight | Tile::WallLightBroken => Tile::WallCracked,
+                Tile::WallText0 => Tile::WallText0Cracked,
+                Tile::WallText1 => Tile::WallText1Cracked,
+                Tile::WallText2 => Tile::WallText2Cracked,
+                Tile::WallText3 => Tile::WallText3Cracked,
+                Tile::Window(axis) => Tile::WindowCracked(axis),
+                other => other,
+            };
+        }
+    }
+
     fn character_push_in_direction(&mut self, entity: Entity, direction: Direction) {
         if let Some(current_coord) = self.spatial_table.coord_of(entity) {
             let target_coord = current_coord + direction.coord();
@@ -551,8 +1009,14 @@ impl World {
         if self.components.player.contains(character) {
             external_events.push(ExternalEvent::SoundEffect(SoundEffect::Die));
             message_log.push(Message::PlayerDies);
-        } else if let Some(enemy) = self.components.enemy.get(character) {
-            message_log.push(Message::EnemyDies(*enemy));
+        } else if let Some(&enemy) = self.components.enemy.get(character) {
+            message_log.push(Message::EnemyDies(enemy));
+            if self.components.carries_keycard.contains(character) {
+                self.drop_keycard(character);
+                message_log.push(Message::EnemyDropsKeycard(enemy));
+            }
+        } else if self.is_friendly_npc(character) {
+            message_log.push(Message::SurvivorDies);
         }
         self.components.to_remove.insert(character, ());
         if self.components.expoodes_on_death.contains(character) {
@@ -560,7 +1024,12 @@ impl World {
                 self.components.expoodes_on_death.remove(character);
                 use explosion::spec::*;
                 let spec = Explosion {
-                    mechanics: Mechanics { range: 2 },
+                    mechanics: Mechanics {
+                        range: 2,
+                        damage: 2,
+                        push_back: 2,
+                        breach: false,
+                    },
                     particle_emitter: ParticleEmitter {
                         duration: Duration::from_millis(400),
                         num_particles_per_frame: 100,
@@ -615,14 +1084,54 @@ impl World {
         external_events: &mut Vec<ExternalEvent>,
         message_log: &mut Vec<Message>,
     ) {
-        if let Some(armour) = self.components.armour.get(entity_to_damage).cloned() {
-            if let Some(remaining_pen) = projectile_damage.pen.checked_sub(armour.value) {
+        let weak_point_hit =
+            self.is_boss_weak_point_hit(entity_to_damage, projectile_movement_direction.coord());
+        // the player has no armour component, so they can only be hurt by projectiles fired by
+        // enemies
+        let armour = if let Some(armour) =
+            self.armour_against(entity_to_damage, projectile_movement_direction.coord())
+        {
+            Some(armour)
+        } else if projectile_damage.enemy.is_some()
+            && self.components.player.contains(entity_to_damage)
+        {
+            Some(0)
+        } else {
+            None
+        };
+        if let Some(armour) = armour {
+            if let Some(remaining_pen) = projectile_damage.pen.checked_sub(armour) {
                 if let Some(&enemy) = self.components.enemy.get(entity_to_damage) {
                     if let Some(weapon) = projectile_damage.weapon_name {
                         message_log.push(Message::PlayerHitEnemy { enemy, weapon });
                     }
                 }
-                let damage = projectile_damage.hit_points;
+                if self.components.player.contains(entity_to_damage) {
+                    if let Some(enemy) = projectile_damage.enemy {
+                        message_log.push(Message::EnemyShootsPlayer(enemy));
+                    }
+                }
+                let mut damage = projectile_damage.hit_points;
+                if projectile_damage.enemy.is_some() {
+                    damage = self.damage_from_enemy_attack(entity_to_damage, damage);
+                }
+                if projectile_damage.weapon_name.is_some() {
+                    let critical_hit_percent = self
+                        .components
+                        .player
+                        .iter()
+                        .next()
+                        .map(|(_, player)| player.critical_hit_percent())
+                        .unwrap_or(0);
+                    if rng.gen_range(0..100) < critical_hit_percent {
+                        message_log.push(Message::CriticalHit);
+                        damage *= 2;
+                    }
+                }
+                if weak_point_hit {
+                    message_log.push(Message::GuardianWeakPointHit);
+                    damage *= 2;
+                }
                 let victim_health = self
                     .components
                     .hit_points
@@ -630,6 +1139,9 @@ impl World {
                     .map(|hp| hp.current)
                     .unwrap_or(0);
                 let actual_damage = damage.min(victim_health);
+                if let Some(effect) = projectile_damage.status_effect {
+                    self.apply_status_effect(entity_to_damage, effect, message_log);
+                }
                 self.damage_character(entity_to_damage, damage, rng, external_events, message_log);
                 if projectile_damage.life_steal {
                     if let Some(player) = self.components.player.entities().next() {
@@ -672,70 +1184,6 @@ impl World {
         }
     }
 
-    pub fn apply_upgrade(
-        &mut self,
-        entity: Entity,
-        upgrade: player::Upgrade,
-    ) -> Result<(), ActionError> {
-        let player = self.components.player.get_mut(entity).unwrap();
-        if player.credit < upgrade.level.cost() {
-            return Err(ActionError::CannotAffordUpgrade);
-        }
-        player.credit -= upgrade.level.cost();
-        {
-            let player_level = match upgrade.typ {
-                player::UpgradeType::Toughness => &mut player.upgrade_table.toughness,
-                player::UpgradeType::Accuracy => &mut player.upgrade_table.accuracy,
-                player::UpgradeType::Endurance => &mut player.upgrade_table.endurance,
-            };
-            *player_level = Some(upgrade.level);
-        }
-        use player::{Upgrade, UpgradeLevel::*, UpgradeType::*};
-        match upgrade {
-            Upgrade {
-                typ: Toughness,
-                level: Level1,
-            } => {
-                player.ranged_weapons.push(None);
-            }
-            Upgrade {
-                typ: Toughness,
-                level: Level2,
-            } => {
-                let hit_points = self.components.hit_points.get_mut(entity).unwrap();
-                hit_points.max *= 2;
-                hit_points.current *= 2;
-            }
-            Upgrade {
-                typ: Accuracy,
-                level: Level1,
-            } => {
-                player.traits.reduce_hull_pen = true;
-            }
-            Upgrade {
-                typ: Accuracy,
-                level: Level2,
-            } => {
-                player.traits.double_damage = true;
-            }
-            Upgrade {
-                typ: Endurance,
-                level: Level1,
-            } => {
-                player.traits.half_vacuum_pull = true;
-            }
-            Upgrade {
-                typ: Endurance,
-                level: Level2,
-            } => {
-                let oxygen = self.components.oxygen.get_mut(entity).unwrap();
-                oxygen.max *= 2;
-                oxygen.current *= 2;
-            }
-        }
-        Ok(())
-    }
-
     pub fn equip_melee_weapon_from_ground(
         &mut self,
         entity: Entity,
@@ -819,3 +1267,430 @@ impl World {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::world::data::Layer;
+    use crate::{
+        terrain,
+        world::{perk::Perk, spawn, StatusEffect, StatusEffects},
+    };
+    use gridbugs::coord_2d::{Axis, Size};
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    const SLOT: player::RangedWeaponSlot = player::RangedWeaponSlot::Slot1;
+
+    /// A room open to space on its east side, with two hull cells outside the opening
+    const HULL_MAP: &str = "
+          
+  ####    
+  #@.     
+  ####    
+          
+";
+
+    fn world_with_booted_player() -> (World, Entity) {
+        let mut terrain = terrain::from_str(HULL_MAP, spawn::make_player());
+        terrain
+            .world
+            .components
+            .player
+            .get_mut(terrain.player)
+            .unwrap()
+            .magnetic_boots = true;
+        (terrain.world, terrain.player)
+    }
+
+    fn walk(world: &mut World, entity: Entity, direction: Direction) -> Result<(), Error> {
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world
+            .character_walk_in_direction(
+                entity,
+                direction,
+                &mut rng,
+                &mut Vec::new(),
+                &mut Vec::new(),
+            )
+            .map(|_| ())
+    }
+
+    #[test]
+    fn magnetic_boots_walk_along_the_hull_but_not_off_it() {
+        let (mut world, player) = world_with_booted_player();
+        for _ in 0..3 {
+            assert!(walk(&mut world, player, Direction::East).is_ok());
+        }
+        assert_eq!(world.entity_coord(player), Some(Coord::new(6, 2)));
+        assert!(world.is_held_to_hull(player));
+        assert!(matches!(
+            walk(&mut world, player, Direction::East),
+            Err(Error::WalkOffHull)
+        ));
+        assert_eq!(world.entity_coord(player), Some(Coord::new(6, 2)));
+    }
+
+    #[test]
+    fn walking_on_the_hull_uses_up_oxygen() {
+        let (mut world, player) = world_with_booted_player();
+        world.air.init(&world.spatial_table, &world.components);
+        for _ in 0..3 {
+            walk(&mut world, player, Direction::East).unwrap();
+        }
+        let oxygen = |world: &World| world.components.oxygen.get(player).unwrap().current;
+        let before = oxygen(&world);
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.process_oxygen(player, &mut rng, &mut Vec::new(), &mut Vec::new());
+        assert_eq!(oxygen(&world), before - 1);
+    }
+
+    #[test]
+    fn the_vacuum_cannot_pull_a_player_off_the_hull() {
+        let (mut world, player) = world_with_booted_player();
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.character_pull_in_direction(player, CardinalDirection::East, &mut rng);
+        world.character_pull_in_direction(player, CardinalDirection::East, &mut rng);
+        assert_eq!(world.entity_coord(player), Some(Coord::new(5, 2)));
+        world.character_pull_in_direction(player, CardinalDirection::East, &mut rng);
+        assert_eq!(world.entity_coord(player), Some(Coord::new(5, 2)));
+    }
+
+    #[test]
+    fn stepping_back_inside_leaves_the_hull() {
+        let (mut world, player) = world_with_booted_player();
+        walk(&mut world, player, Direction::East).unwrap();
+        walk(&mut world, player, Direction::East).unwrap();
+        assert!(world.is_held_to_hull(player));
+        walk(&mut world, player, Direction::West).unwrap();
+        assert!(!world.is_held_to_hull(player));
+        assert!(world.is_floor_at_coord(world.entity_coord(player).unwrap()));
+    }
+
+    fn world_with_shotgun(loaded: u32, reserve: u32) -> (World, Entity) {
+        let mut world = World::new(Size::new(1, 1), 0);
+        let entity = world.entity_allocator.alloc();
+        let mut player = player::Player::new();
+        let mut shotgun = player::Weapon::new_shotgun();
+        shotgun.ammo.as_mut().unwrap().current = loaded;
+        player.ranged_weapons[SLOT.index()] = Some(shotgun);
+        player.ammo_reserve.add(AmmoType::Shells, reserve);
+        world.components.player.insert(entity, player);
+        (world, entity)
+    }
+
+    fn loaded_and_reserve(world: &World, entity: Entity) -> (u32, u32) {
+        let player = world.components.player.get(entity).unwrap();
+        let ammo = player.weapon_in_slot(SLOT).unwrap().ammo.unwrap();
+        (ammo.current, player.ammo_reserve.get(AmmoType::Shells))
+    }
+
+    #[test]
+    fn reload_fills_the_magazine_from_the_reserve() {
+        let (mut world, entity) = world_with_shotgun(1, 10);
+        assert!(world
+            .character_reload(entity, SLOT, &mut Vec::new())
+            .is_ok());
+        assert_eq!(loaded_and_reserve(&world, entity), (4, 7));
+    }
+
+    #[test]
+    fn reload_takes_only_what_is_left_in_the_reserve() {
+        let (mut world, entity) = world_with_shotgun(0, 2);
+        assert!(world
+            .character_reload(entity, SLOT, &mut Vec::new())
+            .is_ok());
+        assert_eq!(loaded_and_reserve(&world, entity), (2, 0));
+    }
+
+    #[test]
+    fn reload_fails_when_the_magazine_is_full() {
+        let (mut world, entity) = world_with_shotgun(4, 10);
+        let result = world.character_reload(entity, SLOT, &mut Vec::new());
+        assert!(matches!(result, Err(Error::MagazineFull(_))));
+        assert_eq!(loaded_and_reserve(&world, entity), (4, 10));
+    }
+
+    #[test]
+    fn reload_fails_when_the_reserve_is_empty() {
+        let (mut world, entity) = world_with_shotgun(1, 0);
+        let result = world.character_reload(entity, SLOT, &mut Vec::new());
+        assert!(matches!(result, Err(Error::NoReserveAmmo(_))));
+        assert_eq!(loaded_and_reserve(&world, entity), (1, 0));
+    }
+
+    #[test]
+    fn any_hull_penetration_damages_structures() {
+        let (mut world, player) = world_with_booted_player();
+        let damage = |world: &World| [0, 1, 19, 20, 39, 40, 75].map(|p| world.structure_damage(p));
+        assert_eq!(damage(&world), [0, 1, 1, 1, 1, 2, 3]);
+        world.components.player.get_mut(player).unwrap().credit = 100;
+        world.apply_upgrade(player, Perk::Careful).unwrap();
+        assert_eq!(damage(&world), [0, 1, 1, 1, 1, 1, 1]);
+    }
+
+    fn damage(world: &mut World, entity: Entity, damage: u32) -> bool {
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.damage_structure(entity, damage, &mut rng, &mut Vec::new())
+    }
+
+    fn integrity(world: &World, entity: Entity) -> Option<u32> {
+        world
+            .components
+            .integrity
+            .get(entity)
+            .map(|integrity| integrity.current)
+    }
+
+    #[test]
+    fn walls_crack_at_half_integrity_and_fail_at_zero() {
+        let mut world = World::new(Size::new(1, 1), 0);
+        let wall = world.spawn_wall(Coord::new(0, 0));
+        assert!(!damage(&mut world, wall, 2));
+        assert_eq!(integrity(&world, wall), Some(4));
+        assert_eq!(world.components.tile.get(wall), Some(&Tile::Wall));
+        assert!(!damage(&mut world, wall, 1));
+        assert_eq!(world.components.tile.get(wall), Some(&Tile::WallCracked));
+        assert!(damage(&mut world, wall, 3));
+        assert_eq!(world.entity_coord(wall), None);
+    }
+
+    #[test]
+    fn cracked_walls_fail_along_with_a_neighbour() {
+        let mut world = World::new(Size::new(4, 1), 0);
+        let walls = [0, 1, 2, 3].map(|x| world.spawn_wall(Coord::new(x, 0)));
+        damage(&mut world, walls[0], 3);
+        damage(&mut world, walls[2], 3);
+        assert!(damage(&mut world, walls[1], u32::MAX));
+        assert_eq!(world.entity_coord(walls[0]), None);
+        assert_eq!(world.entity_coord(walls[2]), None);
+        assert_eq!(integrity(&world, walls[3]), Some(6));
+    }
+
+    #[test]
+    fn doors_lose_integrity_without_cracking() {
+        let mut world = World::new(Size::new(3, 1), 0);
+        let door = world.spawn_door(Coord::new(1, 0), Axis::X);
+        let tile = *world.components.tile.get(door).unwrap();
+        assert!(!damage(&mut world, door, 4));
+        assert_eq!(integrity(&world, door), Some(2));
+        assert_eq!(world.components.tile.get(door), Some(&tile));
+    }
+
+    #[test]
+    fn windows_crack_after_one_hit() {
+        let mut world = World::new(Size::new(1, 1), 0);
+        let window = world.spawn_window(Coord::new(0, 0), Axis::X);
+        assert!(!damage(&mut world, window, 1));
+        assert_eq!(
+            world.components.tile.get(window),
+            Some(&Tile::WindowCracked(Axis::X))
+        );
+    }
+
+    #[test]
+    fn a_shattering_window_cracks_the_walls_around_it() {
+        let mut world = World::new(Size::new(3, 1), 0);
+        let left = world.spawn_wall(Coord::new(0, 0));
+        let window = world.spawn_window(Coord::new(1, 0), Axis::X);
+        let right = world.spawn_wall(Coord::new(2, 0));
+        assert!(damage(&mut world, window, 3));
+        for wall in [left, right] {
+            assert_eq!(integrity(&world, wall), Some(6 - WINDOW_SHATTER_DAMAGE));
+            assert_eq!(world.components.tile.get(wall), Some(&Tile::WallCracked));
+        }
+    }
+
+    #[test]
+    fn a_shattering_window_breaks_cracked_walls_around_it() {
+        let mut world = World::new(Size::new(3, 1), 0);
+        let left = world.spawn_wall(Coord::new(0, 0));
+        let window = world.spawn_window(Coord::new(1, 0), Axis::X);
+        let right = world.spawn_wall(Coord::new(2, 0));
+        damage(&mut world, left, 3);
+        assert!(damage(&mut world, window, 3));
+        assert_eq!(world.entity_coord(left), None);
+        assert_eq!(integrity(&world, right), Some(6 - WINDOW_SHATTER_DAMAGE));
+    }
+
+    fn move_debris(world: &mut World, debris: Entity, direction: Direction) {
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.projectile_move(
+            debris,
+            direction,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut rng,
+        );
+    }
+
+    fn world_with_player_on_hull_and_debris() -> (World, Entity, Entity) {
+        let (mut world, player) = world_with_booted_player();
+        walk(&mut world, player, Direction::East).unwrap();
+        walk(&mut world, player, Direction::East).unwrap();
+        let debris = world.spawn_credit(Coord::new(7, 2), 1);
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.start_drift(debris, 2, &mut rng);
+        (world, player, debris)
+    }
+
+    #[test]
+    fn escaping_air_carries_debris_out_through_the_breach() {
+        let (mut world, _) = world_with_booted_player();
+        world.air.update(&world.spatial_table, &world.components);
+        assert!(world.air.is_equalising());
+        assert_eq!(
+            world.air.flow_direction(Coord::new(3, 2)),
+            Some(CardinalDirection::East)
+        );
+        assert_eq!(
+            world.air.flow_direction(Coord::new(5, 2)),
+            Some(CardinalDirection::East)
+        );
+    }
+
+    #[test]
+    fn debris_hits_characters_in_its_path_while_air_is_escaping() {
+        let (mut world, player, debris) = world_with_player_on_hull_and_debris();
+        world.air.update(&world.spatial_table, &world.components);
+        move_debris(&mut world, debris, Direction::West);
+        move_debris(&mut world, debris, Direction::West);
+        assert_eq!(world.components.hit_points.get(player).unwrap().current, 8);
+        assert_eq!(world.entity_coord(debris), None);
+    }
+
+    #[test]
+    fn debris_is_harmless_once_the_air_has_escaped() {
+        let (mut world, player, debris) = world_with_player_on_hull_and_debris();
+        world.air.init(&world.spatial_table, &world.components);
+        move_debris(&mut world, debris, Direction::West);
+        move_debris(&mut world, debris, Direction::West);
+        assert_eq!(world.components.hit_points.get(player).unwrap().current, 10);
+        assert_eq!(world.entity_coord(debris), None);
+    }
+
+    #[test]
+    fn debris_stops_when_it_runs_into_other_debris() {
+        let (mut world, _, debris) = world_with_player_on_hull_and_debris();
+        let other_debris = world.spawn_credit(Coord::new(6, 2), 1);
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.start_drift(other_debris, 2, &mut rng);
+        move_debris(&mut world, debris, Direction::West);
+        assert_eq!(world.entity_coord(debris), None);
+        assert_eq!(world.entity_coord(other_debris), Some(Coord::new(6, 2)));
+    }
+
+    fn world_with_player_and(
+        spawn_victim: fn(&mut World, Coord) -> Entity,
+    ) -> (World, Entity, Entity) {
+        let mut world = World::new(Size::new(5, 1), 0);
+        for coord in Size::new(5, 1).coord_iter_row_major() {
+            world.spawn_floor(coord);
+        }
+        let location = Location {
+            coord: Coord::new(1, 0),
+            layer: Some(Layer::Character),
+        };
+        let player = world.insert_entity_data(location, spawn::make_player());
+        let victim = spawn_victim(&mut world, Coord::new(2, 0));
+        (world, player, victim)
+    }
+
+    fn player_hits(
+        world: &mut World,
+        player: Entity,
+        victim: Entity,
+        rng: &mut Isaac64Rng,
+    ) -> Vec<Message> {
+        let mut message_log = Vec::new();
+        world.player_melee_attack(
+            player,
+            victim,
+            Direction::East,
+            rng,
+            &mut Vec::new(),
+            &mut message_log,
+        );
+        message_log
+    }
+
+    #[test]
+    fn characters_use_their_rear_armour_against_attacks_from_behind() {
+        let (mut world, _, tank) = world_with_player_and(World::spawn_tank);
+        world.components.facing.insert(tank, Direction::East);
+        assert_eq!(world.armour_against(tank, Coord::new(1, 0)), Some(4));
+        assert_eq!(world.armour_against(tank, Coord::new(-1, 0)), Some(10));
+        assert_eq!(world.armour_against(tank, Coord::new(0, 1)), Some(10));
+    }
+
+    #[test]
+    fn a_weak_weapon_only_hurts_a_tank_from_behind() {
+        let (mut world, player, tank) = world_with_player_and(World::spawn_tank);
+        world
+            .components
+            .player
+            .get_mut(player)
+            .unwrap()
+            .melee_weapon = player::Weapon::new_crowbar();
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.components.facing.insert(tank, Direction::West);
+        player_hits(&mut world, player, tank, &mut rng);
+        assert_eq!(world.components.hit_points.get(tank).unwrap().current, 10);
+        world.components.facing.insert(tank, Direction::East);
+        player_hits(&mut world, player, tank, &mut rng);
+        assert!(world.components.hit_points.get(tank).unwrap().current < 10);
+    }
+
+    #[test]
+    fn critical_hits_happen_at_the_player_s_critical_hit_rate() {
+        let count_critical_hits = |perks: &[Perk]| {
+            let (mut world, player, zombie) = world_with_player_and(World::spawn_zombie);
+            world.components.player.get_mut(player).unwrap().credit = 100;
+            for &perk in perks {
+                world.apply_upgrade(player, perk).unwrap();
+            }
+            let mut rng = Isaac64Rng::seed_from_u64(0);
+            (0..1000)
+                .filter(|_| {
+                    world.components.hit_points.get_mut(zombie).unwrap().current = u32::MAX;
+                    player_hits(&mut world, player, zombie, &mut rng)
+                        .iter()
+                        .any(|message| matches!(message, Message::CriticalHit))
+                })
+                .count()
+        };
+        // 5% without perks, and 50% with both perks raising the critical hit chance
+        assert!((25..=75).contains(&count_critical_hits(&[])));
+        assert!((450..=550).contains(&count_critical_hits(&[Perk::Careful, Perk::Deadeye])));
+    }
+
+    fn attack_status_effect(spawn_attacker: fn(&mut World, Coord) -> Entity) -> StatusEffects {
+        let mut world = World::new(Size::new(5, 1), 0);
+        for coord in Size::new(5, 1).coord_iter_row_major() {
+            world.spawn_floor(coord);
+        }
+        let attacker = spawn_attacker(&mut world, Coord::new(0, 0));
+        let victim = world.spawn_zombie(Coord::new(1, 0));
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.npc_melee_attack(attacker, victim, &mut rng, &mut Vec::new(), &mut Vec::new());
+        world.components.status_effects.get(victim).unwrap().clone()
+    }
+
+    #[test]
+    fn zombie_attacks_cause_bleeding() {
+        assert!(attack_status_effect(World::spawn_zombie).has(StatusEffect::Bleeding));
+    }
+
+    #[test]
+    fn tank_attacks_stun() {
+        assert!(attack_status_effect(World::spawn_tank).has(StatusEffect::Stunned));
+    }
+
+    #[test]
+    fn reload_fails_with_an_empty_slot() {
+        let (mut world, entity) = world_with_shotgun(1, 10);
+        let result =
+            world.character_reload(entity, player::RangedWeaponSlot::Slot2, &mut Vec::new());
+        assert!(matches!(result, Err(Error::NoWeaponInSlot(_))));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::world::{Components, SpatialTable};
+use crate::world::{data::Mass, Components, SpatialTable};
 use crate::Entity;
 use gridbugs::{
     direction::{CardinalDirection, CardinalDirections},
@@ -17,6 +17,45 @@ pub struct Air {
 
 const MAX_AIR: u8 = 6;
 
+/// An entity being sucked towards a breach
+pub struct Pull {
+    pub entity: Entity,
+    pub direction: CardinalDirection,
+    /// Pressure of the cell the entity was in before it lost some air. Higher pressure means a
+    /// stronger pull.
+    pub pressure: u8,
+}
+
+impl Pull {
+    /// The number of cells the entity moves this update. Characters holding onto a door are
+    /// pulled one cell less than they otherwise would be.
+    pub fn num_cells(&self, mass: Mass, anchored: bool) -> u32 {
+        let strong = self.pressure > MAX_AIR / 2;
+        let num_cells: u32 = match mass {
+            Mass::Light => {
+                if strong {
+                    2
+                } else {
+                    1
+                }
+            }
+            Mass::Normal => 1,
+            Mass::Heavy => {
+                if strong {
+                    1
+                } else {
+                    0
+                }
+            }
+        };
+        if anchored {
+            num_cells.saturating_sub(1)
+        } else {
+            num_cells
+        }
+    }
+}
+
 impl Air {
     pub fn new(size: Size) -> Self {
         let pressure = Grid::new_copy(size, MAX_AIR);
@@ -56,11 +95,7 @@ impl Air {
         }
     }
 
-    pub fn update(
-        &mut self,
-        spatial_table: &SpatialTable,
-        components: &Components,
-    ) -> Vec<(Entity, CardinalDirection)> {
+    pub fn update(&mut self, spatial_table: &SpatialTable, components: &Components) -> Vec<Pull> {
         let mut to_move = Vec::new();
         self.queue.clear();
         for (coord, flow_cell) in self.flow.enumerate_mut() {
@@ -88,13 +123,18 @@ impl Air {
                         *flow_cell = Some(current_flow + 1);
                         let pressure = self.pressure.get_checked_mut(nei_coord);
                         if *pressure > 0 {
-                            *pressure = pressure.saturating_sub(1);
+                            let pull = |entity| Pull {
+                                entity,
+                                direction: direction.opposite(),
+                                pressure: *pressure,
+                            };
                             if let Some(character) = layers.character {
-                                to_move.push((character, direction.opposite()));
+                                to_move.push(pull(character));
                             }
                             if let Some(item) = layers.item {
-                                to_move.push((item, direction.opposite()));
+                                to_move.push(pull(item));
                             }
+                            *pressure = pressure.saturating_sub(1);
                         }
                         self.queue.push_back(nei_coord);
                     }
@@ -117,6 +157,27 @@ impl Air {
         }
     }
 
+    /// True while air is still being sucked out of some part of the station
+    pub fn is_equalising(&self) -> bool {
+        self.flow
+            .iter()
+            .zip(self.pressure.iter())
+            .any(|(flow, &pressure)| flow.map(|f| f > 0).unwrap_or(false) && pressure > 0)
+    }
+
+    /// The direction air is moving through a cell: towards the nearest breach, and once out in
+    /// space, onwards away from the breach it came through
+    pub fn flow_direction(&self, coord: Coord) -> Option<CardinalDirection> {
+        let flow = (*self.flow.get(coord)?)?;
+        CardinalDirections.into_iter().find(|direction| {
+            if flow == 0 {
+                self.flow.get(coord - direction.coord()) == Some(&Some(1))
+            } else {
+                self.flow.get(coord + direction.coord()) == Some(&Some(flow - 1))
+            }
+        })
+    }
+
     pub fn has_flow(&self, coord: Coord) -> bool {
         if let Some(Some(flow)) = self.flow.get(coord) {
             *flow > 0
@@ -125,3 +186,52 @@ impl Air {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::world::World;
+    use gridbugs::{coord_2d::Axis, entity_table::EntityAllocator};
+
+    fn pull(pressure: u8) -> Pull {
+        Pull {
+            entity: EntityAllocator::default().alloc(),
+            direction: CardinalDirection::North,
+            pressure,
+        }
+    }
+
+    #[test]
+    fn heavier_things_are_pulled_fewer_cells() {
+        let strong = pull(MAX_AIR);
+        assert_eq!(strong.num_cells(Mass::Light, false), 2);
+        assert_eq!(strong.num_cells(Mass::Normal, false), 1);
+        assert_eq!(strong.num_cells(Mass::Heavy, false), 1);
+        let weak = pull(1);
+        assert_eq!(weak.num_cells(Mass::Light, false), 1);
+        assert_eq!(weak.num_cells(Mass::Normal, false), 1);
+        assert_eq!(weak.num_cells(Mass::Heavy, false), 0);
+    }
+
+    #[test]
+    fn holding_onto_a_door_resists_one_cell_of_pull() {
+        let strong = pull(MAX_AIR);
+        assert_eq!(strong.num_cells(Mass::Light, true), 1);
+        assert_eq!(strong.num_cells(Mass::Normal, true), 0);
+        assert_eq!(pull(1).num_cells(Mass::Heavy, true), 0);
+    }
+
+    #[test]
+    fn characters_next_to_a_door_are_anchored() {
+        let mut world = World::new(Size::new(4, 1), 0);
+        for coord in Size::new(4, 1).coord_iter_row_major() {
+            world.spawn_floor(coord);
+        }
+        world.spawn_door(Coord::new(0, 0), Axis::X);
+        let near = world.spawn_zombie(Coord::new(1, 0));
+        let far = world.spawn_zombie(Coord::new(3, 0));
+        assert!(world.is_anchored(near));
+        assert!(!world.is_anchored(far));
+        assert_eq!(world.mass(near), Mass::Normal);
+    }
+}
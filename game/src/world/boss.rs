@@ -0,0 +1,160 @@
+use crate::{
+    world::{
+        data::{BossPhase, HitPoints},
+        ExternalEvent, World,
+    },
+    Entity, Message,
+};
+use gridbugs::{coord_2d::Coord, direction::CardinalDirections, line_2d::LineSegment};
+use rand::Rng;
+
+/// Turns between the boss shattering windows while in the venting phase
+const VENT_INTERVAL: u32 = 8;
+
+/// Only windows this close to the boss will be vented
+const VENT_RANGE: u32 = 10;
+
+/// Turns between the boss summoning skeletons while in the summoning phase
+const SUMMON_INTERVAL: u32 = 6;
+
+/// Skeletons summoned by the boss rise faster than those reassembling after being killed
+const SUMMON_RESPAWN_TURNS: u32 = 3;
+
+const NUM_SUMMONED: usize = 2;
+
+impl BossPhase {
+    fn from_hit_points(hit_points: HitPoints) -> Self {
+        if hit_points.current * 3 > hit_points.max * 2 {
+            Self::Armoured
+        } else if hit_points.current * 3 > hit_points.max {
+            Self::Venting
+        } else {
+            Self::Summoning
+        }
+    }
+}
+
+impl World {
+    /// Returns true if an attack travelling in the given direction would strike the exposed
+    /// reactor on a boss's back
+    pub fn is_boss_weak_point_hit(&self, entity: Entity, attack_direction: Coord) -> bool {
+        self.components.boss.contains(entity) && self.is_hit_from_behind(entity, attack_direction)
+    }
+
+    /// Advances each boss's phase based on its remaining health, and uses its special ability
+    /// if it can see the player
+    pub fn process_boss<R: Rng>(
+        &mut self,
+        player: Entity,
+        rng: &mut R,
+        external_events: &mut Vec<ExternalEvent>,
+        message_log: &mut Vec<Message>,
+    ) {
+        let player_coord = if let Some(coord) = self.spatial_table.coord_of(player) {
+            coord
+        } else {
+            return;
+        };
+        for entity in self.components.boss.entities().collect::<Vec<_>>() {
+            let coord = match self.spatial_table.coord_of(entity) {
+                Some(coord) => coord,
+                None => continue,
+            };
+            let hit_points = match self.components.hit_points.get(entity) {
+                Some(&hit_points) => hit_points,
+                None => continue,
+            };
+            let boss = self.components.boss.get_mut(entity).unwrap();
+            let phase = BossPhase::from_hit_points(hit_points);
+            if phase != boss.phase {
+                boss.phase = phase;
+                boss.turns_until_ability = 0;
+                match phase {
+                    BossPhase::Armoured => (),
+                    BossPhase::Venting => message_log.push(Message::GuardianArmourBuckles),
+                    BossPhase::Summoning => message_log.push(Message::GuardianOverloads),
+                }
+            }
+            if boss.turns_until_ability > 0 {
+                boss.turns_until_ability -= 1;
+                continue;
+            }
+            if self.is_solid_feature_in_line_segment(LineSegment::new(coord, player_coord)) {
+                continue;
+            }
+            match phase {
+                BossPhase::Armoured => (),
+                BossPhase::Venting => {
+                    if self.boss_vent(coord, rng, external_events) {
+                        message_log.push(Message::GuardianVentsRoom);
+                    }
+                    self.reset_boss_ability(entity, VENT_INTERVAL);
+                }
+                BossPhase::Summoning => {
+                    if self.boss_summon(coord) {
+                        message_log.push(Message::GuardianSummons);
+                    }
+                    self.reset_boss_ability(entity, SUMMON_INTERVAL);
+                }
+            }
+        }
+    }
+
+    fn reset_boss_ability(&mut self, entity: Entity, turns: u32) {
+        if let Some(boss) = self.components.boss.get_mut(entity) {
+            boss.turns_until_ability = turns;
+        }
+    }
+
+    /// Shatters the nearest window to let the air out of the room
+    fn boss_vent<R: Rng>(
+        &mut self,
+        coord: Coord,
+        rng: &mut R,
+        external_events: &mut Vec<ExternalEvent>,
+    ) -> bool {
+        let nearest_window = self
+            .components
+            .integrity
+            .entities()
+            .filter(|&entity| self.is_window(entity))
+            .filter_map(|entity| {
+                self.spatial_table
+                    .coord_of(entity)
+                    .map(|window_coord| (entity, window_coord.manhattan_distance(coord)))
+            })
+            .filter(|&(_, distance)| distance <= VENT_RANGE)
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(entity, _)| entity);
+        if let Some(window) = nearest_window {
+            self.damage_structure(window, u32::MAX, rng, external_events)
+        } else {
+            false
+        }
+    }
+
+    /// Places skeleton remains next to the boss which will soon rise as skeletons
+    fn boss_summon(&mut self, coord: Coord) -> bool {
+        let summon_coords = CardinalDirections
+            .into_iter()
+            .map(|direction| coord + direction.coord())
+            .filter(|&summon_coord| {
+                self.can_npc_traverse_feature_at_coord(summon_coord)
+                    && self
+                        .spatial_table
+                        .layers_at(summon_coord)
+                        .map(|layers| layers.character.is_none() && layers.item.is_none())
+                        .unwrap_or(false)
+            })
+            .take(NUM_SUMMONED)
+            .collect::<Vec<_>>();
+        for &summon_coord in summon_coords.iter() {
+            if let Some(entity) = self.spawn_skeleton_respawn(summon_coord) {
+                self.components
+                    .skeleton_respawn
+                    .insert(entity, SUMMON_RESPAWN_TURNS);
+            }
+        }
+        !summon_coords.is_empty()
+    }
+}
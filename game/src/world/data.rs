@@ -1,11 +1,16 @@
 use crate::visibility::Light;
 pub use crate::world::{
     explosion_spec,
+    grenade::Grenade,
     player::{self, Player},
     spatial::{Layer, Location},
+    status::{StatusEffect, StatusEffects},
+    vendor::Vendor,
 };
 use gridbugs::{
-    coord_2d::Axis, direction::CardinalDirection, entity_table::declare_entity_module,
+    coord_2d::{Axis, Coord},
+    direction::Direction,
+    entity_table::{declare_entity_module, Entity},
     rgb_int::Rgb24,
 };
 use serde::{Deserialize, Serialize};
@@ -18,6 +23,8 @@ declare_entity_module! {
         realtime: (),
         blocks_gameplay: (),
         light: Light,
+        station_light: (),
+        light_fixture: Entity,
         on_collision: OnCollision,
         colour_hint: Rgb24,
         npc: Npc,
@@ -32,15 +39,19 @@ declare_entity_module! {
         ignore_lighting: (),
         door_state: DoorState,
         door_close_countdown: u32,
+        hack_progress: u32,
+        carries_keycard: (),
         stairs: (),
         next_action: NpcAction,
         to_remove: (),
-        move_half_speed: MoveHalfSpeed,
         item: Item,
         damage: u32,
         particle: (),
         destructible: (),
         upgrade: (),
+        workbench: player::WeaponAbility,
+        vendor: Vendor,
+        terminal: bool,
         weapon: player::Weapon,
         push_back: (),
         expoodes_on_death: (),
@@ -48,6 +59,15 @@ declare_entity_module! {
         skeleton_respawn: u32,
         enemy: Enemy,
         map: bool,
+        debris: u32,
+        mass: Mass,
+        integrity: HitPoints,
+        ranged_attack: RangedAttack,
+        boss: Boss,
+        facing: Direction,
+        status_effects: StatusEffects,
+        attack_status_effect: StatusEffect,
+        grenade: Grenade,
     }
 }
 pub use components::Components;
@@ -57,20 +77,32 @@ pub use components::EntityData;
 pub enum Tile {
     Player,
     Wall,
+    WallCracked,
+    WallLight,
+    WallLightBroken,
     Floor,
     DoorClosed(Axis),
     DoorOpen(Axis),
+    DoorLocked(Axis),
     Stairs,
     Window(Axis),
+    WindowCracked(Axis),
     Zombie,
     Tank,
     Boomer,
     Skeleton,
     SkeletonRespawn,
+    Drone,
+    Guardian(Direction),
+    Survivor,
     WallText0,
     WallText1,
     WallText2,
     WallText3,
+    WallText0Cracked,
+    WallText1Cracked,
+    WallText2Cracked,
+    WallText3Cracked,
     FuelText0,
     FuelText1,
     FuelHatch,
@@ -78,7 +110,14 @@ pub enum Tile {
     Credit1,
     Credit2,
     Upgrade,
+    Workbench,
+    Vendor,
+    Terminal,
+    TerminalOffline,
     Chainsaw,
+    Crowbar,
+    ShockBaton,
+    FireAxe,
     Shotgun,
     Railgun,
     Rifle,
@@ -86,6 +125,14 @@ pub enum Tile {
     Oxidiser,
     LifeStealer,
     Medkit,
+    OxygenCanister,
+    RepairKit,
+    MagneticBoots,
+    Keycard,
+    Flashlight,
+    Grenade,
+    LiveGrenade,
+    Ammo(AmmoType),
     Map,
     MapLocked,
 }
@@ -96,6 +143,17 @@ pub enum Enemy {
     Skeleton,
     Boomer,
     Tank,
+    Drone,
+    Guardian,
+}
+
+/// Determines how far an entity is pulled towards a hull breach. Characters without a mass
+/// component are `Normal`, and everything else is `Light`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mass {
+    Light,
+    Normal,
+    Heavy,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -121,6 +179,16 @@ impl RangedWeapon {
         }
     }
 
+    pub fn ammo_type(self) -> AmmoType {
+        use RangedWeapon::*;
+        match self {
+            Shotgun => AmmoType::Shells,
+            Rifle => AmmoType::Bullets,
+            Railgun | GausCannon => AmmoType::Slugs,
+            Oxidiser | LifeStealer => AmmoType::Cells,
+        }
+    }
+
     pub fn new_weapon(self) -> player::Weapon {
         use player::Weapon;
         use RangedWeapon::*;
@@ -135,9 +203,45 @@ impl RangedWeapon {
     }
 }
 
+/// Each family of ranged weapon is reloaded from its own kind of ammunition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AmmoType {
+    Shells,
+    Bullets,
+    Slugs,
+    Cells,
+}
+
+impl AmmoType {
+    pub const ALL: [Self; 4] = [Self::Shells, Self::Bullets, Self::Slugs, Self::Cells];
+
+    /// Most spare ammunition of this type the player can carry
+    pub fn max_reserve(self) -> u32 {
+        match self {
+            Self::Shells => 16,
+            Self::Bullets => 16,
+            Self::Slugs => 6,
+            Self::Cells => 30,
+        }
+    }
+
+    /// Amount of ammunition found in a single pickup
+    pub fn pickup_amount(self) -> u32 {
+        match self {
+            Self::Shells => 4,
+            Self::Bullets => 4,
+            Self::Slugs => 2,
+            Self::Cells => 10,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MeleeWeapon {
     Chainsaw,
+    Crowbar,
+    ShockBaton,
+    FireAxe,
 }
 
 impl MeleeWeapon {
@@ -145,6 +249,9 @@ impl MeleeWeapon {
         use MeleeWeapon::*;
         match self {
             Chainsaw => Tile::Chainsaw,
+            Crowbar => Tile::Crowbar,
+            ShockBaton => Tile::ShockBaton,
+            FireAxe => Tile::FireAxe,
         }
     }
 
@@ -153,6 +260,30 @@ impl MeleeWeapon {
         use MeleeWeapon::*;
         match self {
             Chainsaw => Weapon::new_chainsaw(),
+            Crowbar => Weapon::new_crowbar(),
+            ShockBaton => Weapon::new_shock_baton(),
+            FireAxe => Weapon::new_fire_axe(),
+        }
+    }
+}
+
+/// Items which are carried in the player's inventory until they are used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Consumable {
+    Medkit,
+    OxygenCanister,
+    Grenade,
+    RepairKit,
+}
+
+impl Consumable {
+    pub fn tile(self) -> Tile {
+        use Consumable::*;
+        match self {
+            Medkit => Tile::Medkit,
+            OxygenCanister => Tile::OxygenCanister,
+            Grenade => Tile::Grenade,
+            RepairKit => Tile::RepairKit,
         }
     }
 }
@@ -162,13 +293,19 @@ pub enum Item {
     Credit(u32),
     RangedWeapon(RangedWeapon),
     MeleeWeapon(MeleeWeapon),
-    Medkit,
+    Consumable(Consumable),
+    Ammo { ammo_type: AmmoType, amount: u32 },
+    MagneticBoots,
+    Keycard,
+    Flashlight,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Disposition {
     Hostile,
     Afraid,
+    /// Follows the player and never attacks. Hostile npcs will attack friendly npcs they can see.
+    Friendly,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -207,6 +344,33 @@ pub struct ProjectileDamage {
     pub life_steal: bool,
     pub oxidise: bool,
     pub weapon_name: Option<player::WeaponName>,
+    /// The enemy who fired the projectile, if it was fired by an enemy
+    pub enemy: Option<Enemy>,
+    pub status_effect: Option<StatusEffect>,
+}
+
+/// Lets an npc shoot at the player from a distance
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RangedAttack {
+    pub projectile_damage: ProjectileDamage,
+    pub light_colour: Option<Rgb24>,
+    /// Number of turns the npc must wait after firing before it can fire again
+    pub reload: u32,
+    pub turns_until_ready: u32,
+}
+
+/// The stage of a boss fight, determined by the fraction of its health the boss has left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BossPhase {
+    Armoured,
+    Venting,
+    Summoning,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Boss {
+    pub phase: BossPhase,
+    pub turns_until_ability: u32,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -236,11 +400,17 @@ impl Oxygen {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Armour {
     pub value: u32,
+    /// Armour against attacks which strike the character from behind
+    pub rear: u32,
 }
 
 impl Armour {
     pub fn new(value: u32) -> Self {
-        Self { value }
+        Self { value, rear: value }
+    }
+
+    pub fn directional(front: u32, rear: u32) -> Self {
+        Self { value: front, rear }
     }
 }
 
@@ -248,15 +418,13 @@ impl Armour {
 pub enum DoorState {
     Open,
     Closed,
+    /// Opens with a keycard, a pry bar, or by hacking the lock for several turns
+    Locked,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NpcAction {
-    Walk(CardinalDirection),
+    Walk(Direction),
+    Fire(Coord),
     Wait,
 }
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
-pub struct MoveHalfSpeed {
-    pub skip_next_move: bool,
-}
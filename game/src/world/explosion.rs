@@ -1,5 +1,5 @@
 use crate::{
-    world::{realtime, ExternalEvent, World},
+    world::{noise, realtime, ExternalEvent, StatusEffect, World},
     Entity, Message,
 };
 use gridbugs::{coord_2d::Coord, direction::Direction, line_2d::LineSegment};
@@ -23,6 +23,12 @@ pub mod spec {
     #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
     pub struct Mechanics {
         pub range: u32,
+        /// Hit points lost by each character caught in the blast
+        pub damage: u32,
+        /// Number of cells characters caught in the blast are thrown
+        pub push_back: u32,
+        /// Structures right next to the explosion are destroyed outright rather than just damaged
+        pub breach: bool,
     }
 
     #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -32,33 +38,42 @@ pub mod spec {
     }
 }
 
+const DIRECT_STRUCTURE_DAMAGE: u32 = 6;
+const INDIRECT_STRUCTURE_DAMAGE: u32 = 3;
+
 fn apply_indirect_hit<R: Rng>(
     world: &mut World,
     character_entity: Entity,
     explosion_to_character: LineSegment,
+    mechanics: &spec::Mechanics,
     rng: &mut R,
     external_events: &mut Vec<ExternalEvent>,
     message_log: &mut Vec<Message>,
 ) {
-    let push_back = 2;
-    let damage = 2;
     world.components.realtime.insert(character_entity, ());
     world.realtime_components.movement.insert(
         character_entity,
         realtime::movement::spec::Movement {
             path: explosion_to_character.delta(),
-            repeat: realtime::movement::spec::Repeat::Steps(push_back as usize),
+            repeat: realtime::movement::spec::Repeat::Steps(mechanics.push_back as usize),
             cardinal_step_duration: Duration::from_millis(100),
         }
         .build(),
     );
-    world.damage_character(character_entity, damage, rng, external_events, message_log);
+    world.damage_character(
+        character_entity,
+        mechanics.damage,
+        rng,
+        external_events,
+        message_log,
+    );
 }
 
 fn apply_direct_hit<R: Rng>(
     world: &mut World,
     explosion_coord: Coord,
     character_entity: Entity,
+    mechanics: &spec::Mechanics,
     rng: &mut R,
     external_events: &mut Vec<ExternalEvent>,
     message_log: &mut Vec<Message>,
@@ -72,8 +87,6 @@ fn apply_direct_hit<R: Rng>(
             }
         }
     }
-    let push_back = 2;
-    let damage = 2;
     if solid_neighbour_vector.is_zero() {
         log::warn!("Direct hit with no solid neighbours shouldn't be possible.");
     } else {
@@ -83,13 +96,20 @@ fn apply_direct_hit<R: Rng>(
             character_entity,
             realtime::movement::spec::Movement {
                 path: travel_vector,
-                repeat: realtime::movement::spec::Repeat::Steps(push_back as usize),
+                repeat: realtime::movement::spec::Repeat::Steps(mechanics.push_back as usize),
                 cardinal_step_duration: Duration::from_millis(100),
             }
             .build(),
         );
     }
-    world.damage_character(character_entity, damage, rng, external_events, message_log);
+    world.apply_status_effect(character_entity, StatusEffect::Burning, message_log);
+    world.damage_character(
+        character_entity,
+        mechanics.damage,
+        rng,
+        external_events,
+        message_log,
+    );
 }
 
 fn is_in_explosion_range(
@@ -115,6 +135,7 @@ fn apply_mechanics<R: Rng>(
                     world,
                     explosion_coord,
                     character_entity,
+                    mechanics,
                     rng,
                     external_events,
                     message_log,
@@ -128,6 +149,7 @@ fn apply_mechanics<R: Rng>(
                     world,
                     character_entity,
                     explosion_to_character,
+                    mechanics,
                     rng,
                     external_events,
                     message_log,
@@ -138,7 +160,17 @@ fn apply_mechanics<R: Rng>(
     for destructible_entity in world.components.destructible.entities().collect::<Vec<_>>() {
         if let Some(coord) = world.spatial_table.coord_of(destructible_entity) {
             if is_in_explosion_range(explosion_coord, mechanics, coord) {
-                world.components.to_remove.insert(destructible_entity, ());
+                // structures right next to the explosion take the full force of the blast
+                let damage = if explosion_coord.distance2(coord) <= 2 {
+                    if mechanics.breach {
+                        u32::MAX
+                    } else {
+                        DIRECT_STRUCTURE_DAMAGE
+                    }
+                } else {
+                    INDIRECT_STRUCTURE_DAMAGE
+                };
+                world.damage_structure(destructible_entity, damage, rng, external_events);
             }
         }
     }
@@ -162,4 +194,5 @@ pub fn explode<R: Rng>(
         message_log,
     );
     external_events.push(ExternalEvent::Explosion(coord));
+    world.make_noise(coord, noise::loudness::EXPLOSION);
 }
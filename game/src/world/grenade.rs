@@ -0,0 +1,165 @@
+use crate::{
+    world::{action::Error, explosion, Consumable, ExternalEvent, World},
+    Entity, Message,
+};
+use gridbugs::{coord_2d::Coord, line_2d::LineSegment};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Grenades thrown at a target further away than this land short of it
+const THROW_RANGE: usize = 6;
+
+/// Turns after landing before a grenade explodes
+const FUSE_TURNS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Grenade {
+    pub turns_until_explode: u32,
+    pub explosion: explosion::spec::Explosion,
+}
+
+impl Grenade {
+    /// A grenade with a lit fuse. Its blast is strong enough to blow a hole in the hull.
+    pub fn new() -> Self {
+        use explosion::spec::*;
+        Self {
+            turns_until_explode: FUSE_TURNS,
+            explosion: Explosion {
+                mechanics: Mechanics {
+                    range: 3,
+                    damage: 3,
+                    push_back: 2,
+                    breach: true,
+                },
+                particle_emitter: ParticleEmitter {
+                    duration: Duration::from_millis(500),
+                    num_particles_per_frame: 150,
+                    min_step: Duration::from_millis(100),
+                    max_step: Duration::from_millis(300),
+                    fade_duration: Duration::from_millis(600),
+                },
+            },
+        }
+    }
+}
+
+impl World {
+    /// The cells a grenade thrown at the target passes through. It lands in front of the first
+    /// solid feature or character in its way, or at the limit of its range.
+    pub fn grenade_throw_path(&self, start: Coord, target: Coord) -> Vec<Coord> {
+        let mut path = Vec::new();
+        if start == target {
+            return path;
+        }
+        for coord in LineSegment::new(start, target)
+            .iter()
+            .skip(1)
+            .take(THROW_RANGE)
+        {
+            let layers = match self.spatial_table.layers_at(coord) {
+                Some(layers) => layers,
+                None => break,
+            };
+            if layers.character.is_some() {
+                break;
+            }
+            if let Some(feature) = layers.feature {
+                if self.components.solid.contains(feature) {
+                    break;
+                }
+            }
+            path.push(coord);
+        }
+        path
+    }
+
+    pub fn character_throw_grenade(
+        &mut self,
+        character: Entity,
+        target: Coord,
+    ) -> Result<(), Error> {
+        let character_coord = if let Some(coord) = self.spatial_table.coord_of(character) {
+            coord
+        } else {
+            panic!("failed to find coord for {:?}", character);
+        };
+        if let Some(player) = self.components.player.get_mut(character) {
+            if !player.inventory.take(Consumable::Grenade) {
+                return Err(Error::NoGrenades);
+            }
+        }
+        let landing_coord = self
+            .grenade_throw_path(character_coord, target)
+            .last()
+            .cloned()
+            .unwrap_or(character_coord);
+        self.spawn_live_grenade(character_coord, landing_coord);
+        Ok(())
+    }
+
+    /// Counts down the fuse of each grenade which has finished moving, exploding those which
+    /// reach zero
+    pub fn process_grenades<R: Rng>(
+        &mut self,
+        rng: &mut R,
+        external_events: &mut Vec<ExternalEvent>,
+        message_log: &mut Vec<Message>,
+    ) {
+        for entity in self.components.grenade.entities().collect::<Vec<_>>() {
+            if self.components.realtime.contains(entity) {
+                continue;
+            }
+            let grenade = self.components.grenade.get_mut(entity).unwrap();
+            grenade.turns_until_explode = grenade.turns_until_explode.saturating_sub(1);
+            if grenade.turns_until_explode > 0 {
+                continue;
+            }
+            let explosion = grenade.explosion;
+            let coord = self.spatial_table.coord_of(entity);
+            self.spatial_table.remove(entity);
+            self.components.remove_entity(entity);
+            self.entity_allocator.free(entity);
+            self.realtime_components.remove_entity(entity);
+            if let Some(coord) = coord {
+                message_log.push(Message::GrenadeExplodes);
+                explosion::explode(self, coord, explosion, external_events, message_log, rng);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{terrain, world::spawn};
+
+    const MAP: &str = "
+#######
+#@....#
+#######
+";
+
+    #[test]
+    fn throwing_without_a_grenade_fails() {
+        let terrain = terrain::from_str(MAP, spawn::make_player());
+        let mut world = terrain.world;
+        let result = world.character_throw_grenade(terrain.player, Coord::new(4, 1));
+        assert!(matches!(result, Err(Error::NoGrenades)));
+        assert_eq!(world.components.grenade.entities().count(), 0);
+    }
+
+    #[test]
+    fn throwing_uses_up_a_grenade() {
+        let terrain = terrain::from_str(MAP, spawn::make_player());
+        let mut world = terrain.world;
+        let player = terrain.player;
+        let inventory = &mut world.components.player.get_mut(player).unwrap().inventory;
+        inventory.insert(Consumable::Grenade);
+        assert!(world
+            .character_throw_grenade(player, Coord::new(4, 1))
+            .is_ok());
+        assert_eq!(world.components.grenade.entities().count(), 1);
+        let inventory = &world.components.player.get(player).unwrap().inventory;
+        assert_eq!(inventory.count(Consumable::Grenade), 0);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{
+    world::{action::Error, Consumable, ExternalEvent, Item, Tile, World},
+    Entity, Message,
+};
+use gridbugs::direction::Directions;
+
+impl World {
+    pub fn character_pick_up_item(
+        &mut self,
+        character: Entity,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        let item_entity = self
+            .spatial_table
+            .coord_of(character)
+            .and_then(|coord| self.spatial_table.layers_at(coord))
+            .and_then(|layers| layers.item)
+            .ok_or(Error::NoItemToGet)?;
+        let consumable = match self.components.item.get(item_entity) {
+            Some(&Item::Consumable(consumable)) => consumable,
+            _ => return Err(Error::NoItemToGet),
+        };
+        let player = self
+            .components
+            .player
+            .get_mut(character)
+            .ok_or(Error::NoItemToGet)?;
+        if !player.inventory.insert(consumable) {
+            return Err(Error::InventoryFull);
+        }
+        message_log.push(Message::TakeItem(consumable));
+        self.components.to_remove.insert(item_entity, ());
+        Ok(())
+    }
+
+    pub fn character_drop_item(
+        &mut self,
+        character: Entity,
+        index: usize,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        let coord = self
+            .spatial_table
+            .coord_of(character)
+            .ok_or(Error::NoItemInInventorySlot)?;
+        if let Some(layers) = self.spatial_table.layers_at(coord) {
+            if layers.item.is_some() {
+                return Err(Error::NoSpaceToDrop);
+            }
+        }
+        let consumable = self
+            .components
+            .player
+            .get_mut(character)
+            .and_then(|player| player.inventory.remove(index))
+            .ok_or(Error::NoItemInInventorySlot)?;
+        self.spawn_consumable(coord, consumable);
+        message_log.push(Message::DropItem(consumable));
+        Ok(())
+    }
+
+    /// Uses up an item from the inventory. Grenades are thrown rather than used so they are left
+    /// in the inventory.
+    pub fn character_use_item(
+        &mut self,
+        character: Entity,
+        index: usize,
+        external_events: &mut Vec<ExternalEvent>,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        let consumable = self
+            .components
+            .player
+            .get(character)
+            .and_then(|player| player.inventory.get(index))
+            .ok_or(Error::NoItemInInventorySlot)?;
+        match consumable {
+            Consumable::Medkit => {
+                let hit_points = self.components.hit_points.get(character);
+                if hit_points.is_some_and(|hit_points| hit_points.current >= hit_points.max) {
+                    return Err(Error::AlreadyAtFullHealth);
+                }
+                self.heal_fully(character, external_events, message_log);
+            }
+            Consumable::OxygenCanister => {
+                // characters who don't breathe are treated as having full oxygen
+                let oxygen = self
+                    .components
+                    .oxygen
+                    .get_mut(character)
+                    .filter(|oxygen| oxygen.current < oxygen.max)
+                    .ok_or(Error::AlreadyAtFullOxygen)?;
+                oxygen.current = oxygen.max;
+                message_log.push(Message::RefillOxygen);
+            }
+            Consumable::RepairKit => {
+                if !self.repair_adjacent_structures(character) {
+                    return Err(Error::NothingToRepair);
+                }
+                message_log.push(Message::RepairStructures);
+            }
+            Consumable::Grenade => return Ok(()),
+        }
+        if let Some(player) = self.components.player.get_mut(character) {
+            player.inventory.remove(index);
+        }
+        Ok(())
+    }
+
+    /// Restores the integrity of damaged walls, doors and windows around a character. Returns
+    /// false if there was nothing to repair.
+    fn repair_adjacent_structures(&mut self, character: Entity) -> bool {
+        let coord = match self.spatial_table.coord_of(character) {
+            Some(coord) => coord,
+            None => return false,
+        };
+        let mut repaired = false;
+        for direction in Directions {
+            let feature = match self
+                .spatial_table
+                .layers_at(coord + direction.coord())
+                .and_then(|layers| layers.feature)
+            {
+                Some(feature) => feature,
+                None => continue,
+            };
+            if let Some(integrity) = self.components.integrity.get_mut(feature) {
+                if integrity.current < integrity.max {
+                    integrity.current = integrity.max;
+                    repaired = true;
+                    if let Some(tile) = self.components.tile.get_mut(feature) {
+                        *tile = match *tile {
+                            Tile::WallCracked => Tile::Wall,
+                            Tile::WallText0Cracked => Tile::WallText0,
+                            Tile::WallText1Cracked => Tile::WallText1,
+                            Tile::WallText2Cracked => Tile::WallText2,
+                            Tile::WallText3Cracked => Tile::WallText3,
+                            Tile::WindowCracked(axis) => Tile::Window(axis),
+                            other => other,
+                        };
+                    }
+                }
+            }
+        }
+        repaired
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{terrain, world::spawn};
+
+    const MAP: &str = "
+###
+#@#
+###
+";
+
+    fn world_with_item(consumable: Consumable) -> (World, Entity) {
+        let terrain = terrain::from_str(MAP, spawn::make_player());
+        let mut world = terrain.world;
+        let player = world.components.player.get_mut(terrain.player).unwrap();
+        player.inventory.insert(consumable);
+        (world, terrain.player)
+    }
+
+    fn use_item(world: &mut World, character: Entity) -> Result<(), Error> {
+        world.character_use_item(character, 0, &mut Vec::new(), &mut Vec::new())
+    }
+
+    fn inventory_len(world: &World, character: Entity) -> usize {
+        world
+            .components
+            .player
+            .get(character)
+            .unwrap()
+            .inventory
+            .len()
+    }
+
+    #[test]
+    fn a_medkit_is_kept_at_full_health() {
+        let (mut world, player) = world_with_item(Consumable::Medkit);
+        let result = use_item(&mut world, player);
+        assert!(matches!(result, Err(Error::AlreadyAtFullHealth)));
+        assert_eq!(inventory_len(&world, player), 1);
+    }
+
+    #[test]
+    fn a_medkit_heals_an_injured_character() {
+        let (mut world, player) = world_with_item(Consumable::Medkit);
+        world.components.hit_points.get_mut(player).unwrap().current = 1;
+        assert!(use_item(&mut world, player).is_ok());
+        let hit_points = world.components.hit_points.get(player).unwrap();
+        assert_eq!(hit_points.current, hit_points.max);
+        assert_eq!(inventory_len(&world, player), 0);
+    }
+
+    #[test]
+    fn an_oxygen_canister_is_kept_at_full_oxygen() {
+        let (mut world, player) = world_with_item(Consumable::OxygenCanister);
+        let result = use_item(&mut world, player);
+        assert!(matches!(result, Err(Error::AlreadyAtFullOxygen)));
+        world.components.oxygen.remove(player);
+        let result = use_item(&mut world, player);
+        assert!(matches!(result, Err(Error::AlreadyAtFullOxygen)));
+        assert_eq!(inventory_len(&world, player), 1);
+    }
+
+    #[test]
+    fn an_oxygen_canister_refills_oxygen() {
+        let (mut world, player) = world_with_item(Consumable::OxygenCanister);
+        world.components.oxygen.get_mut(player).unwrap().current = 0;
+        assert!(use_item(&mut world, player).is_ok());
+        let oxygen = world.components.oxygen.get(player).unwrap();
+        assert_eq!(oxygen.current, oxygen.max);
+        assert_eq!(inventory_len(&world, player), 0);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    world::{action::Error, data::Tile, World},
+    Entity, ExternalEvent, Message, SoundEffect,
+};
+use gridbugs::direction::Direction;
+
+impl World {
+    /// Removes the light mounted on a fixture, returning whether it was still lit
+    pub fn break_light_fixture(&mut self, fixture: Entity) -> bool {
+        if let Some(light) = self.components.light_fixture.remove(fixture) {
+            self.components.to_remove.insert(light, ());
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn shoot_out_light(
+        &mut self,
+        fixture: Entity,
+        external_events: &mut Vec<ExternalEvent>,
+        message_log: &mut Vec<Message>,
+    ) {
+        if self.break_light_fixture(fixture) {
+            self.components.tile.insert(fixture, Tile::WallLightBroken);
+            external_events.push(ExternalEvent::SoundEffect(SoundEffect::WindowShatter));
+            message_log.push(Message::LightShotOut);
+        }
+    }
+
+    /// Whether the character would be hidden by darkness, ignoring any other lights such as
+    /// muzzle flashes
+    pub fn is_in_darkness(&self, character: Entity) -> bool {
+        !self.lights_on && self.flashlight_beam(character).is_none()
+    }
+
+    /// The direction a character's flashlight is pointing, if it's switched on
+    pub fn flashlight_beam(&self, character: Entity) -> Option<Direction> {
+        let player = self.components.player.get(character)?;
+        if !player.flashlight?.on {
+            return None;
+        }
+        Some(
+            self.components
+                .facing
+                .get(character)
+                .cloned()
+                .unwrap_or(Direction::South),
+        )
+    }
+
+    pub fn character_toggle_flashlight(&mut self, character: Entity) -> Result<(), Error> {
+        let flashlight = self
+            .components
+            .player
+            .get_mut(character)
+            .and_then(|player| player.flashlight.as_mut())
+            .ok_or(Error::NoFlashlight)?;
+        if !flashlight.on && flashlight.battery == 0 {
+            return Err(Error::FlashlightBatteryFlat);
+        }
+        flashlight.on = !flashlight.on;
+        Ok(())
+    }
+
+    pub fn drain_flashlight(&mut self, character: Entity, message_log: &mut Vec<Message>) {
+        if let Some(flashlight) = self
+            .components
+            .player
+            .get_mut(character)
+            .and_then(|player| player.flashlight.as_mut())
+        {
+            if flashlight.on {
+                flashlight.battery = flashlight.battery.saturating_sub(1);
+                if flashlight.battery == 0 {
+                    flashlight.on = false;
+                    message_log.push(Message::FlashlightBatteryFlat);
+                }
+            }
+        }
+    }
+}
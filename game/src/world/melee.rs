@@ -0,0 +1,95 @@
+use crate::{
+    world::{action::Error, noise, player, ExternalEvent, Mass, World},
+    Entity, Message, SoundEffect,
+};
+use gridbugs::direction::Direction;
+use rand::Rng;
+
+/// Structural damage dealt by each swing of a weapon which can breach walls
+const BREACH_DAMAGE: u32 = 2;
+
+impl World {
+    pub fn has_melee_ability(&self, character: Entity, ability: player::WeaponAbility) -> bool {
+        self.components
+            .player
+            .get(character)
+            .map(|player| player.melee_weapon.abilities.contains(&ability))
+            .unwrap_or(false)
+    }
+
+    /// Uses up one charge of the character's melee weapon, going back to bare hands when the last
+    /// charge is used
+    fn use_melee_weapon_charge(&mut self, character: Entity) {
+        if let Some(player) = self.components.player.get_mut(character) {
+            if let Some(ammo) = player.melee_weapon.ammo.as_mut() {
+                ammo.current = ammo.current.saturating_sub(1);
+                if ammo.current == 0 {
+                    player.melee_weapon = player::Weapon::new_bare_hands();
+                }
+            }
+        }
+    }
+
+    /// Swings a weapon with the breach ability at a wall, door or window
+    pub fn character_hack_at_structure<R: Rng>(
+        &mut self,
+        character: Entity,
+        structure: Entity,
+        rng: &mut R,
+        external_events: &mut Vec<ExternalEvent>,
+    ) {
+        external_events.push(ExternalEvent::SoundEffect(SoundEffect::Punch));
+        if let Some(coord) = self.spatial_table.coord_of(structure) {
+            self.make_noise(coord, noise::loudness::HACK);
+        }
+        self.damage_structure(structure, BREACH_DAMAGE, rng, external_events);
+        self.use_melee_weapon_charge(character);
+    }
+
+    /// Pushes the character standing next to the shover one cell further away. Heavy characters
+    /// can't be shoved, and there must be an unoccupied cell for them to be pushed into, though
+    /// that cell needn't have a floor.
+    pub fn character_shove(
+        &mut self,
+        character: Entity,
+        direction: Direction,
+        external_events: &mut Vec<ExternalEvent>,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        let coord = self
+            .spatial_table
+            .coord_of(character)
+            .ok_or(Error::NothingToShove)?;
+        if self.is_diagonal_step_blocked(coord, direction) {
+            return Err(Error::NothingToShove);
+        }
+        let victim_coord = coord + direction.coord();
+        let victim = self
+            .get_character_at_coord(victim_coord)
+            .ok_or(Error::NothingToShove)?;
+        if self.mass(victim) == Mass::Heavy {
+            return Err(Error::TooHeavyToShove);
+        }
+        let target_coord = victim_coord + direction.coord();
+        let blocked = match self.spatial_table.layers_at(target_coord) {
+            Some(layers) => {
+                layers.character.is_some()
+                    || self.is_solid_feature_at_coord(target_coord)
+                    || self.is_diagonal_step_blocked(victim_coord, direction)
+            }
+            None => true,
+        };
+        if blocked {
+            return Err(Error::NoSpaceToShove);
+        }
+        self.spatial_table
+            .update_coord(victim, target_coord)
+            .unwrap();
+        self.components.facing.insert(character, direction);
+        external_events.push(ExternalEvent::SoundEffect(SoundEffect::Punch));
+        if let Some(&enemy) = self.components.enemy.get(victim) {
+            message_log.push(Message::ShoveEnemy(enemy));
+        }
+        Ok(())
+    }
+}
@@ -10,15 +10,21 @@ use serde::{Deserialize, Serialize};
 mod air;
 pub use air::Air;
 
+mod noise;
+pub use noise::Hearing;
+use noise::Noise;
+
 mod spatial;
 use spatial::SpatialTable;
 
 pub mod player;
 
+pub mod perk;
+
 mod data;
 pub use data::{
-    Armour, Disposition, Enemy, EntityData, HitPoints, Item, Layer, Location, MeleeWeapon,
-    NpcAction, Oxygen, RangedWeapon, Tile,
+    AmmoType, Armour, Consumable, Disposition, Enemy, EntityData, HitPoints, Item, Layer, Location,
+    Mass, MeleeWeapon, NpcAction, Oxygen, RangedWeapon, Tile,
 };
 use data::{Components, Npc};
 
@@ -36,6 +42,29 @@ pub use action::Error as ActionError;
 mod spawn;
 pub use spawn::make_player;
 
+mod boss;
+
+mod grenade;
+
+mod inventory;
+
+mod melee;
+
+mod security;
+
+mod workbench;
+
+mod vendor;
+pub use vendor::Ware;
+
+mod terminal;
+pub use terminal::TerminalCommand;
+
+mod lighting;
+
+mod status;
+pub use status::{ActiveStatusEffect, StatusEffect, StatusEffects};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct World {
     pub level: u32,
@@ -44,6 +73,13 @@ pub struct World {
     pub realtime_components: realtime::data::RealtimeComponents,
     pub spatial_table: SpatialTable,
     pub air: Air,
+    /// Noises made since the npcs last took a turn
+    pub noises: Vec<Noise>,
+    /// Whether the station lights have power. Cleared on blackout floors and when the lights are
+    /// switched off from a terminal.
+    pub lights_on: bool,
+    /// Set when a terminal has revealed the positions of every enemy on the floor
+    pub enemies_revealed: bool,
 }
 
 impl World {
@@ -60,6 +96,9 @@ impl World {
             spatial_table,
             level,
             air,
+            noises: Vec::new(),
+            lights_on: true,
+            enemies_revealed: false,
         }
     }
 }
@@ -176,6 +215,9 @@ impl World {
         self.components
             .light
             .iter()
+            .filter(move |(entity, _)| {
+                self.lights_on || !self.components.station_light.contains(*entity)
+            })
             .filter_map(move |(entity, light)| {
                 self.spatial_table
                     .coord_of(entity)
@@ -187,10 +229,17 @@ impl World {
         let coord = self.spatial_table.coord_of(entity)?;
         let &hit_points = self.components.hit_points.get(entity)?;
         let &oxygen = self.components.oxygen.get(entity)?;
+        let status_effects = self
+            .components
+            .status_effects
+            .get(entity)
+            .cloned()
+            .unwrap_or_default();
         Some(CharacterInfo {
             coord,
             hit_points,
             oxygen,
+            status_effects,
         })
     }
 
@@ -284,4 +333,5 @@ pub struct CharacterInfo {
     pub coord: Coord,
     pub hit_points: HitPoints,
     pub oxygen: Oxygen,
+    pub status_effects: StatusEffects,
 }
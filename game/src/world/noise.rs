@@ -0,0 +1,131 @@
+use crate::world::World;
+use gridbugs::{
+    direction::CardinalDirections,
+    grid_2d::{Coord, Grid, Size},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+pub mod loudness {
+    pub const EXPLOSION: u32 = 20;
+    pub const GUNSHOT: u32 = 12;
+    pub const QUIET_GUNSHOT: u32 = 6;
+    pub const DOOR: u32 = 5;
+    pub const HACK: u32 = 8;
+    pub const HACK_LOCK: u32 = 3;
+}
+
+/// Loudness lost when a noise passes through a closed door
+const CLOSED_DOOR_ATTENUATION: u32 = 4;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Noise {
+    pub coord: Coord,
+    pub loudness: u32,
+}
+
+impl World {
+    pub fn make_noise(&mut self, coord: Coord, loudness: u32) {
+        self.noises.push(Noise { coord, loudness });
+    }
+
+    /// Loudness lost by a noise entering a cell, or `None` if noise can't enter the cell. Noise
+    /// doesn't travel through walls or vacuum.
+    fn noise_attenuation(&self, coord: Coord) -> Option<u32> {
+        let layers = self.spatial_table.layers_at(coord)?;
+        layers.floor?;
+        if !self.air.has_air(coord) {
+            return None;
+        }
+        if let Some(feature) = layers.feature {
+            if self.components.door_state.contains(feature) {
+                if self.components.solid.contains(feature) {
+                    return Some(CLOSED_DOOR_ATTENUATION);
+                }
+            } else if self.components.solid.contains(feature) {
+                return None;
+            }
+        }
+        Some(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct HearingCell {
+    count: u64,
+    loudness: u32,
+    source: Coord,
+}
+
+/// The loudest noise audible from each cell, and where it came from
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Hearing {
+    count: u64,
+    cells: Grid<HearingCell>,
+    queue: VecDeque<Coord>,
+}
+
+impl Hearing {
+    pub fn new(size: Size) -> Self {
+        Self {
+            count: 1,
+            cells: Grid::new_copy(
+                size,
+                HearingCell {
+                    count: 0,
+                    loudness: 0,
+                    source: Coord::new(0, 0),
+                },
+            ),
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn update(&mut self, world: &World) {
+        self.count += 1;
+        for noise in world.noises.iter() {
+            self.queue.clear();
+            if let Some(cell) = self.cells.get_mut(noise.coord) {
+                if cell.count != self.count || cell.loudness < noise.loudness {
+                    *cell = HearingCell {
+                        count: self.count,
+                        loudness: noise.loudness,
+                        source: noise.coord,
+                    };
+                    self.queue.push_back(noise.coord);
+                }
+            }
+            while let Some(coord) = self.queue.pop_front() {
+                let loudness = self.cells.get_checked(coord).loudness;
+                for direction in CardinalDirections {
+                    let neighbour_coord = coord + direction.coord();
+                    let attenuation = match world.noise_attenuation(neighbour_coord) {
+                        Some(attenuation) => attenuation,
+                        None => continue,
+                    };
+                    let neighbour_loudness = match loudness.checked_sub(attenuation) {
+                        Some(0) | None => continue,
+                        Some(neighbour_loudness) => neighbour_loudness,
+                    };
+                    let cell = self.cells.get_checked_mut(neighbour_coord);
+                    if cell.count != self.count || cell.loudness < neighbour_loudness {
+                        *cell = HearingCell {
+                            count: self.count,
+                            loudness: neighbour_loudness,
+                            source: noise.coord,
+                        };
+                        self.queue.push_back(neighbour_coord);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The source of the loudest noise heard at the given coord since the last update
+    pub fn heard(&self, coord: Coord) -> Option<Coord> {
+        self.cells
+            .get(coord)
+            .filter(|cell| cell.count == self.count)
+            .map(|cell| cell.source)
+    }
+}
@@ -0,0 +1,391 @@
+use crate::{
+    world::{ActionError, Mass, World},
+    Entity,
+};
+use serde::{Deserialize, Serialize};
+
+/// Chance that a hit on an enemy is critical before any perks are taken
+const BASE_CRITICAL_HIT_PERCENT: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Perk {
+    StrongBack,
+    Hardy,
+    ThickSkin,
+    Careful,
+    KillShot,
+    Deadeye,
+    SureFooted,
+    BigLungs,
+    Ballast,
+}
+
+/// Rules of the game which behave differently for a player with the perk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerkRule {
+    HalveHullPen,
+    DoubleDamage,
+    HalveVacuumPull,
+    CountAsHeavy,
+    ReduceDamageTaken,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerkEffect {
+    ExtraWeaponSlot,
+    MultiplyMaxHitPoints(u32),
+    MultiplyMaxOxygen(u32),
+    AddCriticalHitPercent(u32),
+    Rule(PerkRule),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PerkSpec {
+    pub perk: Perk,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub cost: u32,
+    /// A perk which must be taken before this one
+    pub requires: Option<Perk>,
+    /// Taking this perk closes off these perks for the rest of the run
+    pub excludes: &'static [Perk],
+    pub effects: &'static [PerkEffect],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PerkBranch {
+    pub name: &'static str,
+    pub root: Perk,
+    pub children: &'static [Perk],
+}
+
+/// The upgrade store offers a branch for each of these. A branch's root must be taken before
+/// either of its children, and taking one child rules out the other.
+pub const PERK_TREE: &[PerkBranch] = &[
+    PerkBranch {
+        name: "Toughness",
+        root: Perk::StrongBack,
+        children: &[Perk::Hardy, Perk::ThickSkin],
+    },
+    PerkBranch {
+        name: "Accuracy",
+        root: Perk::Careful,
+        children: &[Perk::KillShot, Perk::Deadeye],
+    },
+    PerkBranch {
+        name: "Endurance",
+        root: Perk::SureFooted,
+        children: &[Perk::BigLungs, Perk::Ballast],
+    },
+];
+
+const PERK_SPECS: &[PerkSpec] = &[
+    PerkSpec {
+        perk: Perk::StrongBack,
+        name: "Strong Back",
+        description: "Gain a third ranged weapon slot.",
+        cost: 5,
+        requires: None,
+        excludes: &[],
+        effects: &[PerkEffect::ExtraWeaponSlot],
+    },
+    PerkSpec {
+        perk: Perk::Hardy,
+        name: "Hardy",
+        description: "Double your maximum health.",
+        cost: 10,
+        requires: Some(Perk::StrongBack),
+        excludes: &[Perk::ThickSkin],
+        effects: &[PerkEffect::MultiplyMaxHitPoints(2)],
+    },
+    PerkSpec {
+        perk: Perk::ThickSkin,
+        name: "Thick Skin",
+        description: "Take 1 less damage from enemy attacks, to a minimum of 1.",
+        cost: 10,
+        requires: Some(Perk::StrongBack),
+        excludes: &[Perk::Hardy],
+        effects: &[PerkEffect::Rule(PerkRule::ReduceDamageTaken)],
+    },
+    PerkSpec {
+        perk: Perk::Careful,
+        name: "Careful",
+        description: "Halve hull pen chance and raise critical hit chance.",
+        cost: 5,
+        requires: None,
+        excludes: &[],
+        effects: &[
+            PerkEffect::Rule(PerkRule::HalveHullPen),
+            PerkEffect::AddCriticalHitPercent(10),
+        ],
+    },
+    PerkSpec {
+        perk: Perk::KillShot,
+        name: "Kill Shot",
+        description: "Deal double damage to enemies and raise critical hit chance.",
+        cost: 10,
+        requires: Some(Perk::Careful),
+        excludes: &[Perk::Deadeye],
+        effects: &[
+            PerkEffect::Rule(PerkRule::DoubleDamage),
+            PerkEffect::AddCriticalHitPercent(10),
+        ],
+    },
+    PerkSpec {
+        perk: Perk::Deadeye,
+        name: "Deadeye",
+        description: "Half of your hits on enemies are critical hits.",
+        cost: 10,
+        requires: Some(Perk::Careful),
+        excludes: &[Perk::KillShot],
+        effects: &[PerkEffect::AddCriticalHitPercent(35)],
+    },
+    PerkSpec {
+        perk: Perk::SureFooted,
+        name: "Sure-Footed",
+        description: "Vacuum pulls you one space each turn instead of two.",
+        cost: 5,
+        requires: None,
+        excludes: &[],
+        effects: &[PerkEffect::Rule(PerkRule::HalveVacuumPull)],
+    },
+    PerkSpec {
+        perk: Perk::BigLungs,
+        name: "Big Lungs",
+        description: "Double your maximum oxygen.",
+        cost: 10,
+        requires: Some(Perk::SureFooted),
+        excludes: &[Perk::Ballast],
+        effects: &[PerkEffect::MultiplyMaxOxygen(2)],
+    },
+    PerkSpec {
+        perk: Perk::Ballast,
+        name: "Ballast",
+        description: "You count as heavy, so only the strongest decompression can move you.",
+        cost: 10,
+        requires: Some(Perk::SureFooted),
+        excludes: &[Perk::BigLungs],
+        effects: &[PerkEffect::Rule(PerkRule::CountAsHeavy)],
+    },
+];
+
+impl Perk {
+    pub fn spec(self) -> &'static PerkSpec {
+        PERK_SPECS
+            .iter()
+            .find(|spec| spec.perk == self)
+            .expect("perk has no spec")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerkStatus {
+    Taken,
+    Available,
+    /// The perk's prerequisite hasn't been taken yet
+    Locked,
+    /// A perk which rules this one out has been taken
+    Excluded,
+}
+
+/// The perks a player has taken, in the order they were taken
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Perks {
+    taken: Vec<Perk>,
+}
+
+impl Perks {
+    pub fn iter(&self) -> impl '_ + Iterator<Item = Perk> {
+        self.taken.iter().cloned()
+    }
+
+    pub fn has(&self, perk: Perk) -> bool {
+        self.taken.contains(&perk)
+    }
+
+    fn effects(&self) -> impl '_ + Iterator<Item = PerkEffect> {
+        self.iter()
+            .flat_map(|perk| perk.spec().effects.iter().cloned())
+    }
+
+    pub fn has_rule(&self, rule: PerkRule) -> bool {
+        self.effects()
+            .any(|effect| effect == PerkEffect::Rule(rule))
+    }
+
+    /// Percentage chance that a hit on an enemy deals double damage
+    pub fn critical_hit_percent(&self) -> u32 {
+        BASE_CRITICAL_HIT_PERCENT
+            + self
+                .effects()
+                .map(|effect| match effect {
+                    PerkEffect::AddCriticalHitPercent(percent) => percent,
+                    _ => 0,
+                })
+                .sum::<u32>()
+    }
+
+    pub fn status(&self, perk: Perk) -> PerkStatus {
+        let spec = perk.spec();
+        if self.has(perk) {
+            PerkStatus::Taken
+        } else if self
+            .iter()
+            .any(|taken| taken.spec().excludes.contains(&perk))
+        {
+            PerkStatus::Excluded
+        } else if spec.requires.map(|required| self.has(required)) == Some(false) {
+            PerkStatus::Locked
+        } else {
+            PerkStatus::Available
+        }
+    }
+
+    pub fn available(&self) -> Vec<Perk> {
+        PERK_SPECS
+            .iter()
+            .map(|spec| spec.perk)
+            .filter(|&perk| self.status(perk) == PerkStatus::Available)
+            .collect()
+    }
+}
+
+impl World {
+    pub fn apply_upgrade(&mut self, entity: Entity, perk: Perk) -> Result<(), ActionError> {
+        let spec = perk.spec();
+        let player = self.components.player.get_mut(entity).unwrap();
+        if player.perks.status(perk) != PerkStatus::Available {
+            return Err(ActionError::PerkUnavailable);
+        }
+        if player.credit < spec.cost {
+            return Err(ActionError::CannotAffordUpgrade);
+        }
+        player.credit -= spec.cost;
+        player.perks.taken.push(perk);
+        for &effect in spec.effects {
+            match effect {
+                PerkEffect::ExtraWeaponSlot => {
+                    let player = self.components.player.get_mut(entity).unwrap();
+                    player.ranged_weapons.push(None);
+                }
+                PerkEffect::MultiplyMaxHitPoints(factor) => {
+                    let hit_points = self.components.hit_points.get_mut(entity).unwrap();
+                    hit_points.max *= factor;
+                    hit_points.current *= factor;
+                }
+                PerkEffect::MultiplyMaxOxygen(factor) => {
+                    let oxygen = self.components.oxygen.get_mut(entity).unwrap();
+                    oxygen.max *= factor;
+                    oxygen.current *= factor;
+                }
+                PerkEffect::AddCriticalHitPercent(_) | PerkEffect::Rule(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// True if the entity is a player who has taken a perk with the given rule
+    pub fn has_perk_rule(&self, entity: Entity, rule: PerkRule) -> bool {
+        self.components
+            .player
+            .get(entity)
+            .map(|player| player.perks.has_rule(rule))
+            .unwrap_or(false)
+    }
+
+    /// The damage a character actually takes from an enemy's attack
+    pub fn damage_from_enemy_attack(&self, victim: Entity, damage: u32) -> u32 {
+        if self.has_perk_rule(victim, PerkRule::ReduceDamageTaken) {
+            damage.saturating_sub(1).max(1)
+        } else {
+            damage
+        }
+    }
+
+    /// Players with the ballast perk are treated as heavy regardless of their usual mass
+    pub(crate) fn perk_mass(&self, entity: Entity) -> Option<Mass> {
+        if self.has_perk_rule(entity, PerkRule::CountAsHeavy) {
+            Some(Mass::Heavy)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn perks(taken: &[Perk]) -> Perks {
+        Perks {
+            taken: taken.to_vec(),
+        }
+    }
+
+    #[test]
+    fn every_perk_in_the_tree_has_a_spec() {
+        for branch in PERK_TREE {
+            assert_eq!(branch.root.spec().perk, branch.root);
+            for &child in branch.children {
+                assert_eq!(child.spec().perk, child);
+            }
+        }
+    }
+
+    #[test]
+    fn children_require_the_root_and_exclude_each_other() {
+        for branch in PERK_TREE {
+            assert_eq!(branch.root.spec().requires, None);
+            for &child in branch.children {
+                let spec = child.spec();
+                assert_eq!(spec.requires, Some(branch.root));
+                for &sibling in branch.children {
+                    if sibling != child {
+                        assert!(spec.excludes.contains(&sibling));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn only_roots_are_available_at_first() {
+        let perks = perks(&[]);
+        let roots = PERK_TREE
+            .iter()
+            .map(|branch| branch.root)
+            .collect::<Vec<_>>();
+        assert_eq!(perks.available(), roots);
+        assert_eq!(perks.status(Perk::Hardy), PerkStatus::Locked);
+        assert_eq!(perks.status(Perk::Deadeye), PerkStatus::Locked);
+    }
+
+    #[test]
+    fn taking_a_root_unlocks_its_children() {
+        let perks = perks(&[Perk::Careful]);
+        assert_eq!(perks.status(Perk::Careful), PerkStatus::Taken);
+        assert_eq!(perks.status(Perk::KillShot), PerkStatus::Available);
+        assert_eq!(perks.status(Perk::Deadeye), PerkStatus::Available);
+        // other branches stay locked
+        assert_eq!(perks.status(Perk::Hardy), PerkStatus::Locked);
+    }
+
+    #[test]
+    fn taking_a_child_rules_out_its_sibling() {
+        let perks = perks(&[Perk::SureFooted, Perk::Ballast]);
+        assert_eq!(perks.status(Perk::Ballast), PerkStatus::Taken);
+        assert_eq!(perks.status(Perk::BigLungs), PerkStatus::Excluded);
+        assert!(!perks.available().contains(&Perk::BigLungs));
+    }
+
+    #[test]
+    fn rules_and_critical_hits_come_from_taken_perks() {
+        let perks = perks(&[Perk::Careful, Perk::Deadeye]);
+        assert!(perks.has_rule(PerkRule::HalveHullPen));
+        assert!(!perks.has_rule(PerkRule::DoubleDamage));
+        assert_eq!(
+            perks.critical_hit_percent(),
+            BASE_CRITICAL_HIT_PERCENT + 10 + 35
+        );
+    }
+}
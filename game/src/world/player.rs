@@ -1,4 +1,4 @@
-use crate::world::{MeleeWeapon, RangedWeapon};
+use crate::world::{perk::Perks, AmmoType, Consumable, MeleeWeapon, RangedWeapon, StatusEffect};
 use gridbugs::rgb_int::Rgb24;
 use serde::{Deserialize, Serialize};
 
@@ -7,8 +7,12 @@ pub struct Player {
     pub melee_weapon: Weapon,
     pub credit: u32,
     pub ranged_weapons: Vec<Option<Weapon>>,
-    pub upgrade_table: UpgradeTable,
-    pub traits: PlayerTraits,
+    pub perks: Perks,
+    pub magnetic_boots: bool,
+    pub keycards: u32,
+    pub flashlight: Option<Flashlight>,
+    pub inventory: Inventory,
+    pub ammo_reserve: AmmoReserve,
 }
 
 impl Player {
@@ -17,12 +21,12 @@ impl Player {
             melee_weapon: Weapon::new_bare_hands(),
             credit: 0,
             ranged_weapons: vec![None, None],
-            upgrade_table: UpgradeTable {
-                toughness: None,
-                accuracy: None,
-                endurance: None,
-            },
-            traits: Default::default(),
+            perks: Default::default(),
+            magnetic_boots: false,
+            keycards: 0,
+            flashlight: None,
+            inventory: Default::default(),
+            ammo_reserve: Default::default(),
         }
     }
 
@@ -30,6 +34,39 @@ impl Player {
         self.ranged_weapons[slot.index()].as_ref()
     }
 
+    pub fn held_weapon(&self, held_weapon: HeldWeapon) -> Option<&Weapon> {
+        match held_weapon {
+            HeldWeapon::Melee => Some(&self.melee_weapon),
+            HeldWeapon::Ranged(slot) => self.ranged_weapons.get(slot.index())?.as_ref(),
+        }
+    }
+
+    pub fn held_weapon_mut(&mut self, held_weapon: HeldWeapon) -> Option<&mut Weapon> {
+        match held_weapon {
+            HeldWeapon::Melee => Some(&mut self.melee_weapon),
+            HeldWeapon::Ranged(slot) => self.ranged_weapons.get_mut(slot.index())?.as_mut(),
+        }
+    }
+
+    /// Weapons which can be taken to a workbench, i.e. everything except bare hands
+    pub fn moddable_weapons(&self) -> Vec<HeldWeapon> {
+        let mut out = Vec::new();
+        if self.melee_weapon.name != WeaponName::BareHands {
+            out.push(HeldWeapon::Melee);
+        }
+        for slot in [
+            RangedWeaponSlot::Slot1,
+            RangedWeaponSlot::Slot2,
+            RangedWeaponSlot::Slot3,
+        ] {
+            let held_weapon = HeldWeapon::Ranged(slot);
+            if self.held_weapon(held_weapon).is_some() {
+                out.push(held_weapon);
+            }
+        }
+        out
+    }
+
     pub fn melee_dmg(&self) -> u32 {
         self.melee_weapon.dmg
     }
@@ -38,50 +75,125 @@ impl Player {
         self.melee_weapon.pen
     }
 
-    pub fn available_upgrades(&self) -> Vec<Upgrade> {
-        let mut out = Vec::new();
-        match self.upgrade_table.toughness {
-            None => out.push(Upgrade {
-                typ: UpgradeType::Toughness,
-                level: UpgradeLevel::Level1,
-            }),
-            Some(UpgradeLevel::Level1) => out.push(Upgrade {
-                typ: UpgradeType::Toughness,
-                level: UpgradeLevel::Level2,
-            }),
-            Some(UpgradeLevel::Level2) => (),
-        }
-        match self.upgrade_table.accuracy {
-            None => out.push(Upgrade {
-                typ: UpgradeType::Accuracy,
-                level: UpgradeLevel::Level1,
-            }),
-            Some(UpgradeLevel::Level1) => out.push(Upgrade {
-                typ: UpgradeType::Accuracy,
-                level: UpgradeLevel::Level2,
-            }),
-            Some(UpgradeLevel::Level2) => (),
-        }
-        match self.upgrade_table.endurance {
-            None => out.push(Upgrade {
-                typ: UpgradeType::Endurance,
-                level: UpgradeLevel::Level1,
-            }),
-            Some(UpgradeLevel::Level1) => out.push(Upgrade {
-                typ: UpgradeType::Endurance,
-                level: UpgradeLevel::Level2,
-            }),
-            Some(UpgradeLevel::Level2) => (),
+    /// Percentage chance that a hit on an enemy deals double damage
+    pub fn critical_hit_percent(&self) -> u32 {
+        self.perks.critical_hit_percent()
+    }
+}
+
+/// Spare ammunition for each family of weapon, used to reload their magazines
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmmoReserve {
+    shells: u32,
+    bullets: u32,
+    slugs: u32,
+    cells: u32,
+}
+
+impl AmmoReserve {
+    pub fn get(&self, ammo_type: AmmoType) -> u32 {
+        match ammo_type {
+            AmmoType::Shells => self.shells,
+            AmmoType::Bullets => self.bullets,
+            AmmoType::Slugs => self.slugs,
+            AmmoType::Cells => self.cells,
         }
-        out
     }
+
+    fn get_mut(&mut self, ammo_type: AmmoType) -> &mut u32 {
+        match ammo_type {
+            AmmoType::Shells => &mut self.shells,
+            AmmoType::Bullets => &mut self.bullets,
+            AmmoType::Slugs => &mut self.slugs,
+            AmmoType::Cells => &mut self.cells,
+        }
+    }
+
+    /// How much more ammunition of this type there is room for
+    pub fn space(&self, ammo_type: AmmoType) -> u32 {
+        ammo_type.max_reserve().saturating_sub(self.get(ammo_type))
+    }
+
+    /// Adds as much of the ammunition as there is room for, returning the amount added
+    pub fn add(&mut self, ammo_type: AmmoType, amount: u32) -> u32 {
+        let reserve = self.get_mut(ammo_type);
+        let added = amount.min(ammo_type.max_reserve().saturating_sub(*reserve));
+        *reserve += added;
+        added
+    }
+
+    /// Removes up to the requested amount of ammunition, returning the amount removed
+    pub fn take(&mut self, ammo_type: AmmoType, amount: u32) -> u32 {
+        let reserve = self.get_mut(ammo_type);
+        let taken = amount.min(*reserve);
+        *reserve -= taken;
+        taken
+    }
+}
+
+/// Maximum number of consumables the player can carry
+pub const INVENTORY_CAPACITY: usize = 6;
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inventory {
+    items: Vec<Consumable>,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct PlayerTraits {
-    pub reduce_hull_pen: bool,
-    pub double_damage: bool,
-    pub half_vacuum_pull: bool,
+impl Inventory {
+    pub fn iter(&self) -> impl '_ + Iterator<Item = Consumable> {
+        self.items.iter().cloned()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Consumable> {
+        self.items.get(index).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= INVENTORY_CAPACITY
+    }
+
+    pub fn count(&self, consumable: Consumable) -> usize {
+        self.items
+            .iter()
+            .filter(|&&item| item == consumable)
+            .count()
+    }
+
+    /// Returns false if there was no room for the item
+    pub fn insert(&mut self, consumable: Consumable) -> bool {
+        if self.is_full() {
+            false
+        } else {
+            self.items.push(consumable);
+            true
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Consumable> {
+        if index < self.items.len() {
+            Some(self.items.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Removes one of the given kind of item, returning false if there were none
+    pub fn take(&mut self, consumable: Consumable) -> bool {
+        if let Some(index) = self.items.iter().position(|&item| item == consumable) {
+            self.items.remove(index);
+            true
+        } else {
+            false
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -91,11 +203,85 @@ pub enum WeaponName {
     MeleeWeapon(MeleeWeapon),
 }
 
+impl WeaponName {
+    /// The kind of ammunition used to reload the weapon, if it can be reloaded
+    pub fn ammo_type(self) -> Option<AmmoType> {
+        match self {
+            Self::RangedWeapon(ranged_weapon) => Some(ranged_weapon.ammo_type()),
+            Self::BareHands | Self::MeleeWeapon(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WeaponAbility {
     KnockBack,
     LifeSteal,
     Oxidise,
+    Stun,
+    Ignite,
+    Slow,
+    Bleed,
+    /// Walking into a closed door wedges it open so it can't close again
+    Pry,
+    /// Walking into a wall or window hacks at it, possibly breaching the hull
+    Breach,
+}
+
+impl WeaponAbility {
+    /// Abilities which can be added to a weapon at a workbench
+    pub const FITTABLE: [Self; 5] = [
+        Self::KnockBack,
+        Self::Stun,
+        Self::Ignite,
+        Self::Slow,
+        Self::Bleed,
+    ];
+
+    /// The status effect inflicted on characters damaged by a weapon with this ability
+    pub fn status_effect(self) -> Option<StatusEffect> {
+        match self {
+            Self::Stun => Some(StatusEffect::Stunned),
+            Self::Ignite => Some(StatusEffect::Burning),
+            Self::Slow => Some(StatusEffect::Slowed),
+            Self::Bleed => Some(StatusEffect::Bleeding),
+            Self::KnockBack | Self::LifeSteal | Self::Oxidise | Self::Pry | Self::Breach => None,
+        }
+    }
+}
+
+/// A modification fitted to a weapon at a workbench. Each kind of mod can only be fitted to a
+/// weapon once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeaponMod {
+    /// Increases PEN by half
+    ArmourPiercing,
+    /// Halves HULL PEN
+    Dampener,
+    /// Increases the magazine size by half
+    ExtendedMagazine,
+    Ability(WeaponAbility),
+}
+
+impl WeaponMod {
+    pub fn cost(self) -> u32 {
+        match self {
+            Self::ArmourPiercing => 6,
+            Self::Dampener => 3,
+            Self::ExtendedMagazine => 4,
+            Self::Ability(_) => 8,
+        }
+    }
+
+    fn is_same_kind(self, other: Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::ArmourPiercing, Self::ArmourPiercing)
+                | (Self::Dampener, Self::Dampener)
+                | (Self::ExtendedMagazine, Self::ExtendedMagazine)
+                | (Self::Ability(_), Self::Ability(_))
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -110,6 +296,26 @@ impl Ammo {
     }
 }
 
+/// Number of turns a fully charged flashlight stays on for
+pub const FLASHLIGHT_BATTERY: u32 = 80;
+
+/// Lights a cone of cells in the direction the player is facing. The battery drains while it's
+/// switched on and is recharged at the start of each floor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flashlight {
+    pub on: bool,
+    pub battery: u32,
+}
+
+impl Flashlight {
+    pub fn new_charged() -> Self {
+        Self {
+            on: false,
+            battery: FLASHLIGHT_BATTERY,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Weapon {
     pub name: WeaponName,
@@ -118,6 +324,7 @@ pub struct Weapon {
     pub dmg: u32,
     pub hull_pen_percent: u32,
     pub abilities: Vec<WeaponAbility>,
+    pub mods: Vec<WeaponMod>,
     pub light_colour: Option<Rgb24>,
     pub bright: bool,
 }
@@ -131,6 +338,7 @@ impl Weapon {
             dmg: 2,
             hull_pen_percent: 0,
             abilities: vec![WeaponAbility::KnockBack],
+            mods: Vec::new(),
             light_colour: None,
             bright: false,
         }
@@ -143,6 +351,46 @@ impl Weapon {
             dmg: 5,
             hull_pen_percent: 0,
             abilities: vec![],
+            mods: Vec::new(),
+            light_colour: None,
+            bright: false,
+        }
+    }
+    pub fn new_crowbar() -> Self {
+        Self {
+            name: WeaponName::MeleeWeapon(MeleeWeapon::Crowbar),
+            ammo: None,
+            pen: 4,
+            dmg: 3,
+            hull_pen_percent: 0,
+            abilities: vec![WeaponAbility::Pry],
+            mods: Vec::new(),
+            light_colour: None,
+            bright: false,
+        }
+    }
+    pub fn new_shock_baton() -> Self {
+        Self {
+            name: WeaponName::MeleeWeapon(MeleeWeapon::ShockBaton),
+            ammo: Some(Ammo::new_full(10)),
+            pen: 3,
+            dmg: 2,
+            hull_pen_percent: 0,
+            abilities: vec![WeaponAbility::Stun],
+            mods: Vec::new(),
+            light_colour: None,
+            bright: false,
+        }
+    }
+    pub fn new_fire_axe() -> Self {
+        Self {
+            name: WeaponName::MeleeWeapon(MeleeWeapon::FireAxe),
+            ammo: Some(Ammo::new_full(8)),
+            pen: 6,
+            dmg: 4,
+            hull_pen_percent: 0,
+            abilities: vec![WeaponAbility::Breach],
+            mods: Vec::new(),
             light_colour: None,
             bright: false,
         }
@@ -155,6 +403,7 @@ impl Weapon {
             dmg: 8,
             hull_pen_percent: 40,
             abilities: vec![WeaponAbility::KnockBack],
+            mods: Vec::new(),
             light_colour: None,
             bright: false,
         }
@@ -167,6 +416,7 @@ impl Weapon {
             dmg: 10,
             hull_pen_percent: 75,
             abilities: vec![],
+            mods: Vec::new(),
             light_colour: Some(Rgb24::new(0, 255, 255)),
             bright: true,
         }
@@ -179,6 +429,7 @@ impl Weapon {
             dmg: 4,
             hull_pen_percent: 40,
             abilities: vec![],
+            mods: Vec::new(),
             light_colour: None,
             bright: false,
         }
@@ -191,6 +442,7 @@ impl Weapon {
             dmg: 10,
             hull_pen_percent: 0,
             abilities: vec![],
+            mods: Vec::new(),
             light_colour: Some(Rgb24::new(127, 0, 255)),
             bright: true,
         }
@@ -203,6 +455,7 @@ impl Weapon {
             dmg: 4,
             hull_pen_percent: 0,
             abilities: vec![WeaponAbility::Oxidise],
+            mods: Vec::new(),
             light_colour: Some(Rgb24::new(0, 0, 255)),
             bright: false,
         }
@@ -215,10 +468,52 @@ impl Weapon {
             dmg: 4,
             hull_pen_percent: 0,
             abilities: vec![WeaponAbility::LifeSteal],
+            mods: Vec::new(),
             light_colour: Some(Rgb24::new(255, 0, 0)),
             bright: false,
         }
     }
+    /// The mods that a workbench offering the given ability could fit to this weapon
+    pub fn available_mods(&self, ability: WeaponAbility) -> Vec<WeaponMod> {
+        [
+            WeaponMod::ArmourPiercing,
+            WeaponMod::Dampener,
+            WeaponMod::ExtendedMagazine,
+            WeaponMod::Ability(ability),
+        ]
+        .into_iter()
+        .filter(|&weapon_mod| self.can_fit(weapon_mod))
+        .collect()
+    }
+
+    pub fn can_fit(&self, weapon_mod: WeaponMod) -> bool {
+        if self.name == WeaponName::BareHands
+            || self.mods.iter().any(|&m| m.is_same_kind(weapon_mod))
+        {
+            return false;
+        }
+        match weapon_mod {
+            WeaponMod::ArmourPiercing => true,
+            WeaponMod::Dampener => self.hull_pen_percent > 0,
+            WeaponMod::ExtendedMagazine => self.ammo.is_some(),
+            WeaponMod::Ability(ability) => !self.abilities.contains(&ability),
+        }
+    }
+
+    pub fn apply_mod(&mut self, weapon_mod: WeaponMod) {
+        match weapon_mod {
+            WeaponMod::ArmourPiercing => self.pen += (self.pen / 2).max(1),
+            WeaponMod::Dampener => self.hull_pen_percent /= 2,
+            WeaponMod::ExtendedMagazine => {
+                if let Some(ammo) = self.ammo.as_mut() {
+                    ammo.max += (ammo.max / 2).max(1);
+                }
+            }
+            WeaponMod::Ability(ability) => self.abilities.push(ability),
+        }
+        self.mods.push(weapon_mod);
+    }
+
     pub fn is_ranged(&self) -> bool {
         match self.name {
             WeaponName::RangedWeapon(_) => true,
@@ -257,50 +552,54 @@ impl RangedWeaponSlot {
     }
 }
 
-// Toughness:
-//   1. Extra weapon slot
-//   2. Double HP
-//
-// Accuracy:
-//   1. Reduce hull pen chance to half
-//   2. Deal double damage
-//
-// Endurance:
-//   1. Half effect of vacumm pull
-//   2. Double oxygen
-//
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct UpgradeTable {
-    pub toughness: Option<UpgradeLevel>,
-    pub accuracy: Option<UpgradeLevel>,
-    pub endurance: Option<UpgradeLevel>,
+/// A weapon the player is holding, which may be their melee weapon or one of their ranged weapons
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeldWeapon {
+    Melee,
+    Ranged(RangedWeaponSlot),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum UpgradeLevel {
-    Level1,
-    Level2,
-}
+#[cfg(test)]
+mod test {
+    use super::*;
 
-impl UpgradeLevel {
-    pub fn cost(self) -> u32 {
-        match self {
-            Self::Level1 => 5,
-            Self::Level2 => 10,
-        }
+    #[test]
+    fn add_is_capped_at_the_max_reserve() {
+        let mut reserve = AmmoReserve::default();
+        assert_eq!(reserve.add(AmmoType::Slugs, 4), 4);
+        assert_eq!(reserve.add(AmmoType::Slugs, 4), 2);
+        assert_eq!(reserve.get(AmmoType::Slugs), AmmoType::Slugs.max_reserve());
+        assert_eq!(reserve.add(AmmoType::Slugs, 4), 0);
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum UpgradeType {
-    Toughness,
-    Accuracy,
-    Endurance,
-}
+    #[test]
+    fn take_is_capped_at_the_current_reserve() {
+        let mut reserve = AmmoReserve::default();
+        reserve.add(AmmoType::Shells, 3);
+        assert_eq!(reserve.take(AmmoType::Shells, 2), 2);
+        assert_eq!(reserve.take(AmmoType::Shells, 2), 1);
+        assert_eq!(reserve.take(AmmoType::Shells, 2), 0);
+        assert_eq!(reserve.get(AmmoType::Shells), 0);
+    }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Upgrade {
-    pub typ: UpgradeType,
-    pub level: UpgradeLevel,
+    #[test]
+    fn space_is_what_add_would_accept() {
+        let mut reserve = AmmoReserve::default();
+        for ammo_type in AmmoType::ALL {
+            assert_eq!(reserve.space(ammo_type), ammo_type.max_reserve());
+            reserve.add(ammo_type, ammo_type.pickup_amount());
+            let space = reserve.space(ammo_type);
+            assert_eq!(space, ammo_type.max_reserve() - ammo_type.pickup_amount());
+            assert_eq!(reserve.clone().add(ammo_type, u32::MAX), space);
+        }
+    }
+
+    #[test]
+    fn ammo_types_are_kept_separately() {
+        let mut reserve = AmmoReserve::default();
+        reserve.add(AmmoType::Bullets, 5);
+        assert_eq!(reserve.get(AmmoType::Bullets), 5);
+        assert_eq!(reserve.take(AmmoType::Shells, 5), 0);
+        assert_eq!(reserve.get(AmmoType::Cells), 0);
+    }
 }
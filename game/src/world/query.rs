@@ -1,10 +1,41 @@
 use crate::{
-    world::{data::Tile, World},
+    world::{
+        data::{Disposition, DoorState, Mass, Tile},
+        World,
+    },
     Entity,
 };
-use gridbugs::{coord_2d::Coord, line_2d::LineSegment};
+use gridbugs::{
+    coord_2d::Coord,
+    direction::{CardinalDirections, Direction, Directions},
+    line_2d::LineSegment,
+};
+
+/// How close a survivor needs to be to the player when they take the stairs to be rescued
+const ESCORT_DISTANCE: i32 = 2;
 
 impl World {
+    /// Returns true if an attack travelling in the given direction would strike the character
+    /// from behind
+    pub fn is_hit_from_behind(&self, entity: Entity, attack_direction: Coord) -> bool {
+        if let Some(facing) = self.components.facing.get(entity) {
+            let facing = facing.coord();
+            facing.x * attack_direction.x + facing.y * attack_direction.y > 0
+        } else {
+            false
+        }
+    }
+
+    /// The armour protecting a character from an attack travelling in the given direction
+    pub fn armour_against(&self, entity: Entity, attack_direction: Coord) -> Option<u32> {
+        let armour = self.components.armour.get(entity)?;
+        if self.is_hit_from_behind(entity, attack_direction) {
+            Some(armour.rear)
+        } else {
+            Some(armour.value)
+        }
+    }
+
     pub fn is_solid_feature_at_coord(&self, coord: Coord) -> bool {
         let cell = self.spatial_table.layers_at_checked(coord);
         if let Some(feature) = cell.feature {
@@ -14,6 +45,24 @@ impl World {
         }
     }
 
+    /// Characters can't squeeze diagonally between two solid features, so diagonal steps never
+    /// cross a gap that air can't flow through
+    pub fn is_diagonal_step_blocked(&self, coord: Coord, direction: Direction) -> bool {
+        if let Some(ordinal_direction) = direction.ordinal() {
+            let (a, b) = ordinal_direction.to_cardinals();
+            let is_solid = |coord| {
+                self.spatial_table
+                    .layers_at(coord)
+                    .and_then(|layers| layers.feature)
+                    .map(|feature| self.components.solid.contains(feature))
+                    .unwrap_or(false)
+            };
+            is_solid(coord + a.coord()) && is_solid(coord + b.coord())
+        } else {
+            false
+        }
+    }
+
     pub fn is_solid_feature_in_line_segment(&self, line_segment: LineSegment) -> bool {
         for coord in line_segment.iter() {
             if self.is_solid_feature_at_coord(coord) {
@@ -28,7 +77,12 @@ impl World {
             if let Some(entity) = spatial_cell.feature {
                 if let Some(tile) = self.components.tile.get(entity) {
                     match tile {
-                        Tile::Window(_) | Tile::Wall => true,
+                        Tile::Window(_)
+                        | Tile::WindowCracked(_)
+                        | Tile::Wall
+                        | Tile::WallCracked
+                        | Tile::WallLight
+                        | Tile::WallLightBroken => true,
                         _ => false,
                     }
                 } else {
@@ -47,9 +101,14 @@ impl World {
             if let Some(entity) = spatial_cell.feature {
                 if let Some(tile) = self.components.tile.get(entity) {
                     match tile {
-                        Tile::Window(_) | Tile::Wall | Tile::DoorClosed(_) | Tile::DoorOpen(_) => {
-                            true
-                        }
+                        Tile::Window(_)
+                        | Tile::WindowCracked(_)
+                        | Tile::Wall
+                        | Tile::WallCracked
+                        | Tile::WallLight
+                        | Tile::WallLightBroken
+                        | Tile::DoorClosed(_)
+                        | Tile::DoorOpen(_) => true,
                         _ => false,
                     }
                 } else {
@@ -75,17 +134,104 @@ impl World {
         }
     }
 
+    /// The outside of the station's hull: a cell with no floor which is next to a wall, window
+    /// or door. A player wearing magnetic boots can walk on these cells.
+    pub fn is_hull_exterior_at_coord(&self, coord: Coord) -> bool {
+        if let Some(spatial_cell) = self.spatial_table.layers_at(coord) {
+            if spatial_cell.floor.is_some() {
+                return false;
+            }
+            Directions
+                .into_iter()
+                .any(|direction| self.is_wall_like_at_coord(coord + direction.coord()))
+        } else {
+            false
+        }
+    }
+
+    pub fn mass(&self, entity: Entity) -> Mass {
+        if let Some(mass) = self.perk_mass(entity) {
+            mass
+        } else if let Some(&mass) = self.components.mass.get(entity) {
+            mass
+        } else if self.components.character.contains(entity) {
+            Mass::Normal
+        } else {
+            Mass::Light
+        }
+    }
+
+    /// A character standing in or next to a doorway can hold onto the door to resist the vacuum
+    pub fn is_anchored(&self, entity: Entity) -> bool {
+        if !self.components.character.contains(entity) {
+            return false;
+        }
+        if let Some(coord) = self.spatial_table.coord_of(entity) {
+            std::iter::once(coord)
+                .chain(CardinalDirections.into_iter().map(|d| coord + d.coord()))
+                .any(|coord| self.is_door_at_coord(coord))
+        } else {
+            false
+        }
+    }
+
+    fn is_door_at_coord(&self, coord: Coord) -> bool {
+        self.spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.feature)
+            .map(|feature| self.components.door_state.contains(feature))
+            .unwrap_or(false)
+    }
+
+    pub fn is_next_to_window(&self, coord: Coord) -> bool {
+        CardinalDirections.into_iter().any(|direction| {
+            self.spatial_table
+                .layers_at(coord + direction.coord())
+                .and_then(|layers| layers.feature)
+                .and_then(|feature| self.components.tile.get(feature))
+                .map(|tile| matches!(tile, Tile::Window(_) | Tile::WindowCracked(_)))
+                .unwrap_or(false)
+        })
+    }
+
+    /// For npcs with a ranged attack, returns whether they are ready to fire
+    pub fn npc_ranged_attack_ready(&self, entity: Entity) -> Option<bool> {
+        self.components
+            .ranged_attack
+            .get(entity)
+            .map(|ranged_attack| ranged_attack.turns_until_ready == 0)
+    }
+
+    pub fn has_magnetic_boots(&self, entity: Entity) -> bool {
+        self.components
+            .player
+            .get(entity)
+            .map(|player| player.magnetic_boots)
+            .unwrap_or(false)
+    }
+
+    /// A character wearing magnetic boots on the outside of the hull can't be pulled by the vacuum
+    pub fn is_held_to_hull(&self, entity: Entity) -> bool {
+        self.has_magnetic_boots(entity)
+            && self
+                .spatial_table
+                .coord_of(entity)
+                .is_some_and(|coord| self.is_hull_exterior_at_coord(coord))
+    }
+
     pub fn can_npc_traverse_feature_at_coord(&self, coord: Coord) -> bool {
         if let Some(spatial_cell) = self.spatial_table.layers_at(coord) {
             if spatial_cell.floor.is_none() {
                 return false;
             }
             if let Some(feature) = spatial_cell.feature {
-                self.components.door_state.contains(feature)
-                    || !(self.components.solid.contains(feature)
-                        || self.components.stairs.contains(feature)
-                        || self.components.upgrade.contains(feature)
-                        || self.components.map.contains(feature))
+                matches!(
+                    self.components.door_state.get(feature),
+                    Some(DoorState::Open | DoorState::Closed)
+                ) || !(self.components.solid.contains(feature)
+                    || self.components.stairs.contains(feature)
+                    || self.components.upgrade.contains(feature)
+                    || self.components.map.contains(feature))
             } else {
                 true
             }
@@ -118,6 +264,44 @@ impl World {
         }
     }
 
+    pub fn is_friendly_npc(&self, entity: Entity) -> bool {
+        self.components
+            .npc
+            .get(entity)
+            .map(|npc| npc.disposition == Disposition::Friendly)
+            .unwrap_or(false)
+    }
+
+    pub fn is_hostile_npc(&self, entity: Entity) -> bool {
+        self.components
+            .npc
+            .get(entity)
+            .map(|npc| npc.disposition != Disposition::Friendly)
+            .unwrap_or(false)
+    }
+
+    pub fn friendly_npc_coords(&self) -> impl '_ + Iterator<Item = Coord> {
+        self.components
+            .npc
+            .iter()
+            .filter(|(_, npc)| npc.disposition == Disposition::Friendly)
+            .filter_map(|(entity, _)| self.spatial_table.coord_of(entity))
+    }
+
+    /// Counts the friendly npcs close enough to a character to follow them down the stairs
+    pub fn num_survivors_escorted_by(&self, character: Entity) -> u32 {
+        let coord = match self.spatial_table.coord_of(character) {
+            Some(coord) => coord,
+            None => return 0,
+        };
+        self.friendly_npc_coords()
+            .filter(|&survivor_coord| {
+                let delta = survivor_coord - coord;
+                delta.x.abs().max(delta.y.abs()) <= ESCORT_DISTANCE
+            })
+            .count() as u32
+    }
+
     pub fn is_character_at_coord(&self, coord: Coord) -> bool {
         if let Some(spatial_cell) = self.spatial_table.layers_at(coord) {
             spatial_cell.character.is_some()
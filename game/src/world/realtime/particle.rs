@@ -220,6 +220,8 @@ impl RealtimeComponent for ParticleEmitterState {
                     life_steal: false,
                     oxidise: false,
                     weapon_name: None,
+                    enemy: None,
+                    status_effect: None,
                 })
             }),
         };
@@ -0,0 +1,183 @@
+use crate::{
+    world::{
+        action::Error,
+        data::{DoorState, Tile},
+        noise, player, World,
+    },
+    Entity, Message,
+};
+use gridbugs::direction::CardinalDirections;
+
+/// Number of turns the player must spend at a locked door without a keycard before it opens
+const HACK_TURNS: u32 = 6;
+
+impl World {
+    /// Turns a locked door into an ordinary closed door, which can be broken down like any other
+    pub fn unlock_door(&mut self, door: Entity) {
+        self.components.hack_progress.remove(door);
+        self.components.door_state.insert(door, DoorState::Closed);
+        self.components.destructible.insert(door, ());
+        if let Some(tile) = self.components.tile.get_mut(door) {
+            if let Tile::DoorLocked(axis) = *tile {
+                *tile = Tile::DoorClosed(axis);
+            }
+        }
+    }
+
+    /// Called when a character walks into a locked door. A pry bar forces the lock, otherwise a
+    /// keycard is used up if the player has one, and failing that the player spends the turn
+    /// hacking at the lock. Returns true if the door is now unlocked.
+    pub fn character_try_unlock_door(
+        &mut self,
+        character: Entity,
+        door: Entity,
+        message_log: &mut Vec<Message>,
+    ) -> Result<bool, Error> {
+        if self.has_melee_ability(character, player::WeaponAbility::Pry) {
+            self.unlock_door(door);
+            message_log.push(Message::PryLock);
+            return Ok(true);
+        }
+        let player = self
+            .components
+            .player
+            .get_mut(character)
+            .ok_or(Error::WalkIntoSolidCell)?;
+        if player.keycards > 0 {
+            player.keycards -= 1;
+            self.unlock_door(door);
+            message_log.push(Message::UnlockDoor);
+            return Ok(true);
+        }
+        let progress = self
+            .components
+            .hack_progress
+            .get(door)
+            .cloned()
+            .unwrap_or(0)
+            + 1;
+        if let Some(coord) = self.spatial_table.coord_of(door) {
+            self.make_noise(coord, noise::loudness::HACK_LOCK);
+        }
+        if progress >= HACK_TURNS {
+            self.unlock_door(door);
+            message_log.push(Message::DoorHacked);
+            Ok(true)
+        } else {
+            self.components.hack_progress.insert(door, progress);
+            message_log.push(Message::HackDoor {
+                progress,
+                total: HACK_TURNS,
+            });
+            Ok(false)
+        }
+    }
+
+    /// Leaves a keycard where the character is standing, or in a neighbouring cell if there's
+    /// already an item there
+    pub fn drop_keycard(&mut self, character: Entity) {
+        self.components.carries_keycard.remove(character);
+        let coord = match self.spatial_table.coord_of(character) {
+            Some(coord) => coord,
+            None => return,
+        };
+        let is_free = |world: &Self, coord| {
+            world
+                .spatial_table
+                .layers_at(coord)
+                .map(|layers| layers.item.is_none() && layers.floor.is_some())
+                .unwrap_or(false)
+        };
+        let drop_coord = std::iter::once(coord)
+            .chain(
+                CardinalDirections
+                    .into_iter()
+                    .map(|direction| coord + direction.coord()),
+            )
+            .find(|&coord| is_free(self, coord));
+        if let Some(drop_coord) = drop_coord {
+            self.spawn_keycard(drop_coord);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{terrain, world::spawn};
+    use gridbugs::coord_2d::{Axis, Coord};
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    const MAP: &str = "
+###.#
+#@..#
+#####
+";
+
+    fn world_with_locked_door() -> (World, Entity, Entity) {
+        let terrain = terrain::from_str(MAP, spawn::make_player());
+        let mut world = terrain.world;
+        let door = world.spawn_locked_door(Coord::new(3, 1), Axis::X);
+        (world, terrain.player, door)
+    }
+
+    fn unlock(world: &mut World, player: Entity, door: Entity) -> (bool, Vec<Message>) {
+        let mut message_log = Vec::new();
+        let unlocked = world
+            .character_try_unlock_door(player, door, &mut message_log)
+            .unwrap();
+        (unlocked, message_log)
+    }
+
+    #[test]
+    fn locked_doors_cannot_be_broken_down() {
+        let (mut world, _, door) = world_with_locked_door();
+        let window = world.spawn_window(Coord::new(3, 0), Axis::X);
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        assert!(!world.damage_structure(door, u32::MAX, &mut rng, &mut Vec::new()));
+        assert!(world.damage_structure(window, u32::MAX, &mut rng, &mut Vec::new()));
+        let integrity = world.components.integrity.get(door).unwrap();
+        assert_eq!(integrity.current, integrity.max);
+    }
+
+    #[test]
+    fn a_keycard_unlocks_a_door() {
+        let (mut world, player, door) = world_with_locked_door();
+        world.components.player.get_mut(player).unwrap().keycards = 1;
+        let (unlocked, message_log) = unlock(&mut world, player, door);
+        assert!(unlocked);
+        assert!(matches!(message_log[..], [Message::UnlockDoor]));
+        assert_eq!(world.components.player.get(player).unwrap().keycards, 0);
+        assert_eq!(
+            world.components.door_state.get(door),
+            Some(&DoorState::Closed)
+        );
+        assert!(world.components.destructible.contains(door));
+    }
+
+    #[test]
+    fn a_pry_bar_forces_the_lock() {
+        let (mut world, player, door) = world_with_locked_door();
+        world
+            .components
+            .player
+            .get_mut(player)
+            .unwrap()
+            .melee_weapon = player::Weapon::new_crowbar();
+        let (unlocked, message_log) = unlock(&mut world, player, door);
+        assert!(unlocked);
+        assert!(matches!(message_log[..], [Message::PryLock]));
+    }
+
+    #[test]
+    fn hacking_takes_several_turns() {
+        let (mut world, player, door) = world_with_locked_door();
+        for _ in 1..HACK_TURNS {
+            assert!(!unlock(&mut world, player, door).0);
+        }
+        let (unlocked, message_log) = unlock(&mut world, player, door);
+        assert!(unlocked);
+        assert!(matches!(message_log[..], [Message::DoorHacked]));
+    }
+}
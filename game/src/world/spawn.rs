@@ -2,25 +2,41 @@ use crate::{
     visibility::Light,
     world::{
         data::{
-            Armour, CollidesWith, Disposition, DoorState, Enemy, EntityData, HitPoints, Item,
-            Layer, Location, MeleeWeapon, Npc, OnCollision, Oxygen, ProjectileDamage, RangedWeapon,
-            Tile,
+            AmmoType, Armour, Boss, BossPhase, CollidesWith, Consumable, Disposition, DoorState,
+            Enemy, EntityData, Grenade, HitPoints, Item, Layer, Location, Mass, MeleeWeapon, Npc,
+            OnCollision, Oxygen, ProjectileDamage, RangedAttack, RangedWeapon, StatusEffect,
+            StatusEffects, Tile, Vendor,
         },
         explosion,
         player::{self, WeaponAbility},
-        realtime, World,
+        realtime,
+        vendor::Ware,
+        World,
     },
     Entity,
 };
 use gridbugs::{
     coord_2d::{Axis, Coord},
-    direction::CardinalDirections,
+    direction::{CardinalDirection, CardinalDirections},
     rgb_int::Rgb24,
     shadowcast::vision_distance::Circle,
 };
 use rand::Rng;
 use std::time::Duration;
 
+const WALL_INTEGRITY: u32 = 6;
+const DOOR_INTEGRITY: u32 = 6;
+const WINDOW_INTEGRITY: u32 = 3;
+
+const GUARDIAN_HIT_POINTS: u32 = 40;
+/// Only the heaviest weapons can penetrate the guardian's armour from the front
+const GUARDIAN_ARMOUR: u32 = 12;
+
+/// Tanks are heavily armoured at the front but exposed from behind
+const TANK_REAR_ARMOUR: u32 = 4;
+
+const STATION_LIGHT_COLOUR: Rgb24 = Rgb24::new(200, 200, 160);
+
 pub fn make_player() -> EntityData {
     EntityData {
         tile: Some(Tile::Player),
@@ -61,6 +77,9 @@ impl World {
         self.components.solid.insert(entity, ());
         self.components.opacity.insert(entity, 255);
         self.components.destructible.insert(entity, ());
+        self.components
+            .integrity
+            .insert(entity, HitPoints::new_full(WALL_INTEGRITY));
         entity
     }
 
@@ -141,6 +160,39 @@ impl World {
         target: Coord,
         weapon: &player::Weapon,
         rng: &mut R,
+    ) -> Entity {
+        let projectile_damage = ProjectileDamage {
+            hit_points: weapon.dmg,
+            push_back: weapon.abilities.contains(&player::WeaponAbility::KnockBack),
+            pen: weapon.pen,
+            hull_pen_percent: weapon.hull_pen_percent,
+            oxidise: weapon.abilities.contains(&WeaponAbility::Oxidise),
+            life_steal: weapon.abilities.contains(&WeaponAbility::LifeSteal),
+            weapon_name: Some(weapon.name),
+            enemy: None,
+            status_effect: weapon
+                .abilities
+                .iter()
+                .find_map(|ability| ability.status_effect()),
+        };
+        self.spawn_projectile(
+            start,
+            target,
+            projectile_damage,
+            weapon.light_colour,
+            weapon.bright,
+            rng,
+        )
+    }
+
+    pub fn spawn_projectile<R: Rng>(
+        &mut self,
+        start: Coord,
+        target: Coord,
+        projectile_damage: ProjectileDamage,
+        light_colour: Option<Rgb24>,
+        bright: bool,
+        rng: &mut R,
     ) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
@@ -166,9 +218,9 @@ impl World {
             }
             .build(),
         );
-        let particle_emitter_ = if let Some(light_colour) = weapon.light_colour {
+        let particle_emitter_ = if let Some(light_colour) = light_colour {
             use realtime::particle::spec::*;
-            if weapon.bright {
+            if bright {
                 ParticleEmitter {
                     emit_particle_every_period: Duration::from_millis(8),
                     fade_out_duration: None,
@@ -247,27 +299,9 @@ impl World {
             },
         );
         self.components.tile.insert(entity, Tile::Bullet);
-        self.components.projectile_damage.insert(
-            entity,
-            ProjectileDamage {
-                hit_points: weapon.dmg,
-                push_back: weapon
-                    .abilities
-                    .iter()
-                    .any(|a| *a == player::WeaponAbility::KnockBack),
-                pen: weapon.pen,
-                hull_pen_percent: weapon.hull_pen_percent,
-                oxidise: weapon
-                    .abilities
-                    .iter()
-                    .any(|a| *a == WeaponAbility::Oxidise),
-                life_steal: weapon
-                    .abilities
-                    .iter()
-                    .any(|a| *a == WeaponAbility::LifeSteal),
-                weapon_name: Some(weapon.name),
-            },
-        );
+        self.components
+            .projectile_damage
+            .insert(entity, projectile_damage);
         entity
     }
 
@@ -356,6 +390,45 @@ impl World {
         emitter_entity
     }
 
+    pub fn spawn_window_shatter_emitter<R: Rng>(&mut self, coord: Coord, rng: &mut R) -> Entity {
+        let emitter_entity = self.entity_allocator.alloc();
+        let duration = Duration::from_millis(100);
+        self.spatial_table
+            .update(emitter_entity, Location { coord, layer: None })
+            .unwrap();
+        self.realtime_components
+            .fade
+            .insert(emitter_entity, realtime::fade::FadeState::new(duration));
+        self.components.realtime.insert(emitter_entity, ());
+        self.realtime_components
+            .particle_emitter
+            .insert(emitter_entity, {
+                use realtime::particle::spec::*;
+                ParticleEmitter {
+                    emit_particle_every_period: realtime::data::period_per_frame(40),
+                    fade_out_duration: Some(duration),
+                    particle: Particle {
+                        tile: None,
+                        movement: Some(Movement {
+                            angle_range: Radians::uniform_range_all(),
+                            cardinal_period_range: UniformInclusiveRange {
+                                low: Duration::from_millis(20),
+                                high: Duration::from_millis(80),
+                            },
+                        }),
+                        fade_duration: Some(Duration::from_millis(400)),
+                        colour_hint: Some(UniformInclusiveRange {
+                            low: Rgb24::new(127, 187, 200),
+                            high: Rgb24::new(255, 255, 255),
+                        }),
+                        ..Default::default()
+                    },
+                }
+                .build(rng)
+            });
+        emitter_entity
+    }
+
     pub fn spawn_door(&mut self, coord: Coord, axis: Axis) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
@@ -372,6 +445,18 @@ impl World {
         self.components.solid.insert(entity, ());
         self.components.door_state.insert(entity, DoorState::Closed);
         self.components.destructible.insert(entity, ());
+        self.components
+            .integrity
+            .insert(entity, HitPoints::new_full(DOOR_INTEGRITY));
+        entity
+    }
+
+    /// Locked doors can't be broken down, so the only way through is to unlock them
+    pub fn spawn_locked_door(&mut self, coord: Coord, axis: Axis) -> Entity {
+        let entity = self.spawn_door(coord, axis);
+        self.components.tile.insert(entity, Tile::DoorLocked(axis));
+        self.components.door_state.insert(entity, DoorState::Locked);
+        self.components.destructible.remove(entity);
         entity
     }
 
@@ -389,6 +474,9 @@ impl World {
         self.components.tile.insert(entity, Tile::Window(axis));
         self.components.solid.insert(entity, ());
         self.components.destructible.insert(entity, ());
+        self.components
+            .integrity
+            .insert(entity, HitPoints::new_full(WINDOW_INTEGRITY));
         entity
     }
 
@@ -448,6 +536,57 @@ impl World {
         entity
     }
 
+    pub fn spawn_workbench(&mut self, coord: Coord, ability: WeaponAbility) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Workbench);
+        self.components.workbench.insert(entity, ability);
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_vendor(&mut self, coord: Coord, stock: Vec<Ware>) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Vendor);
+        self.components.vendor.insert(entity, Vendor { stock });
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_terminal(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Terminal);
+        self.components.terminal.insert(entity, true);
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
     pub fn spawn_map(&mut self, coord: Coord) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
@@ -489,6 +628,9 @@ impl World {
             .insert(entity, HitPoints::new_full(4));
         self.components.armour.insert(entity, Armour::new(2));
         self.components.damage.insert(entity, 1);
+        self.components
+            .attack_status_effect
+            .insert(entity, StatusEffect::Bleeding);
         self.components.enemy.insert(entity, Enemy::Zombie);
         entity
     }
@@ -519,6 +661,7 @@ impl World {
         self.components.damage.insert(entity, 1);
         self.components.skeleton.insert(entity, ());
         self.components.enemy.insert(entity, Enemy::Skeleton);
+        self.components.mass.insert(entity, Mass::Light);
         entity
     }
 
@@ -577,10 +720,118 @@ impl World {
         self.components
             .hit_points
             .insert(entity, HitPoints::new_full(10));
-        self.components.armour.insert(entity, Armour::new(10));
+        self.components
+            .armour
+            .insert(entity, Armour::directional(10, TANK_REAR_ARMOUR));
         self.components.damage.insert(entity, 2);
         self.components.push_back.insert(entity, ());
+        self.components
+            .attack_status_effect
+            .insert(entity, StatusEffect::Stunned);
         self.components.enemy.insert(entity, Enemy::Tank);
+        self.components.mass.insert(entity, Mass::Heavy);
+        entity
+    }
+
+    pub fn spawn_drone(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Character),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Drone);
+        self.components.npc.insert(
+            entity,
+            Npc {
+                disposition: Disposition::Hostile,
+            },
+        );
+        self.components.character.insert(entity, ());
+        self.components
+            .hit_points
+            .insert(entity, HitPoints::new_full(4));
+        self.components.armour.insert(entity, Armour::new(2));
+        self.components.damage.insert(entity, 1);
+        self.components.ranged_attack.insert(
+            entity,
+            RangedAttack {
+                projectile_damage: ProjectileDamage {
+                    hit_points: 2,
+                    push_back: false,
+                    pen: 2,
+                    hull_pen_percent: 20,
+                    life_steal: false,
+                    oxidise: false,
+                    weapon_name: None,
+                    enemy: Some(Enemy::Drone),
+                    status_effect: None,
+                },
+                light_colour: Some(Rgb24::new(255, 0, 0)),
+                reload: 2,
+                turns_until_ready: 0,
+            },
+        );
+        self.components.enemy.insert(entity, Enemy::Drone);
+        self.components.mass.insert(entity, Mass::Light);
+        entity
+    }
+
+    pub fn spawn_guardian(&mut self, coord: Coord, facing: CardinalDirection) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Character),
+                },
+            )
+            .unwrap();
+        self.components
+            .tile
+            .insert(entity, Tile::Guardian(facing.direction()));
+        self.components.npc.insert(
+            entity,
+            Npc {
+                disposition: Disposition::Hostile,
+            },
+        );
+        self.components.character.insert(entity, ());
+        self.components
+            .hit_points
+            .insert(entity, HitPoints::new_full(GUARDIAN_HIT_POINTS));
+        self.components
+            .armour
+            .insert(entity, Armour::directional(GUARDIAN_ARMOUR, 0));
+        self.components.damage.insert(entity, 3);
+        self.components.push_back.insert(entity, ());
+        self.components
+            .status_effects
+            .insert(entity, StatusEffects::permanent(StatusEffect::Slowed));
+        self.components.enemy.insert(entity, Enemy::Guardian);
+        self.components.facing.insert(entity, facing.direction());
+        self.components.mass.insert(entity, Mass::Heavy);
+        self.components.boss.insert(
+            entity,
+            Boss {
+                phase: BossPhase::Armoured,
+                turns_until_ability: 0,
+            },
+        );
+        self.components.light.insert(
+            entity,
+            Light {
+                colour: Rgb24::new(0, 255, 0),
+                vision_distance: Circle::new_squared(20),
+                diminish_numerator: 1,
+                diminish_denominator: 2,
+            },
+        );
         entity
     }
 
@@ -613,6 +864,34 @@ impl World {
         entity
     }
 
+    /// A stranded crew member who follows the player once they've seen them. They have no armour,
+    /// so stray shots from the player will hurt them too.
+    pub fn spawn_survivor(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Character),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Survivor);
+        self.components.npc.insert(
+            entity,
+            Npc {
+                disposition: Disposition::Friendly,
+            },
+        );
+        self.components.character.insert(entity, ());
+        self.components
+            .hit_points
+            .insert(entity, HitPoints::new_full(6));
+        self.components.armour.insert(entity, Armour::new(0));
+        entity
+    }
+
     pub fn spawn_ranged_weapon(&mut self, coord: Coord, ranged_weapon: RangedWeapon) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
@@ -655,7 +934,120 @@ impl World {
         entity
     }
 
-    pub fn spawn_medkit(&mut self, coord: Coord) -> Entity {
+    pub fn spawn_consumable(&mut self, coord: Coord, consumable: Consumable) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, consumable.tile());
+        self.components
+            .item
+            .insert(entity, Item::Consumable(consumable));
+        entity
+    }
+
+    pub fn spawn_ammo(&mut self, coord: Coord, ammo_type: AmmoType) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Ammo(ammo_type));
+        self.components.item.insert(
+            entity,
+            Item::Ammo {
+                ammo_type,
+                amount: ammo_type.pickup_amount(),
+            },
+        );
+        entity
+    }
+
+    /// A thrown grenade, which slides along the floor to where it lands and explodes once its
+    /// fuse runs out
+    pub fn spawn_live_grenade(&mut self, start: Coord, target: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord: start,
+                    layer: None,
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::LiveGrenade);
+        self.components.grenade.insert(entity, Grenade::new());
+        if start != target {
+            self.components.realtime.insert(entity, ());
+            self.components.blocks_gameplay.insert(entity, ());
+            self.components.collides_with.insert(
+                entity,
+                CollidesWith {
+                    solid: true,
+                    character: true,
+                },
+            );
+            self.components
+                .on_collision
+                .insert(entity, OnCollision::RemoveRealtime);
+            self.realtime_components.movement.insert(
+                entity,
+                realtime::movement::spec::Movement {
+                    path: target - start,
+                    cardinal_step_duration: Duration::from_millis(60),
+                    repeat: realtime::movement::spec::Repeat::Once,
+                }
+                .build(),
+            );
+        }
+        entity
+    }
+
+    pub fn spawn_magnetic_boots(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::MagneticBoots);
+        self.components.item.insert(entity, Item::MagneticBoots);
+        entity
+    }
+
+    pub fn spawn_keycard(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Keycard);
+        self.components.item.insert(entity, Item::Keycard);
+        entity
+    }
+
+    pub fn spawn_flashlight(&mut self, coord: Coord) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
             .update(
@@ -666,8 +1058,18 @@ impl World {
                 },
             )
             .unwrap();
-        self.components.tile.insert(entity, Tile::Medkit);
-        self.components.item.insert(entity, Item::Medkit);
+        self.components.tile.insert(entity, Tile::Flashlight);
+        self.components.item.insert(entity, Item::Flashlight);
         entity
     }
+
+    /// Mounts a light fixture on an existing wall, lighting the floor cell in front of it. The
+    /// light goes out if the station loses power or the fixture is shot.
+    pub fn spawn_wall_light(&mut self, wall: Entity, light_coord: Coord) -> Entity {
+        let light = self.spawn_light(light_coord, STATION_LIGHT_COLOUR);
+        self.components.station_light.insert(light, ());
+        self.components.tile.insert(wall, Tile::WallLight);
+        self.components.light_fixture.insert(wall, light);
+        light
+    }
 }
@@ -0,0 +1,315 @@
+use crate::{
+    world::{ExternalEvent, World},
+    Entity, Message,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusEffect {
+    /// Lose health every turn until the effect wears off or the character enters a vacuum
+    Burning,
+    /// Lose the next turn
+    Stunned,
+    /// Lose health every second turn
+    Bleeding,
+    /// Only move every second time you try to
+    Slowed,
+}
+
+impl StatusEffect {
+    /// Number of turns the effect lasts when inflicted by an attack
+    pub fn duration(self) -> u32 {
+        match self {
+            Self::Burning => 3,
+            Self::Stunned => 1,
+            Self::Bleeding => 4,
+            Self::Slowed => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ActiveStatusEffect {
+    pub effect: StatusEffect,
+    /// `None` for effects which never wear off
+    pub turns_remaining: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusEffects {
+    active: Vec<ActiveStatusEffect>,
+    skip_next_move: bool,
+}
+
+impl StatusEffects {
+    pub fn permanent(effect: StatusEffect) -> Self {
+        Self {
+            active: vec![ActiveStatusEffect {
+                effect,
+                turns_remaining: None,
+            }],
+            skip_next_move: false,
+        }
+    }
+
+    pub fn iter(&self) -> impl '_ + Iterator<Item = &ActiveStatusEffect> {
+        self.active.iter()
+    }
+
+    pub fn has(&self, effect: StatusEffect) -> bool {
+        self.active.iter().any(|active| active.effect == effect)
+    }
+
+    /// Adds an effect, or extends it if it's already active. Returns true if the effect wasn't
+    /// already active.
+    fn apply(&mut self, effect: StatusEffect, turns: u32) -> bool {
+        if let Some(active) = self
+            .active
+            .iter_mut()
+            .find(|active| active.effect == effect)
+        {
+            if let Some(turns_remaining) = active.turns_remaining.as_mut() {
+                *turns_remaining = (*turns_remaining).max(turns);
+            }
+            false
+        } else {
+            self.active.push(ActiveStatusEffect {
+                effect,
+                turns_remaining: Some(turns),
+            });
+            true
+        }
+    }
+
+    fn remove(&mut self, effect: StatusEffect) {
+        self.active.retain(|active| active.effect != effect);
+    }
+
+    /// Counts down all timed effects besides stun, which wears off when it costs the character a
+    /// turn
+    fn tick(&mut self) {
+        for active in self.active.iter_mut() {
+            if active.effect == StatusEffect::Stunned {
+                continue;
+            }
+            if let Some(turns_remaining) = active.turns_remaining.as_mut() {
+                *turns_remaining = turns_remaining.saturating_sub(1);
+            }
+        }
+        self.active
+            .retain(|active| active.turns_remaining.map(|t| t > 0).unwrap_or(true));
+    }
+
+    fn consume_stun(&mut self) -> bool {
+        if self.has(StatusEffect::Stunned) {
+            self.remove(StatusEffect::Stunned);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_move(&mut self) -> bool {
+        if self.has(StatusEffect::Slowed) {
+            let skip = self.skip_next_move;
+            self.skip_next_move = !skip;
+            skip
+        } else {
+            self.skip_next_move = false;
+            false
+        }
+    }
+}
+
+impl World {
+    pub fn apply_status_effect(
+        &mut self,
+        entity: Entity,
+        effect: StatusEffect,
+        message_log: &mut Vec<Message>,
+    ) {
+        if !self.components.character.contains(entity) {
+            return;
+        }
+        if effect == StatusEffect::Burning {
+            if let Some(coord) = self.spatial_table.coord_of(entity) {
+                if !self.air.has_air(coord) {
+                    return;
+                }
+            }
+        }
+        if !self.components.status_effects.contains(entity) {
+            self.components
+                .status_effects
+                .insert(entity, Default::default());
+        }
+        let status_effects = self.components.status_effects.get_mut(entity).unwrap();
+        if status_effects.apply(effect, effect.duration()) {
+            if self.components.player.contains(entity) {
+                message_log.push(Message::PlayerStatusEffect(effect));
+            } else if let Some(&enemy) = self.components.enemy.get(entity) {
+                message_log.push(Message::EnemyStatusEffect(enemy, effect));
+            }
+        }
+    }
+
+    /// Returns true if the character was stunned, in which case it loses its turn and the stun
+    /// wears off
+    pub fn consume_stun(&mut self, entity: Entity) -> bool {
+        self.components
+            .status_effects
+            .get_mut(entity)
+            .map(|status_effects| status_effects.consume_stun())
+            .unwrap_or(false)
+    }
+
+    /// Returns true if the character is slowed and must skip this move
+    pub fn skip_move_due_to_status_effect(&mut self, entity: Entity) -> bool {
+        self.components
+            .status_effects
+            .get_mut(entity)
+            .map(|status_effects| status_effects.skip_move())
+            .unwrap_or(false)
+    }
+
+    pub fn process_status_effects<R: Rng>(
+        &mut self,
+        rng: &mut R,
+        external_events: &mut Vec<ExternalEvent>,
+        message_log: &mut Vec<Message>,
+    ) {
+        for entity in self
+            .components
+            .status_effects
+            .entities()
+            .collect::<Vec<_>>()
+        {
+            let in_vacuum = self
+                .spatial_table
+                .coord_of(entity)
+                .map(|coord| !self.air.has_air(coord))
+                .unwrap_or(false);
+            let status_effects = self.components.status_effects.get_mut(entity).unwrap();
+            if in_vacuum {
+                status_effects.remove(StatusEffect::Burning);
+            }
+            let mut damage = 0;
+            for active in status_effects.iter() {
+                match active.effect {
+                    StatusEffect::Burning => damage += 1,
+                    StatusEffect::Bleeding => {
+                        if active.turns_remaining.unwrap_or(0) % 2 == 0 {
+                            damage += 1;
+                        }
+                    }
+                    StatusEffect::Stunned | StatusEffect::Slowed => (),
+                }
+            }
+            status_effects.tick();
+            if damage > 0 && self.components.hit_points.contains(entity) {
+                self.damage_character(entity, damage, rng, external_events, message_log);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gridbugs::coord_2d::{Coord, Size};
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    fn world_with_zombie() -> (World, Entity) {
+        let mut world = World::new(Size::new(3, 3), 0);
+        for coord in Size::new(3, 3).coord_iter_row_major() {
+            world.spawn_floor(coord);
+        }
+        let zombie = world.spawn_zombie(Coord::new(1, 1));
+        (world, zombie)
+    }
+
+    fn hit_points(world: &World, entity: Entity) -> u32 {
+        world.components.hit_points.get(entity).unwrap().current
+    }
+
+    fn process(world: &mut World) {
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        world.process_status_effects(&mut rng, &mut Vec::new(), &mut Vec::new());
+    }
+
+    #[test]
+    fn applying_an_effect_reports_it_once() {
+        let (mut world, zombie) = world_with_zombie();
+        let mut message_log = Vec::new();
+        world.apply_status_effect(zombie, StatusEffect::Burning, &mut message_log);
+        world.apply_status_effect(zombie, StatusEffect::Burning, &mut message_log);
+        assert_eq!(message_log.len(), 1);
+        let status_effects = world.components.status_effects.get(zombie).unwrap();
+        assert!(status_effects.has(StatusEffect::Burning));
+        assert_eq!(status_effects.iter().count(), 1);
+    }
+
+    #[test]
+    fn burning_deals_damage_every_turn_until_it_wears_off() {
+        let (mut world, zombie) = world_with_zombie();
+        world.apply_status_effect(zombie, StatusEffect::Burning, &mut Vec::new());
+        for turn in 1..=StatusEffect::Burning.duration() {
+            process(&mut world);
+            assert_eq!(hit_points(&world, zombie), 4 - turn);
+        }
+        process(&mut world);
+        assert_eq!(
+            hit_points(&world, zombie),
+            4 - StatusEffect::Burning.duration()
+        );
+        assert!(!world
+            .components
+            .status_effects
+            .get(zombie)
+            .unwrap()
+            .has(StatusEffect::Burning));
+    }
+
+    #[test]
+    fn bleeding_deals_damage_every_second_turn() {
+        let (mut world, zombie) = world_with_zombie();
+        world.apply_status_effect(zombie, StatusEffect::Bleeding, &mut Vec::new());
+        let mut damage_per_turn = Vec::new();
+        for _ in 0..StatusEffect::Bleeding.duration() + 1 {
+            let before = hit_points(&world, zombie);
+            process(&mut world);
+            damage_per_turn.push(before - hit_points(&world, zombie));
+        }
+        assert_eq!(damage_per_turn, vec![1, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn nothing_burns_in_a_vacuum() {
+        let mut world = World::new(Size::new(1, 1), 0);
+        let zombie = world.spawn_zombie(Coord::new(0, 0));
+        world.air.init(&world.spatial_table, &world.components);
+        world.apply_status_effect(zombie, StatusEffect::Burning, &mut Vec::new());
+        assert!(!world.components.status_effects.contains(zombie));
+    }
+
+    #[test]
+    fn stun_costs_exactly_one_turn() {
+        let (mut world, zombie) = world_with_zombie();
+        world.apply_status_effect(zombie, StatusEffect::Stunned, &mut Vec::new());
+        process(&mut world);
+        assert!(world.consume_stun(zombie));
+        assert!(!world.consume_stun(zombie));
+    }
+
+    #[test]
+    fn slowed_characters_skip_every_second_move() {
+        let (mut world, zombie) = world_with_zombie();
+        world.apply_status_effect(zombie, StatusEffect::Slowed, &mut Vec::new());
+        let skips = (0..4)
+            .map(|_| world.skip_move_due_to_status_effect(zombie))
+            .collect::<Vec<_>>();
+        assert_eq!(skips, vec![false, true, false, true]);
+    }
+}
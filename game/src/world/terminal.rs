@@ -0,0 +1,152 @@
+use crate::{
+    world::{
+        action::Error,
+        data::{DoorState, Tile},
+        ExternalEvent, World,
+    },
+    Entity, Message,
+};
+use gridbugs::{coord_2d::Coord, direction::CardinalDirections};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// Damage dealt by explosive decompression to each hostile character in a vented room. Anyone
+/// who survives still has to contend with the vacuum pulling them towards the breach.
+const VENT_DAMAGE: u32 = 4;
+
+/// A command run from a security terminal. Each terminal goes offline after running a single
+/// command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerminalCommand {
+    UnlockDoors,
+    /// Blows out the windows of the room containing the coord, hurting the hostiles inside
+    VentRoom(Coord),
+    LightsOff,
+    RevealEnemies,
+}
+
+impl World {
+    /// The floor cells of the room containing the coord. Rooms are bounded by walls, windows and
+    /// doors, so an empty list is returned if the coord isn't on open floor.
+    pub fn room_cells(&self, coord: Coord) -> Vec<Coord> {
+        let is_room_cell = |coord| {
+            self.spatial_table
+                .layers_at(coord)
+                .map(|layers| {
+                    layers.floor.is_some()
+                        && layers.feature.is_none_or(|feature| {
+                            !self.components.solid.contains(feature)
+                                && !self.components.door_state.contains(feature)
+                        })
+                })
+                .unwrap_or(false)
+        };
+        let mut cells = Vec::new();
+        if !is_room_cell(coord) {
+            return cells;
+        }
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(coord);
+        queue.push_back(coord);
+        while let Some(coord) = queue.pop_front() {
+            cells.push(coord);
+            for direction in CardinalDirections {
+                let nei_coord = coord + direction.coord();
+                if is_room_cell(nei_coord) && seen.insert(nei_coord) {
+                    queue.push_back(nei_coord);
+                }
+            }
+        }
+        cells
+    }
+
+    fn room_windows(&self, room_cells: &[Coord]) -> Vec<Entity> {
+        let mut windows = Vec::new();
+        for &coord in room_cells {
+            for direction in CardinalDirections {
+                if let Some(feature) = self
+                    .spatial_table
+                    .layers_at(coord + direction.coord())
+                    .and_then(|layers| layers.feature)
+                {
+                    if self.is_window(feature) && !windows.contains(&feature) {
+                        windows.push(feature);
+                    }
+                }
+            }
+        }
+        windows
+    }
+
+    pub fn character_use_terminal<R: Rng>(
+        &mut self,
+        character: Entity,
+        terminal: Entity,
+        command: TerminalCommand,
+        rng: &mut R,
+        external_events: &mut Vec<ExternalEvent>,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        if self.components.terminal.get(terminal) != Some(&true) {
+            return Err(Error::TerminalOffline);
+        }
+        match command {
+            TerminalCommand::UnlockDoors => {
+                let locked_doors = self
+                    .components
+                    .door_state
+                    .iter()
+                    .filter(|(_, &door_state)| door_state == DoorState::Locked)
+                    .map(|(entity, _)| entity)
+                    .collect::<Vec<_>>();
+                if locked_doors.is_empty() {
+                    return Err(Error::NothingToUnlock);
+                }
+                for door in locked_doors {
+                    self.unlock_door(door);
+                }
+                message_log.push(Message::TerminalUnlocksDoors);
+            }
+            TerminalCommand::VentRoom(coord) => {
+                let room_cells = self.room_cells(coord);
+                let windows = self.room_windows(&room_cells);
+                if windows.is_empty() {
+                    return Err(Error::NoWindowsToVent);
+                }
+                for window in windows {
+                    self.damage_structure(window, u32::MAX, rng, external_events);
+                }
+                for coord in room_cells {
+                    if let Some(victim) = self.get_character_at_coord(coord) {
+                        if victim != character
+                            && !self.is_friendly_npc(victim)
+                            && !self.components.boss.contains(victim)
+                        {
+                            self.damage_character(
+                                victim,
+                                VENT_DAMAGE,
+                                rng,
+                                external_events,
+                                message_log,
+                            );
+                        }
+                    }
+                }
+                message_log.push(Message::TerminalVentsRoom);
+            }
+            TerminalCommand::LightsOff => {
+                self.lights_on = false;
+                message_log.push(Message::TerminalLightsOff);
+            }
+            TerminalCommand::RevealEnemies => {
+                self.enemies_revealed = true;
+                message_log.push(Message::TerminalRevealsEnemies);
+            }
+        }
+        self.components.terminal.insert(terminal, false);
+        self.components.tile.insert(terminal, Tile::TerminalOffline);
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    world::{action::Error, AmmoType, Consumable, RangedWeapon, World},
+    Entity, Message,
+};
+use serde::{Deserialize, Serialize};
+
+/// Something a vendor terminal can sell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ware {
+    RangedWeapon(RangedWeapon),
+    Consumable(Consumable),
+    Ammo(AmmoType),
+}
+
+impl Ware {
+    pub fn price(self) -> u32 {
+        match self {
+            Self::RangedWeapon(_) => 6,
+            Self::Consumable(Consumable::Medkit) => 3,
+            Self::Consumable(Consumable::Grenade) => 3,
+            Self::Consumable(Consumable::OxygenCanister) => 2,
+            Self::Consumable(Consumable::RepairKit) => 2,
+            Self::Ammo(_) => 2,
+        }
+    }
+}
+
+/// The wares a vendor terminal has left to sell. Each terminal gets its own stock when the floor
+/// is generated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Vendor {
+    pub stock: Vec<Ware>,
+}
+
+impl World {
+    pub fn vendor_stock(&self, vendor: Entity) -> &[Ware] {
+        self.components
+            .vendor
+            .get(vendor)
+            .map(|vendor| vendor.stock.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Buys a ware from a vendor's stock. Consumables go straight into the inventory and ammo
+    /// into the reserve, while weapons are left at the buyer's feet to be picked up. Ammo can
+    /// only be bought if the whole pack fits in the reserve.
+    pub fn character_buy(
+        &mut self,
+        character: Entity,
+        vendor: Entity,
+        index: usize,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        let ware = self
+            .vendor_stock(vendor)
+            .get(index)
+            .cloned()
+            .ok_or(Error::SoldOut)?;
+        let coord = self
+            .spatial_table
+            .coord_of(character)
+            .ok_or(Error::SoldOut)?;
+        let credit = self
+            .components
+            .player
+            .get(character)
+            .map(|player| player.credit)
+            .ok_or(Error::SoldOut)?;
+        if credit < ware.price() {
+            return Err(Error::CannotAffordWare);
+        }
+        match ware {
+            Ware::RangedWeapon(ranged_weapon) => {
+                if let Some(layers) = self.spatial_table.layers_at(coord) {
+                    if layers.item.is_some() {
+                        return Err(Error::NoSpaceToDispense);
+                    }
+                }
+                self.spawn_ranged_weapon(coord, ranged_weapon);
+            }
+            Ware::Consumable(consumable) => {
+                let player = self.components.player.get_mut(character).unwrap();
+                if !player.inventory.insert(consumable) {
+                    return Err(Error::InventoryFull);
+                }
+            }
+            Ware::Ammo(ammo_type) => {
+                let player = self.components.player.get_mut(character).unwrap();
+                let space = player.ammo_reserve.space(ammo_type);
+                if space == 0 {
+                    return Err(Error::AmmoReserveFull(ammo_type));
+                }
+                // ammo is sold by the pack, so don't charge for a pack that won't fit
+                if space < ammo_type.pickup_amount() {
+                    return Err(Error::NoRoomForAmmo(ammo_type));
+                }
+                player
+                    .ammo_reserve
+                    .add(ammo_type, ammo_type.pickup_amount());
+            }
+        }
+        if let Some(player) = self.components.player.get_mut(character) {
+            player.credit -= ware.price();
+        }
+        if let Some(vendor) = self.components.vendor.get_mut(vendor) {
+            vendor.stock.remove(index);
+        }
+        message_log.push(Message::Buy(ware));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{terrain, world::spawn};
+    use gridbugs::coord_2d::Coord;
+
+    const MAP: &str = "
+##.#
+#@.#
+####
+";
+
+    fn world_with_vendor(stock: Vec<Ware>, credit: u32) -> (World, Entity, Entity) {
+        let terrain = terrain::from_str(MAP, spawn::make_player());
+        let mut world = terrain.world;
+        let vendor = world.spawn_vendor(Coord::new(2, 0), stock);
+        world
+            .components
+            .player
+            .get_mut(terrain.player)
+            .unwrap()
+            .credit = credit;
+        (world, terrain.player, vendor)
+    }
+
+    fn buy(world: &mut World, player: Entity, vendor: Entity, index: usize) -> Result<(), Error> {
+        world.character_buy(player, vendor, index, &mut Vec::new())
+    }
+
+    fn credit(world: &World, player: Entity) -> u32 {
+        world.components.player.get(player).unwrap().credit
+    }
+
+    #[test]
+    fn buying_takes_credit_and_removes_the_ware_from_stock() {
+        let medkit = Ware::Consumable(Consumable::Medkit);
+        let grenade = Ware::Consumable(Consumable::Grenade);
+        let (mut world, player, vendor) = world_with_vendor(vec![medkit, grenade], 10);
+        assert!(buy(&mut world, player, vendor, 0).is_ok());
+        assert_eq!(credit(&world, player), 10 - medkit.price());
+        assert_eq!(world.vendor_stock(vendor), &[grenade]);
+        let inventory = &world.components.player.get(player).unwrap().inventory;
+        assert_eq!(inventory.count(Consumable::Medkit), 1);
+        assert!(matches!(
+            buy(&mut world, player, vendor, 1),
+            Err(Error::SoldOut)
+        ));
+    }
+
+    #[test]
+    fn wares_the_player_cannot_afford_stay_in_stock() {
+        let ware = Ware::RangedWeapon(RangedWeapon::Rifle);
+        let (mut world, player, vendor) = world_with_vendor(vec![ware], ware.price() - 1);
+        assert!(matches!(
+            buy(&mut world, player, vendor, 0),
+            Err(Error::CannotAffordWare)
+        ));
+        assert_eq!(credit(&world, player), ware.price() - 1);
+        assert_eq!(world.vendor_stock(vendor), &[ware]);
+    }
+
+    #[test]
+    fn weapons_are_dispensed_at_the_buyer_s_feet() {
+        let ware = Ware::RangedWeapon(RangedWeapon::Rifle);
+        let (mut world, player, vendor) = world_with_vendor(vec![ware, ware], 20);
+        assert!(buy(&mut world, player, vendor, 0).is_ok());
+        let coord = world.entity_coord(player).unwrap();
+        assert!(world.spatial_table.layers_at_checked(coord).item.is_some());
+        assert!(matches!(
+            buy(&mut world, player, vendor, 0),
+            Err(Error::NoSpaceToDispense)
+        ));
+        assert_eq!(credit(&world, player), 20 - ware.price());
+    }
+
+    #[test]
+    fn ammo_is_only_sold_when_the_whole_pack_fits() {
+        let ammo_type = AmmoType::Slugs;
+        let ware = Ware::Ammo(ammo_type);
+        let (mut world, player, vendor) = world_with_vendor(vec![ware; 4], 20);
+        assert!(buy(&mut world, player, vendor, 0).is_ok());
+        assert!(buy(&mut world, player, vendor, 0).is_ok());
+        let reserve = |world: &World| {
+            world
+                .components
+                .player
+                .get(player)
+                .unwrap()
+                .ammo_reserve
+                .get(ammo_type)
+        };
+        assert_eq!(reserve(&world), 2 * ammo_type.pickup_amount());
+        world
+            .components
+            .player
+            .get_mut(player)
+            .unwrap()
+            .ammo_reserve
+            .add(ammo_type, 1);
+        assert!(matches!(
+            buy(&mut world, player, vendor, 0),
+            Err(Error::NoRoomForAmmo(AmmoType::Slugs))
+        ));
+        world
+            .components
+            .player
+            .get_mut(player)
+            .unwrap()
+            .ammo_reserve
+            .add(ammo_type, ammo_type.max_reserve());
+        assert!(matches!(
+            buy(&mut world, player, vendor, 0),
+            Err(Error::AmmoReserveFull(AmmoType::Slugs))
+        ));
+        assert_eq!(credit(&world, player), 20 - 2 * ware.price());
+        assert_eq!(world.vendor_stock(vendor).len(), 2);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{
+    world::{
+        action::Error,
+        player::{HeldWeapon, WeaponMod},
+        World,
+    },
+    Entity, Message,
+};
+
+impl World {
+    /// Spends the player's credit to fit a mod to one of their weapons
+    pub fn apply_weapon_mod(
+        &mut self,
+        entity: Entity,
+        held_weapon: HeldWeapon,
+        weapon_mod: WeaponMod,
+        message_log: &mut Vec<Message>,
+    ) -> Result<(), Error> {
+        let player = self
+            .components
+            .player
+            .get_mut(entity)
+            .ok_or(Error::CannotFitWeaponMod)?;
+        if player.credit < weapon_mod.cost() {
+            return Err(Error::CannotAffordWeaponMod);
+        }
+        let weapon = player
+            .held_weapon_mut(held_weapon)
+            .ok_or(Error::CannotFitWeaponMod)?;
+        if !weapon.can_fit(weapon_mod) {
+            return Err(Error::CannotFitWeaponMod);
+        }
+        weapon.apply_mod(weapon_mod);
+        message_log.push(Message::ModWeapon(weapon.name, weapon_mod));
+        player.credit -= weapon_mod.cost();
+        Ok(())
+    }
+}
@@ -43,6 +43,8 @@ fn main() {
                 GameCell::Wall => '#',
                 GameCell::Space => ' ',
                 GameCell::Door(_) => '+',
+                GameCell::LockedDoor(_) => '=',
+                GameCell::VaultFloor => ',',
                 GameCell::Window(_) => '%',
                 GameCell::Stairs => '>',
                 GameCell::Spawn => '@',
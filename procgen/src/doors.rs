@@ -3,7 +3,7 @@ use direction::CardinalDirections;
 use grid_2d::{coord_2d::Axis, Coord, Grid};
 use rand::seq::SliceRandom;
 use rand::Rng;
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 type RoomId = usize;
 
@@ -191,10 +191,15 @@ pub enum DoorCell {
     Floor,
     Space,
     Door(Axis),
+    LockedDoor(Axis),
+    VaultFloor,
     Stairs,
     Spawn,
 }
 
+/// Rooms with this many floor cells or fewer can become vaults
+const MAX_VAULT_SIZE: usize = 24;
+
 fn choose_stairs_coord<R: Rng>(grid: &Grid<DoorCell>, rng: &mut R) -> Option<Coord> {
     let mut candidates = grid
         .enumerate()
@@ -235,6 +240,40 @@ fn choose_stairs_coord_small(grid: &Grid<DoorCell>) -> Option<Coord> {
     candidates.pop()
 }
 
+/// Chooses a small room with a single door, other than the room containing the stairs, to be
+/// sealed behind a locked door. Returns the id of the room and the coord of its door.
+fn choose_vault<R: Rng>(
+    floor: &Grid<Option<FloorCell>>,
+    door_coords: &HashMap<DoorCandidateId, Coord>,
+    door_candidates: &[DoorCandidate],
+    stairs_room: RoomId,
+    rng: &mut R,
+) -> Option<(RoomId, Coord)> {
+    let mut room_sizes = HashMap::new();
+    for floor_cell in floor.iter().flatten() {
+        *room_sizes.entry(floor_cell.room_id).or_insert(0) += 1;
+    }
+    let mut room_doors: HashMap<RoomId, Vec<Coord>> = HashMap::new();
+    for (&door_candidate_id, &coord) in door_coords.iter() {
+        let door_candidate = &door_candidates[door_candidate_id];
+        for room_id in [door_candidate.left_room_id, door_candidate.right_room_id] {
+            room_doors.entry(room_id).or_default().push(coord);
+        }
+    }
+    let mut candidates = room_doors
+        .into_iter()
+        .filter(|(room_id, doors)| {
+            *room_id != stairs_room
+                && doors.len() == 1
+                && room_sizes.get(room_id).cloned().unwrap_or(0) <= MAX_VAULT_SIZE
+        })
+        .map(|(room_id, doors)| (room_id, doors[0]))
+        .collect::<Vec<_>>();
+    // hash map iteration order is arbitrary so sort to keep generation deterministic
+    candidates.sort_by_key(|&(room_id, _)| room_id);
+    candidates.choose(rng).cloned()
+}
+
 fn choose_spawn_coord<R: Rng>(
     grid: &Grid<DoorCell>,
     floor: &Grid<Option<FloorCell>>,
@@ -298,24 +337,43 @@ pub fn add_doors<R: Rng>(
     } else {
         choose_stairs_coord(&door_grid, rng)?
     };
+    let mut door_coords = HashMap::new();
     for door_candidate_id in mst.into_iter().chain(
         extra_door_candidates
             .into_iter()
             .take(num_extra_door_candidates),
     ) {
         let door_candidate = &room_graph.door_candidates[door_candidate_id];
-        if small {
+        let door_coord = if small {
             if door_candidate.length < 5 {
                 return None;
             }
-            *door_grid.get_checked_mut(door_candidate.door_coord_small(rng)) =
-                DoorCell::Door(door_candidate.axis);
+            door_candidate.door_coord_small(rng)
         } else {
-            *door_grid.get_checked_mut(door_candidate.door_coord(rng)) =
-                DoorCell::Door(door_candidate.axis);
-        }
+            door_candidate.door_coord(rng)
+        };
+        *door_grid.get_checked_mut(door_coord) = DoorCell::Door(door_candidate.axis);
+        door_coords.insert(door_candidate_id, door_coord);
     }
     *door_grid.get_checked_mut(stairs_coord) = DoorCell::Stairs;
+    if !small {
+        if let Some((vault_room, vault_door_coord)) = choose_vault(
+            &floor,
+            &door_coords,
+            &room_graph.door_candidates,
+            floor.get_checked(stairs_coord).unwrap().room_id,
+            rng,
+        ) {
+            if let DoorCell::Door(axis) = *door_grid.get_checked(vault_door_coord) {
+                *door_grid.get_checked_mut(vault_door_coord) = DoorCell::LockedDoor(axis);
+            }
+            for (coord, floor_cell) in floor.enumerate() {
+                if floor_cell.map(|floor_cell| floor_cell.room_id) == Some(vault_room) {
+                    *door_grid.get_checked_mut(coord) = DoorCell::VaultFloor;
+                }
+            }
+        }
+    }
     let spawn_coord = choose_spawn_coord(
         &door_grid,
         &floor,
@@ -326,3 +384,95 @@ pub fn add_doors<R: Rng>(
     *door_grid.get_checked_mut(spawn_coord) = DoorCell::Spawn;
     Some(door_grid)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use grid_2d::Size;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    /// A single row of cells divided into a large room 0 and small rooms 1, 2 and 3
+    fn floor() -> Grid<Option<FloorCell>> {
+        Grid::new_fn(Size::new(40, 1), |coord| {
+            let room_id = match coord.x {
+                0..=29 => 0,
+                30..=33 => 1,
+                34..=36 => 2,
+                _ => 3,
+            };
+            Some(FloorCell { room_id })
+        })
+    }
+
+    fn door_candidate(left_room_id: RoomId, right_room_id: RoomId) -> DoorCandidate {
+        DoorCandidate {
+            axis: Axis::Y,
+            top_left: Coord::new(0, 0),
+            length: 1,
+            left_room_id,
+            right_room_id,
+        }
+    }
+
+    /// Rooms 1 and 2 open onto room 0, and room 3 opens onto room 2
+    fn doors() -> (Vec<DoorCandidate>, HashMap<DoorCandidateId, Coord>) {
+        let door_candidates = vec![
+            door_candidate(0, 1),
+            door_candidate(0, 2),
+            door_candidate(2, 3),
+        ];
+        let door_coords = (0..door_candidates.len())
+            .map(|id| (id, Coord::new(id as i32, 0)))
+            .collect();
+        (door_candidates, door_coords)
+    }
+
+    #[test]
+    fn vault_is_a_small_room_with_one_door() {
+        let floor = floor();
+        let (door_candidates, door_coords) = doors();
+        for seed in 0..20 {
+            let mut rng = Isaac64Rng::seed_from_u64(seed);
+            let vault = choose_vault(&floor, &door_coords, &door_candidates, 3, &mut rng);
+            // room 0 is too big, room 2 has two doors and room 3 has the stairs
+            assert_eq!(vault, Some((1, Coord::new(0, 0))));
+        }
+    }
+
+    #[test]
+    fn stairs_room_is_never_the_vault() {
+        let floor = floor();
+        let (door_candidates, door_coords) = doors();
+        for seed in 0..20 {
+            let mut rng = Isaac64Rng::seed_from_u64(seed);
+            let vault = choose_vault(&floor, &door_coords, &door_candidates, 1, &mut rng);
+            assert_eq!(vault, Some((3, Coord::new(2, 0))));
+        }
+    }
+
+    #[test]
+    fn no_vault_without_a_suitable_room() {
+        let floor = floor();
+        let door_candidates = vec![door_candidate(0, 1)];
+        let door_coords = [(0, Coord::new(0, 0))].into_iter().collect();
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        let vault = choose_vault(&floor, &door_coords, &door_candidates, 1, &mut rng);
+        assert_eq!(vault, None);
+    }
+
+    #[test]
+    fn vault_choice_is_deterministic() {
+        let floor = floor();
+        let (door_candidates, door_coords) = doors();
+        for seed in 0..20 {
+            let choose = || {
+                let mut rng = Isaac64Rng::seed_from_u64(seed);
+                choose_vault(&floor, &door_coords, &door_candidates, 0, &mut rng)
+            };
+            let vault = choose();
+            assert!(matches!(vault, Some((1, _)) | Some((3, _))));
+            assert_eq!(vault, choose());
+        }
+    }
+}
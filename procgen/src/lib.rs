@@ -12,6 +12,10 @@ pub enum GameCell {
     Floor,
     Space,
     Door(Axis),
+    /// The only way into a vault room
+    LockedDoor(Axis),
+    /// Floor inside a vault room
+    VaultFloor,
     Window(Axis),
     Stairs,
     Spawn,
@@ -40,6 +44,8 @@ pub fn generate<R: Rng>(spec: Spec, rng: &mut R) -> Grid<GameCell> {
                     WindowCell::Floor => GameCell::Floor,
                     WindowCell::Space => GameCell::Space,
                     WindowCell::Door(axis) => GameCell::Door(*axis),
+                    WindowCell::LockedDoor(axis) => GameCell::LockedDoor(*axis),
+                    WindowCell::VaultFloor => GameCell::VaultFloor,
                     WindowCell::Window(axis) => GameCell::Window(*axis),
                     WindowCell::Stairs => GameCell::Stairs,
                     WindowCell::Spawn => GameCell::Spawn,
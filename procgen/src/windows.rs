@@ -68,6 +68,8 @@ pub enum WindowCell {
     Floor,
     Space,
     Door(Axis),
+    LockedDoor(Axis),
+    VaultFloor,
     Window(Axis),
     Stairs,
     Spawn,
@@ -79,6 +81,8 @@ pub fn add_windows<R: Rng>(grid: &Grid<DoorCell>, rng: &mut R) -> Grid<WindowCel
         DoorCell::Wall => WindowCell::Wall,
         DoorCell::Space => WindowCell::Space,
         DoorCell::Door(axis) => WindowCell::Door(*axis),
+        DoorCell::LockedDoor(axis) => WindowCell::LockedDoor(*axis),
+        DoorCell::VaultFloor => WindowCell::VaultFloor,
         DoorCell::Stairs => WindowCell::Stairs,
         DoorCell::Spawn => WindowCell::Spawn,
     });